@@ -1,3 +1,4 @@
+use openrgb2::color::{Gradient, Hsv};
 use openrgb2::{OpenRgbClient, OpenRgbResult};
 
 /// R, G, B from 0.0 to 1.0 (common in shaders)
@@ -63,5 +64,12 @@ async fn main() -> OpenRgbResult<()> {
     cmd.set_leds(color_arr)?;
     cmd.execute().await?;
 
+    // or one of the color spaces in `openrgb2::color`
+    controller.set_all_leds(Hsv::new(280.0, 1.0, 0.5)).await?;
+    let gradient = Gradient::new([Hsv::new(0.0, 1.0, 1.0), Hsv::new(240.0, 1.0, 1.0)]);
+    controller
+        .set_leds(gradient.samples(controller.num_leds()))
+        .await?;
+
     Ok(())
 }
@@ -0,0 +1,121 @@
+/// Side of the screen that LEDs are placed behind, as seen from the front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edge {
+    /// Top of the screen.
+    Top,
+    /// Right side of the screen.
+    Right,
+    /// Bottom of the screen.
+    Bottom,
+    /// Left side of the screen.
+    Left,
+}
+
+/// Which way the LEDs along an edge are numbered, as seen from the front of the screen.
+///
+/// Clockwise runs left to right along the top, top to bottom along the right,
+/// right to left along the bottom and bottom to top along the left.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StripDirection {
+    /// Clockwise around the screen.
+    #[default]
+    Clockwise,
+    /// Counter-clockwise around the screen.
+    CounterClockwise,
+}
+
+/// LEDs of a zone placed along one edge of the screen, see [`super::Ambilight`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeMapping {
+    zone_id: usize,
+    edge: Edge,
+    led_count: usize,
+    direction: StripDirection,
+    led_offset: usize,
+    corner_offset: f32,
+}
+
+impl EdgeMapping {
+    /// Places `led_count` LEDs of zone `zone_id` along `edge`, numbered clockwise
+    /// from the first LED of the zone and spread from corner to corner.
+    pub fn new(zone_id: usize, edge: Edge, led_count: usize) -> Self {
+        Self {
+            zone_id,
+            edge,
+            led_count,
+            direction: StripDirection::default(),
+            led_offset: 0,
+            corner_offset: 0.0,
+        }
+    }
+
+    /// Sets which way the LEDs are numbered, [`StripDirection::Clockwise`] by default.
+    pub fn with_direction(mut self, direction: StripDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the index in the zone of the first LED on this edge, 0 by default.
+    ///
+    /// A strip running around several edges is one zone with an edge mapping per side,
+    /// each starting where the previous one ended.
+    pub fn with_led_offset(mut self, offset: usize) -> Self {
+        self.led_offset = offset;
+        self
+    }
+
+    /// Sets how far from the corners the LEDs start and end, as a part of the edge length.
+    ///
+    /// With 0.1 the LEDs are spread over the middle 80% of the edge, for strips that do not reach
+    /// into the corners. Clamped to 0.0 to 0.5, 0.0 by default.
+    pub fn with_corner_offset(mut self, offset: f32) -> Self {
+        self.corner_offset = offset.clamp(0.0, 0.5);
+        self
+    }
+
+    /// Returns the id of the zone the LEDs are in.
+    pub fn zone_id(&self) -> usize {
+        self.zone_id
+    }
+
+    /// Returns the edge the LEDs are placed along.
+    pub fn edge(&self) -> Edge {
+        self.edge
+    }
+
+    /// Returns the number of LEDs on the edge.
+    pub fn led_count(&self) -> usize {
+        self.led_count
+    }
+
+    /// Returns which way the LEDs are numbered.
+    pub fn direction(&self) -> StripDirection {
+        self.direction
+    }
+
+    /// Returns the index in the zone of the first LED on this edge.
+    pub fn led_offset(&self) -> usize {
+        self.led_offset
+    }
+
+    /// Returns how far from the corners the LEDs start and end, as a part of the edge length.
+    pub fn corner_offset(&self) -> f32 {
+        self.corner_offset
+    }
+
+    /// Returns the part of the edge in front of LED `index`, from 0.0 at the left or top to 1.0.
+    pub(super) fn span(&self, index: usize) -> (f32, f32) {
+        // top and right run clockwise from left to right and from top to bottom
+        let clockwise = matches!(self.edge, Edge::Top | Edge::Right);
+        let index = match clockwise == (self.direction == StripDirection::Clockwise) {
+            true => index,
+            false => self.led_count - 1 - index,
+        };
+        let length = (1.0 - 2.0 * self.corner_offset) / self.led_count as f32;
+        let start = self.corner_offset + index as f32 * length;
+        (start, start + length)
+    }
+}
@@ -0,0 +1,284 @@
+//! Ambient light from the colors at the edges of the screen, for LED strips behind a monitor.
+//!
+//! The crate does not capture the screen. Frames captured elsewhere are passed to an [`Ambilight`]
+//! as an [`Image`], which averages the colors along the edges of the frame for each LED,
+//! smooths them over time and writes them to the zones of a controller.
+//! Each [`EdgeMapping`] places LEDs of a zone along an [`Edge`] of the screen.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use openrgb2::ambilight::{Ambilight, Edge, EdgeMapping};
+//! use openrgb2::image::Image;
+//! use openrgb2::{OpenRgbClient, OpenRgbResult};
+//!
+//! # fn capture() -> (usize, usize, Vec<u8>) { (1, 1, vec![0; 3]) }
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let client = OpenRgbClient::connect().await?;
+//! let strip = client.get_controller(0).await?;
+//! strip.init().await?;
+//!
+//! // one strip of 60 LEDs starting at the bottom left corner, running clockwise
+//! let mut ambilight = Ambilight::new()
+//!     .with_edge(EdgeMapping::new(0, Edge::Left, 10))
+//!     .with_edge(EdgeMapping::new(0, Edge::Top, 20).with_led_offset(10))
+//!     .with_edge(EdgeMapping::new(0, Edge::Right, 10).with_led_offset(30))
+//!     .with_edge(EdgeMapping::new(0, Edge::Bottom, 20).with_led_offset(40))
+//!     .with_smoothing(0.6);
+//!
+//! loop {
+//!     let (width, height, rgb) = capture();
+//!     ambilight.apply(&Image::from_rgb(width, height, &rgb)?, &strip).await?;
+//! }
+//! # }
+//! ```
+
+mod edge;
+
+pub use edge::*;
+
+use crate::image::Image;
+use crate::{Color, Command, Controller, OpenRgbResult};
+
+/// Maps the edges of screen frames to LEDs placed behind the screen.
+///
+/// Every LED shows the average color of the part of the frame in front of it,
+/// which reaches from the edge into the frame by [`Self::with_depth`].
+/// Colors are smoothed from frame to frame, see [`Self::with_smoothing`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ambilight {
+    edges: Vec<EdgeMapping>,
+    depth: f32,
+    smoothing: f32,
+    /// Smoothed colors of each edge, empty until the first frame.
+    colors: Vec<Vec<[f32; 3]>>,
+}
+
+impl Default for Ambilight {
+    fn default() -> Self {
+        Self {
+            edges: Vec::new(),
+            depth: 0.1,
+            smoothing: 0.0,
+            colors: Vec::new(),
+        }
+    }
+}
+
+impl Ambilight {
+    /// Creates a mapping without edges, sampling 10% into the frame without smoothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds LEDs along an edge of the screen.
+    pub fn with_edge(mut self, edge: EdgeMapping) -> Self {
+        self.edges.push(edge);
+        self.colors.clear();
+        self
+    }
+
+    /// Sets how far into the frame the colors of an edge are taken from, as a part of its width or height.
+    ///
+    /// Clamped to 0.0 to 1.0, 0.1 by default. At least one row or column of pixels is always used.
+    pub fn with_depth(mut self, depth: f32) -> Self {
+        self.depth = depth.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how much of the previous color each LED keeps when a frame is added, from 0.0 to 1.0.
+    ///
+    /// Higher values fade slower between frames and hide flicker, at 0.0 (the default)
+    /// the LEDs follow the frames exactly. The fade time depends on the frame rate.
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the edges the LEDs are placed along.
+    pub fn edges(&self) -> &[EdgeMapping] {
+        &self.edges
+    }
+
+    /// Averages the colors in front of each LED in `frame`, and blends them into the smoothed colors.
+    ///
+    /// The first frame is taken as is. Empty frames are ignored.
+    pub fn update(&mut self, frame: &Image) {
+        if frame.is_empty() {
+            return;
+        }
+        let first = self.colors.is_empty();
+        if first {
+            self.colors = self
+                .edges
+                .iter()
+                .map(|edge| vec![[0.0; 3]; edge.led_count()])
+                .collect();
+        }
+        let keep = if first { 0.0 } else { self.smoothing };
+        for (edge, colors) in self.edges.iter().zip(&mut self.colors) {
+            for (index, color) in colors.iter_mut().enumerate() {
+                let target = average(frame, region(frame, edge, index, self.depth));
+                for (channel, target) in color.iter_mut().zip(target) {
+                    *channel = *channel * keep + target * (1.0 - keep);
+                }
+            }
+        }
+    }
+
+    /// Returns the smoothed colors of the LEDs on each edge, in the order of [`Self::edges`].
+    pub fn colors(&self) -> Vec<Vec<Color>> {
+        self.colors
+            .iter()
+            .map(|colors| {
+                colors
+                    .iter()
+                    .map(|color| {
+                        let [r, g, b] = color.map(|channel| channel.round() as u8);
+                        Color::new(r, g, b)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Adds the smoothed colors to `cmd`, with one [`Command::set_zone_leds`] per zone.
+    ///
+    /// Zone LEDs before or between edges are set to black.
+    /// Nothing is added before the first frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a zone does not exist in the controller of `cmd`.
+    pub fn add_to(&self, cmd: &mut Command<'_>) -> OpenRgbResult<()> {
+        let colors = self.colors();
+        let mut zones = Vec::<(usize, Vec<Color>)>::new();
+        for (edge, colors) in self.edges.iter().zip(colors) {
+            let index = match zones.iter().position(|(id, _)| *id == edge.zone_id()) {
+                Some(index) => index,
+                None => {
+                    zones.push((edge.zone_id(), Vec::new()));
+                    zones.len() - 1
+                }
+            };
+            let leds = &mut zones[index].1;
+            let end = edge.led_offset() + colors.len();
+            if leds.len() < end {
+                leds.resize(end, Color::default());
+            }
+            leds[edge.led_offset()..end].copy_from_slice(&colors);
+        }
+        for (zone_id, colors) in zones {
+            cmd.set_zone_leds(zone_id, colors)?;
+        }
+        Ok(())
+    }
+
+    /// Adds `frame` with [`Self::update`] and sends the colors to `controller`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a zone does not exist in `controller` or the update cannot be sent.
+    pub async fn apply(&mut self, frame: &Image, controller: &Controller) -> OpenRgbResult<()> {
+        self.update(frame);
+        let mut cmd = controller.cmd();
+        self.add_to(&mut cmd)?;
+        cmd.execute().await
+    }
+}
+
+/// Returns the pixel columns and rows of `frame` in front of LED `index` of `edge`, `depth` into the frame.
+fn region(frame: &Image, edge: &EdgeMapping, index: usize, depth: f32) -> [(usize, usize); 2] {
+    let (width, height) = (frame.width(), frame.height());
+    let (start, end) = edge.span(index);
+    // from `start` to `end` as a part of `length`, at least one pixel
+    let pixels = |start: f32, end: f32, length: usize| {
+        let first = ((start * length as f32).floor() as usize).min(length - 1);
+        let last = ((end * length as f32).ceil() as usize).clamp(first + 1, length);
+        (first, last)
+    };
+    let (columns, rows) = match edge.edge() {
+        Edge::Top => (pixels(start, end, width), pixels(0.0, depth, height)),
+        Edge::Bottom => (pixels(start, end, width), pixels(1.0 - depth, 1.0, height)),
+        Edge::Left => (pixels(0.0, depth, width), pixels(start, end, height)),
+        Edge::Right => (pixels(1.0 - depth, 1.0, width), pixels(start, end, height)),
+    };
+    [columns, rows]
+}
+
+/// Returns the average color of the pixels in `columns` and `rows` of `frame`.
+fn average(frame: &Image, [columns, rows]: [(usize, usize); 2]) -> [f32; 3] {
+    let mut sum = [0_u64; 3];
+    for y in rows.0..rows.1 {
+        let row = &frame.pixels()[y * frame.width()..][columns.0..columns.1];
+        for pixel in row {
+            sum[0] += u64::from(pixel.r);
+            sum[1] += u64::from(pixel.g);
+            sum[2] += u64::from(pixel.b);
+        }
+    }
+    let count = ((columns.1 - columns.0) * (rows.1 - rows.0)) as f32;
+    sum.map(|channel| channel as f32 / count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ambilight() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Monitor", DeviceType::LEDStrip)
+                    .with_zone(MockZone::linear("Top", 2))
+                    .with_zone(MockZone::linear("Around", 5)),
+            )
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let strip = client.get_controller(0).await?;
+
+        let (red, green, blue) = (
+            Color::new(255, 0, 0),
+            Color::new(0, 255, 0),
+            Color::new(0, 0, 255),
+        );
+        // red and blue on top, green at the bottom
+        let frame = Image::new(4, 2, vec![red, red, blue, blue, green, green, green, green])?;
+        let mut ambilight = Ambilight::new()
+            .with_edge(EdgeMapping::new(0, Edge::Top, 2))
+            .with_edge(EdgeMapping::new(1, Edge::Left, 2))
+            .with_edge(
+                EdgeMapping::new(1, Edge::Top, 2)
+                    .with_direction(StripDirection::CounterClockwise)
+                    .with_led_offset(3),
+            )
+            .with_depth(0.5)
+            .with_smoothing(0.5);
+        ambilight.apply(&frame, &strip).await?;
+        client.get_controller(0).await?;
+        // the left edge runs from the bottom to the top, and LED 2 of the zone has no edge
+        assert_eq!(
+            server.colors(0),
+            Some(vec![red, blue, green, red, Color::default(), blue, red])
+        );
+
+        // half way to black
+        ambilight.update(&Image::from_fn(4, 2, |_, _| Color::default()));
+        assert_eq!(
+            ambilight.colors()[0],
+            [Color::new(128, 0, 0), Color::new(0, 0, 128)]
+        );
+
+        // the middle of the top edge is half red and half blue
+        let mut middle =
+            Ambilight::new().with_edge(EdgeMapping::new(0, Edge::Top, 1).with_corner_offset(0.25));
+        middle.update(&frame);
+        assert_eq!(middle.colors(), [[Color::new(128, 0, 128)]]);
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+
+/// Levels at or below this many decibels relative to full scale are 0.0, see [`Analysis::bands`].
+pub const FLOOR_DB: f32 = -60.0;
+
+/// Frequencies up to this are the bass that beat onsets are detected in.
+const BASS_HZ: f32 = 150.0;
+
+/// Bass energy below this is silence, which never starts a beat.
+const MIN_BEAT_ENERGY: f32 = 1e-4;
+
+/// Shortest time between two beats, which limits detection to 300 beats per minute.
+const MIN_BEAT_GAP: Duration = Duration::from_millis(200);
+
+/// Levels of the latest audio, computed by an [`Analyzer`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    /// Level of each frequency band from low to high, from 0.0 at [`FLOOR_DB`] to 1.0 at full scale.
+    pub bands: Vec<f32>,
+    /// Root mean square of the samples in the window, about 0.7 for a full scale sine.
+    pub rms: f32,
+    /// Number of beat onsets detected since the start.
+    pub beats: u64,
+    /// True if a beat started in the samples since the previous analysis.
+    pub beat: bool,
+    /// Time of the last analysed sample, counted from the first one.
+    pub time: Duration,
+}
+
+impl Analysis {
+    /// Returns the loudness, [`Self::rms`] in decibels from [`FLOOR_DB`] to full scale mapped onto 0.0 to 1.0.
+    pub fn level(&self) -> f32 {
+        level(self.rms)
+    }
+}
+
+/// Maps `amplitude` in decibels from [`FLOOR_DB`] to full scale onto 0.0 to 1.0.
+fn level(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - 20.0 * amplitude.log10() / FLOOR_DB).clamp(0.0, 1.0)
+}
+
+/// Computes spectrum bands, RMS and beat onsets from mono PCM samples.
+///
+/// Samples go in with [`Self::push`]. Every half window of new samples, the latest window
+/// is transformed with an FFT and split into bands spaced evenly in pitch, from low to high.
+/// Beats are sudden rises of the bass energy above its average over the last second.
+pub struct Analyzer {
+    sample_rate: u32,
+    fft_size: usize,
+    band_count: usize,
+    frequencies: (f32, f32),
+    sensitivity: f32,
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    /// FFT bins of each band.
+    bands: Vec<Range<usize>>,
+    /// The last `fft_size` samples, starting as silence.
+    samples: VecDeque<f32>,
+    /// Samples pushed since the last analysis.
+    pending: usize,
+    /// Samples pushed since the start.
+    position: u64,
+    /// Bass energy of the analyses in the last second.
+    energies: VecDeque<f32>,
+    beats: u64,
+    last_beat: Option<u64>,
+}
+
+impl std::fmt::Debug for Analyzer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Analyzer")
+            .field("sample_rate", &self.sample_rate)
+            .field("fft_size", &self.fft_size)
+            .field("band_count", &self.band_count)
+            .field("frequencies", &self.frequencies)
+            .field("sensitivity", &self.sensitivity)
+            .field("position", &self.position)
+            .field("beats", &self.beats)
+            .finish()
+    }
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self {
+            sample_rate: 44_100,
+            fft_size: 1024,
+            band_count: 16,
+            frequencies: (40.0, 16_000.0),
+            sensitivity: 1.5,
+            fft: FftPlanner::new().plan_fft_forward(1),
+            window: Vec::new(),
+            bands: Vec::new(),
+            samples: VecDeque::new(),
+            pending: 0,
+            position: 0,
+            energies: VecDeque::new(),
+            beats: 0,
+            last_beat: None,
+        }
+        .plan()
+    }
+}
+
+impl Analyzer {
+    /// Creates an analyzer for 44.1 kHz audio, with a window of 1024 samples
+    /// and 16 bands from 40 Hz to 16 kHz.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of samples per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_rate` is 0.
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        assert!(sample_rate > 0, "sample rate must be positive");
+        self.sample_rate = sample_rate;
+        self.plan()
+    }
+
+    /// Sets the number of samples in each FFT window, 1024 by default.
+    ///
+    /// Larger windows tell low frequencies apart better, but react slower.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fft_size` is less than 2.
+    pub fn with_fft_size(mut self, fft_size: usize) -> Self {
+        assert!(fft_size >= 2, "FFT size must be at least 2, got {fft_size}");
+        self.fft_size = fft_size;
+        self.plan()
+    }
+
+    /// Sets the number of spectrum bands, 16 by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bands` is 0.
+    pub fn with_bands(mut self, bands: usize) -> Self {
+        assert!(bands > 0, "there must be at least one band");
+        self.band_count = bands;
+        self.plan()
+    }
+
+    /// Sets the frequencies in Hz the bands range over, from 40 Hz to 16 kHz by default.
+    ///
+    /// Frequencies above half the sample rate are not in the samples, so the range is cut off there.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `0.0 < low < high`.
+    pub fn with_frequency_range(mut self, low: f32, high: f32) -> Self {
+        assert!(
+            0.0 < low && low < high,
+            "invalid frequency range {low} to {high} Hz"
+        );
+        self.frequencies = (low, high);
+        self.plan()
+    }
+
+    /// Sets how many times the average bass energy a beat must have, 1.5 by default.
+    ///
+    /// Lower values detect more beats in quiet or busy music, higher values only the hardest hits.
+    pub fn with_beat_sensitivity(mut self, ratio: f32) -> Self {
+        self.sensitivity = ratio;
+        self
+    }
+
+    /// Returns the number of samples per second.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the number of samples in each FFT window.
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Returns the number of new samples after which the next window is analysed.
+    pub fn hop_size(&self) -> usize {
+        (self.fft_size / 2).max(1)
+    }
+
+    /// Adds mono samples, from -1.0 to 1.0 at full scale.
+    ///
+    /// Returns the analysis of the latest window if at least one window was analysed,
+    /// with [`Analysis::beat`] set if any of them started a beat.
+    pub fn push(&mut self, samples: &[f32]) -> Option<Analysis> {
+        let mut latest = None;
+        let mut beat = false;
+        for &sample in samples {
+            self.samples.pop_front();
+            self.samples.push_back(sample);
+            self.position += 1;
+            self.pending += 1;
+            if self.pending >= self.hop_size() {
+                self.pending = 0;
+                let analysis = self.analyze();
+                beat |= analysis.beat;
+                latest = Some(analysis);
+            }
+        }
+        latest.map(|analysis| Analysis { beat, ..analysis })
+    }
+
+    /// Analyses the current window.
+    fn analyze(&mut self) -> Analysis {
+        let mut buffer = self
+            .samples
+            .iter()
+            .zip(&self.window)
+            .map(|(sample, weight)| Complex::new(sample * weight, 0.0))
+            .collect::<Vec<_>>();
+        self.fft.process(&mut buffer);
+        // scaled so a full scale sine has a magnitude of 1.0
+        let scale = 2.0 / self.window.iter().sum::<f32>();
+        let magnitudes = buffer[..=self.fft_size / 2]
+            .iter()
+            .map(|bin| bin.norm() * scale)
+            .collect::<Vec<_>>();
+
+        let bands = self
+            .bands
+            .iter()
+            .map(|bins| level(magnitudes[bins.clone()].iter().copied().fold(0.0, f32::max)))
+            .collect();
+        let rms = (self.samples.iter().map(|s| s * s).sum::<f32>() / self.fft_size as f32).sqrt();
+
+        let bass = 1..=self.bin(BASS_HZ).clamp(1, magnitudes.len() - 1);
+        let energy =
+            magnitudes[bass.clone()].iter().map(|m| m * m).sum::<f32>() / bass.count() as f32;
+        let average = match self.energies.len() {
+            0 => 0.0,
+            len => self.energies.iter().sum::<f32>() / len as f32,
+        };
+        let gap = (MIN_BEAT_GAP.as_secs_f64() * f64::from(self.sample_rate)) as u64;
+        let beat = energy > MIN_BEAT_ENERGY
+            && energy > average * self.sensitivity
+            && self
+                .last_beat
+                .is_none_or(|last| self.position - last >= gap);
+        if beat {
+            self.beats += 1;
+            self.last_beat = Some(self.position);
+        }
+        let history = (self.sample_rate as usize / self.hop_size()).max(1);
+        if self.energies.len() >= history {
+            self.energies.pop_front();
+        }
+        self.energies.push_back(energy);
+
+        Analysis {
+            bands,
+            rms,
+            beats: self.beats,
+            beat,
+            time: Duration::from_secs_f64(self.position as f64 / f64::from(self.sample_rate)),
+        }
+    }
+
+    /// Returns the FFT bin closest to `frequency`.
+    fn bin(&self, frequency: f32) -> usize {
+        (frequency * self.fft_size as f32 / self.sample_rate as f32).round() as usize
+    }
+
+    /// Prepares the FFT, window and bands for the current settings, starting again from silence.
+    fn plan(mut self) -> Self {
+        let size = self.fft_size;
+        self.fft = FftPlanner::new().plan_fft_forward(size);
+        // Hann window
+        self.window = (0..size)
+            .map(|i| {
+                let x = std::f32::consts::TAU * i as f32 / size as f32;
+                (1.0 - x.cos()) / 2.0
+            })
+            .collect();
+
+        let nyquist = self.sample_rate as f32 / 2.0;
+        let (low, high) = self.frequencies;
+        let high = high.min(nyquist).max(low);
+        let edge = |i: usize| low * (high / low).powf(i as f32 / self.band_count as f32);
+        let last = size / 2;
+        self.bands = (0..self.band_count)
+            .map(|i| {
+                let (start, end) = (self.bin(edge(i)), self.bin(edge(i + 1)));
+                let start = start.min(last);
+                // narrow bands between two bins take the closest one
+                start..end.clamp(start + 1, last + 1)
+            })
+            .collect();
+
+        self.samples = VecDeque::from(vec![0.0; size]);
+        self.pending = 0;
+        self.energies.clear();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, amplitude: f32, sample_rate: u32, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                amplitude * (std::f32::consts::TAU * frequency * t).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_analyze() {
+        let mut analyzer = Analyzer::new().with_bands(8);
+        assert!(analyzer.push(&[0.0; 100]).is_none());
+
+        let analysis = analyzer
+            .push(&sine(1000.0, 0.5, 44_100, 4096))
+            .expect("analysed");
+        assert_eq!(analysis.bands.len(), 8);
+        // the last window ends after 8 hops of 512 samples
+        assert_eq!(analysis.time, Duration::from_secs_f64(4096.0 / 44_100.0));
+        assert!((analysis.rms - 0.354).abs() < 0.01, "{}", analysis.rms);
+        // 1 kHz is in the fifth band from 40 Hz to 16 kHz, at -6 dB
+        let (loudest, peak) = analysis
+            .bands
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert_eq!(loudest, 4);
+        assert!((peak - 0.9).abs() < 0.02, "{peak}");
+        assert_eq!(analysis.bands[0], 0.0);
+        assert_eq!(analysis.bands[7], 0.0);
+
+        let silence = analyzer.push(&[0.0; 2048]).expect("analysed");
+        assert_eq!(silence.rms, 0.0);
+        assert_eq!(silence.level(), 0.0);
+    }
+
+    #[test]
+    fn test_beats() {
+        let mut analyzer = Analyzer::new().with_sample_rate(8000);
+        // a 100 ms bass hit every 500 ms
+        let mut beats = 0;
+        for _ in 0..4 {
+            let mut samples = sine(60.0, 0.8, 8000, 800);
+            samples.resize(4000, 0.0);
+            let analysis = analyzer.push(&samples).expect("analysed");
+            beats = analysis.beats;
+        }
+        assert_eq!(beats, 4);
+        assert!(!analyzer.push(&[0.0; 4000]).expect("analysed").beat);
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::Cursor;
+use std::path::Path;
+use std::time::Duration;
+
+use hound::{SampleFormat, WavReader};
+
+use crate::{OpenRgbError, OpenRgbResult};
+
+/// Mono PCM audio with its sample rate, such as a decoded WAV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioClip {
+    sample_rate: u32,
+    samples: Vec<f32>,
+}
+
+impl AudioClip {
+    /// Creates a clip of mono `samples`, from -1.0 to 1.0 at full scale.
+    pub fn new(sample_rate: u32, samples: Vec<f32>) -> Self {
+        Self {
+            sample_rate,
+            samples,
+        }
+    }
+
+    /// Creates a clip of interleaved samples with `channels` channels, mixed down to mono.
+    pub fn from_interleaved(sample_rate: u32, channels: usize, samples: &[f32]) -> Self {
+        Self::new(sample_rate, downmix(channels, samples).collect())
+    }
+
+    /// Decodes a WAV file with integer or float samples, mixed down to mono.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::AudioError`] if `bytes` is not a supported WAV file.
+    pub fn from_wav(bytes: &[u8]) -> OpenRgbResult<Self> {
+        let invalid =
+            |error: hound::Error| OpenRgbError::AudioError(format!("Invalid WAV: {error}"));
+        let reader = WavReader::new(Cursor::new(bytes)).map_err(invalid)?;
+        let spec = reader.spec();
+        let samples = match spec.sample_format {
+            SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<Vec<_>, _>>(),
+            SampleFormat::Int => {
+                let scale = 1.0 / (1_i64 << (spec.bits_per_sample.clamp(1, 32) - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|sample| sample.map(|sample| sample as f32 * scale))
+                    .collect()
+            }
+        }
+        .map_err(invalid)?;
+        Ok(Self::from_interleaved(
+            spec.sample_rate,
+            usize::from(spec.channels),
+            &samples,
+        ))
+    }
+
+    /// Reads a WAV file from `path`, see [`Self::from_wav`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::FileError`] if the file cannot be read,
+    /// or [`OpenRgbError::AudioError`] if it is not a supported WAV file.
+    pub fn load(path: impl AsRef<Path>) -> OpenRgbResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| OpenRgbError::FileError {
+            path: path.to_owned(),
+            source,
+        })?;
+        Self::from_wav(&bytes)
+    }
+
+    /// Returns the number of samples per second.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the mono samples.
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// Returns how long the clip plays.
+    pub fn duration(&self) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.samples.len() as f64 / f64::from(self.sample_rate))
+    }
+}
+
+/// Mixes interleaved samples of `channels` channels down to mono.
+pub(super) fn downmix(channels: usize, samples: &[f32]) -> impl Iterator<Item = f32> + '_ {
+    let channels = channels.max(1);
+    samples
+        .chunks_exact(channels)
+        .map(move |frame| frame.iter().sum::<f32>() / channels as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use hound::{WavSpec, WavWriter};
+
+    use super::*;
+
+    #[test]
+    fn test_from_wav() -> OpenRgbResult<()> {
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut wav = Cursor::new(Vec::new());
+        let mut writer = WavWriter::new(&mut wav, spec).unwrap();
+        for sample in [i16::MIN, 0, 16384, 16384, 0, -16384] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let clip = AudioClip::from_wav(wav.get_ref())?;
+        assert_eq!(clip.sample_rate(), 8000);
+        assert_eq!(clip.samples(), [-0.5, 0.5, -0.25]);
+        assert_eq!(clip.duration(), Duration::from_secs_f64(3.0 / 8000.0));
+
+        assert!(matches!(
+            AudioClip::from_wav(b"RIFF"),
+            Err(OpenRgbError::AudioError(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! Music visualizers driven by PCM audio, with the `audio` feature.
+//!
+//! An [`Analyzer`] computes spectrum bands, RMS and beat onsets from mono samples.
+//! An [`AudioPipeline`] runs it in the background on an [`AudioClip`] read from a WAV file,
+//! or on samples sent through a channel, and shares the latest [`Analysis`] through a watch channel.
+//! The visualizer effects read it while an [`crate::effects::EffectRunner`] renders them:
+//! [`SpectrumBars`] on matrix zones, [`VuMeter`] on linear zones and [`BeatFlash`] on all LEDs.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use openrgb2::audio::{Analyzer, AudioClip, AudioPipeline, SpectrumBars};
+//! use openrgb2::effects::EffectRunner;
+//! use openrgb2::{OpenRgbClient, OpenRgbResult};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let client = OpenRgbClient::connect().await?;
+//! let group = client.get_all_controllers().await?;
+//! group.init().await?;
+//!
+//! let clip = AudioClip::load("song.wav")?;
+//! let duration = clip.duration();
+//! let pipeline = AudioPipeline::play(clip, Analyzer::new().with_bands(22));
+//!
+//! let mut runner = EffectRunner::new(group).with_fps(60.0);
+//! runner.start(SpectrumBars::new(pipeline.analysis()));
+//! tokio::time::sleep(duration).await;
+//! runner.stop().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod analyzer;
+mod clip;
+mod pipeline;
+mod visualizers;
+
+pub use analyzer::*;
+pub use clip::*;
+pub use pipeline::*;
+pub use visualizers::*;
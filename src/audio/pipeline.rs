@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use super::{Analysis, Analyzer, AudioClip, clip::downmix};
+
+/// Analyses PCM audio in a background task, sharing the latest [`Analysis`] with visualizer effects.
+///
+/// Samples come from an [`AudioClip`] played in real time, or from a channel fed by any source,
+/// such as an audio capture library. When the samples run out, a window of silence is analysed,
+/// so visualizers fade out instead of freezing on the last analysis.
+///
+/// The background task is stopped when the pipeline is dropped.
+/// See [the module documentation](super) for an example.
+#[derive(Debug)]
+pub struct AudioPipeline {
+    analysis: watch::Receiver<Analysis>,
+    task: JoinHandle<()>,
+}
+
+impl AudioPipeline {
+    /// Plays `clip`, analysing its samples at the time they would be heard since this call.
+    ///
+    /// Start the playback of the same audio at the same time to keep visualizers in sync with it.
+    /// The sample rate of `analyzer` is set to the one of `clip`.
+    /// Must be called within a tokio runtime.
+    pub fn play(clip: AudioClip, analyzer: Analyzer) -> Self {
+        let mut analyzer = analyzer.with_sample_rate(clip.sample_rate().max(1));
+        let (sender, analysis) = watch::channel(Analysis::default());
+        let task = tokio::spawn(async move {
+            let samples = clip.samples();
+            let rate = f64::from(analyzer.sample_rate());
+            let period = Duration::from_secs_f64(analyzer.hop_size() as f64 / rate);
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            let start = Instant::now();
+            let mut position = 0;
+            while position < samples.len() {
+                interval.tick().await;
+                let due = (start.elapsed().as_secs_f64() * rate) as usize;
+                let due = due.clamp(position, samples.len());
+                publish(&sender, analyzer.push(&samples[position..due]));
+                position = due;
+            }
+            fade_out(&sender, &mut analyzer);
+        });
+        Self { analysis, task }
+    }
+
+    /// Analyses the samples received from `samples`, until all senders are dropped.
+    ///
+    /// Each message holds interleaved samples of `channels` channels, from -1.0 to 1.0 at full scale,
+    /// which are mixed down to mono. Set the sample rate of the source with [`Analyzer::with_sample_rate`].
+    /// Must be called within a tokio runtime.
+    pub fn from_channel(
+        mut samples: mpsc::Receiver<Vec<f32>>,
+        channels: usize,
+        mut analyzer: Analyzer,
+    ) -> Self {
+        let (sender, analysis) = watch::channel(Analysis::default());
+        let task = tokio::spawn(async move {
+            while let Some(frames) = samples.recv().await {
+                let mono = downmix(channels, &frames).collect::<Vec<_>>();
+                publish(&sender, analyzer.push(&mono));
+            }
+            fade_out(&sender, &mut analyzer);
+        });
+        Self { analysis, task }
+    }
+
+    /// Returns a receiver of the latest analysis, to pass to visualizer effects.
+    pub fn analysis(&self) -> watch::Receiver<Analysis> {
+        self.analysis.clone()
+    }
+
+    /// Returns a copy of the latest analysis.
+    pub fn latest(&self) -> Analysis {
+        self.analysis.borrow().clone()
+    }
+
+    /// Returns true once all samples have been analysed.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for AudioPipeline {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn publish(sender: &watch::Sender<Analysis>, analysis: Option<Analysis>) {
+    if let Some(analysis) = analysis {
+        sender.send_replace(analysis);
+    }
+}
+
+/// Analyses a window of silence after the last samples.
+fn fade_out(sender: &watch::Sender<Analysis>, analyzer: &mut Analyzer) {
+    // windows are analysed once per hop, so one more hop makes sure the last one is all silence
+    let silence = vec![0.0; analyzer.fft_size() + analyzer.hop_size()];
+    publish(sender, analyzer.push(&silence));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_from_channel() {
+        let (samples, receiver) = mpsc::channel(4);
+        let pipeline = AudioPipeline::from_channel(receiver, 2, Analyzer::new().with_fft_size(64));
+        let mut analysis = pipeline.analysis();
+
+        // a full scale square wave in both channels
+        let square = (0..64)
+            .flat_map(|i| [if i % 2 == 0 { 1.0 } else { -1.0 }; 2])
+            .collect::<Vec<_>>();
+        samples.send(square).await.unwrap();
+        analysis.changed().await.unwrap();
+        assert_eq!(analysis.borrow_and_update().rms, 1.0);
+
+        drop(samples);
+        analysis.changed().await.unwrap();
+        let silence = analysis.borrow_and_update().clone();
+        assert_eq!(silence.rms, 0.0);
+        assert_eq!(silence.time, Duration::from_secs_f64(160.0 / 44_100.0));
+        while !pipeline.is_finished() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_play() {
+        let clip = AudioClip::new(8000, vec![0.5; 400]);
+        let started = Instant::now();
+        let pipeline = AudioPipeline::play(clip.clone(), Analyzer::new().with_fft_size(64));
+        while !pipeline.is_finished() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(started.elapsed() >= clip.duration());
+        assert_eq!(pipeline.latest().rms, 0.0);
+        assert!(pipeline.latest().time > clip.duration());
+    }
+}
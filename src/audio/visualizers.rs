@@ -0,0 +1,305 @@
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use super::Analysis;
+use crate::Color;
+use crate::color::{Gradient, LinearRgb};
+use crate::effects::{Effect, Frame};
+
+/// Gradient of the levels of [`SpectrumBars`] and [`VuMeter`] unless set: green, yellow, then red.
+fn level_gradient() -> Gradient {
+    Gradient::new([
+        Color::new(0, 255, 0),
+        Color::new(255, 255, 0),
+        Color::new(255, 0, 0),
+    ])
+}
+
+/// Moves `level` to `target`, rising at once and falling from 1.0 to 0.0 in `decay`.
+fn fall(level: f32, target: f32, elapsed: Duration, decay: Duration) -> f32 {
+    if decay.is_zero() {
+        return target;
+    }
+    target.max(level - (elapsed.as_secs_f32() / decay.as_secs_f32()))
+}
+
+/// Shows the spectrum bands as a bar graph on every matrix zone, low frequencies on the left.
+///
+/// Each column shows the loudest band under it, lit from the bottom up
+/// and colored by height along a gradient. Zones without a matrix are not changed.
+#[derive(Debug, Clone)]
+pub struct SpectrumBars {
+    analysis: watch::Receiver<Analysis>,
+    gradient: Gradient,
+    background: Color,
+    decay: Duration,
+    levels: Vec<f32>,
+    last: Duration,
+}
+
+impl SpectrumBars {
+    /// Creates a bar graph of the bands in `analysis`, see [`super::AudioPipeline::analysis`].
+    pub fn new(analysis: watch::Receiver<Analysis>) -> Self {
+        Self {
+            analysis,
+            gradient: level_gradient(),
+            background: Color::default(),
+            decay: Duration::from_millis(500),
+            levels: Vec::new(),
+            last: Duration::ZERO,
+        }
+    }
+
+    /// Sets the colors from the bottom to the top of the bars, green to yellow to red by default.
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// Sets the color of the LEDs above the bars, black by default.
+    pub fn with_background(mut self, color: impl Into<Color>) -> Self {
+        self.background = color.into();
+        self
+    }
+
+    /// Sets how long a bar takes to fall from the top to the bottom, 500 ms by default.
+    ///
+    /// Bars rise at once. With [`Duration::ZERO`] they follow the analysis exactly.
+    pub fn with_decay(mut self, decay: Duration) -> Self {
+        self.decay = decay;
+        self
+    }
+}
+
+impl Effect for SpectrumBars {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        let bands = self.analysis.borrow().bands.clone();
+        self.levels.resize(bands.len(), 0.0);
+        let elapsed = t.saturating_sub(self.last);
+        self.last = t;
+        for (level, target) in self.levels.iter_mut().zip(bands) {
+            *level = fall(*level, target, elapsed, self.decay);
+        }
+
+        for mut zone in frame.zones_mut() {
+            let Some(matrix) = zone.zone().matrix().cloned() else {
+                continue;
+            };
+            let (width, height) = (matrix.num_columns(), matrix.num_rows());
+            let colors = zone.colors_mut();
+            for x in 0..width {
+                // the loudest of the bands under this column, or the band it is part of
+                let start = x * self.levels.len() / width;
+                let end = ((x + 1) * self.levels.len() / width).max(start + 1);
+                let level = self
+                    .levels
+                    .get(start..end.min(self.levels.len()))
+                    .unwrap_or_default()
+                    .iter()
+                    .copied()
+                    .fold(0.0, f32::max);
+                let lit = (level * height as f32).round() as usize;
+                for y in 0..height {
+                    let Some(color) = matrix
+                        .get(y, x)
+                        .and_then(|&led| colors.get_mut(led as usize))
+                    else {
+                        continue;
+                    };
+                    // rows counted from the bottom
+                    let row = height - 1 - y;
+                    *color = match row < lit {
+                        true => self
+                            .gradient
+                            .sample(row as f32 / (height - 1).max(1) as f32),
+                        false => self.background,
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Shows the loudness as a level meter along every zone without a matrix, such as LED strips.
+///
+/// The meter fills from the first LED, colored by position along a gradient.
+/// Matrix zones are not changed, see [`SpectrumBars`] for those.
+#[derive(Debug, Clone)]
+pub struct VuMeter {
+    analysis: watch::Receiver<Analysis>,
+    gradient: Gradient,
+    background: Color,
+    decay: Duration,
+    level: f32,
+    last: Duration,
+}
+
+impl VuMeter {
+    /// Creates a meter of [`Analysis::level`] in `analysis`, see [`super::AudioPipeline::analysis`].
+    pub fn new(analysis: watch::Receiver<Analysis>) -> Self {
+        Self {
+            analysis,
+            gradient: level_gradient(),
+            background: Color::default(),
+            decay: Duration::from_millis(300),
+            level: 0.0,
+            last: Duration::ZERO,
+        }
+    }
+
+    /// Sets the colors from the start to the end of the meter, green to yellow to red by default.
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// Sets the color of the LEDs past the level, black by default.
+    pub fn with_background(mut self, color: impl Into<Color>) -> Self {
+        self.background = color.into();
+        self
+    }
+
+    /// Sets how long the meter takes to fall from full to empty, 300 ms by default.
+    ///
+    /// The meter rises at once. With [`Duration::ZERO`] it follows the analysis exactly.
+    pub fn with_decay(mut self, decay: Duration) -> Self {
+        self.decay = decay;
+        self
+    }
+}
+
+impl Effect for VuMeter {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        let target = self.analysis.borrow().level();
+        self.level = fall(self.level, target, t.saturating_sub(self.last), self.decay);
+        self.last = t;
+
+        for mut zone in frame.zones_mut() {
+            if zone.zone().matrix().is_some() {
+                continue;
+            }
+            let lit = (self.level * zone.len() as f32).round() as usize;
+            for (i, (position, color)) in zone.positions_mut().enumerate() {
+                *color = match i < lit {
+                    true => self.gradient.sample(position),
+                    false => self.background,
+                };
+            }
+        }
+    }
+}
+
+/// Flashes all LEDs on every beat, fading out until the next one.
+#[derive(Debug, Clone)]
+pub struct BeatFlash {
+    analysis: watch::Receiver<Analysis>,
+    color: Color,
+    decay: Duration,
+    beats: u64,
+    flash: Option<Duration>,
+}
+
+impl BeatFlash {
+    /// Creates an effect that flashes `color` on the beats in `analysis`, see [`super::AudioPipeline::analysis`].
+    ///
+    /// Only beats after this call flash.
+    pub fn new(analysis: watch::Receiver<Analysis>, color: impl Into<Color>) -> Self {
+        let beats = analysis.borrow().beats;
+        Self {
+            analysis,
+            color: color.into(),
+            decay: Duration::from_millis(250),
+            beats,
+            flash: None,
+        }
+    }
+
+    /// Sets how long a flash takes to fade out, 250 ms by default.
+    pub fn with_decay(mut self, decay: Duration) -> Self {
+        self.decay = decay;
+        self
+    }
+}
+
+impl Effect for BeatFlash {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        let beats = self.analysis.borrow().beats;
+        if beats != self.beats {
+            self.beats = beats;
+            self.flash = Some(t);
+        }
+        let level = self.flash.map_or(0.0, |start| {
+            fall(1.0, 0.0, t.saturating_sub(start), self.decay).max(0.0)
+        });
+        // fade the light rather than the encoded color, so it looks smooth
+        frame.fill(LinearRgb::from(self.color).scale(level));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use array2d::Array2D;
+
+    use crate::mock::{MockController, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_visualizers() -> OpenRgbResult<()> {
+        // a 4x3 matrix with a hole at the top right, and a strip of 4 LEDs
+        let keys =
+            Array2D::from_row_major(&[0, 1, 2, u32::MAX, 3, 4, 5, 6, 7, 8, 9, 10], 3, 4).unwrap();
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Keyboard", DeviceType::Keyboard)
+                    .with_zone(MockZone::matrix("Keys", 1, 1).with_matrix(keys))
+                    .with_zone(MockZone::linear("Strip", 4)),
+            )
+            .start()
+            .await?;
+        let group = server.client().await?.get_all_controllers().await?;
+        let mut frame = Frame::new(&group);
+
+        let (sender, analysis) = watch::channel(Analysis {
+            bands: vec![1.0, 0.6, 0.3, 0.0],
+            rms: 0.1,
+            ..Analysis::default()
+        });
+        let (on, off) = (Color::new(255, 255, 255), Color::default());
+        let mut bars = SpectrumBars::new(analysis.clone())
+            .with_gradient(Gradient::new([on, on]))
+            .with_decay(Duration::from_secs(1));
+        bars.render(Duration::ZERO, &mut frame);
+        let colors = frame.controllers()[0].colors();
+        assert_eq!(colors[..3], [on, off, off]);
+        assert_eq!(colors[3..7], [on, on, off, off]);
+        assert_eq!(colors[7..11], [on, on, on, off]);
+        assert_eq!(colors[11..], [off; 4]);
+
+        // bars fall slowly
+        sender.send_modify(|analysis| analysis.bands = vec![0.0; 4]);
+        bars.render(Duration::from_millis(500), &mut frame);
+        let colors = frame.controllers()[0].colors();
+        assert_eq!(colors[..3], [off; 3]);
+        assert_eq!(colors[3..11], [on, off, off, off, on, off, off, off]);
+
+        // -20 dB fills two thirds of the strip
+        let mut meter = VuMeter::new(analysis.clone())
+            .with_gradient(Gradient::new([on, on]))
+            .with_decay(Duration::ZERO);
+        meter.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.controllers()[0].colors()[11..], [on, on, on, off]);
+
+        let mut flash = BeatFlash::new(analysis, on).with_decay(Duration::from_millis(100));
+        flash.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.controllers()[0].colors()[0], off);
+        sender.send_modify(|analysis| analysis.beats += 1);
+        flash.render(Duration::from_millis(10), &mut frame);
+        assert_eq!(frame.controllers()[0].colors(), [on; 15]);
+        flash.render(Duration::from_millis(110), &mut frame);
+        assert_eq!(frame.controllers()[0].colors(), [off; 15]);
+        Ok(())
+    }
+}
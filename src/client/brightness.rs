@@ -0,0 +1,239 @@
+use crate::Color;
+
+/// Software brightness that scales the colors this client sends, from 0.0 (off) to 1.0 (unchanged).
+///
+/// Unlike [`crate::ModeData::set_brightness`], this works for every device and mode that takes per-LED colors,
+/// such as direct mode, since the colors themselves are dimmed before they are sent.
+/// Brightness can be set on the [`crate::OpenRgbClient`], a [`crate::ControllerGroup`] and a [`crate::Controller`],
+/// and the brightness of the client and of the controller are multiplied.
+///
+/// By default the channels are multiplied by the level. LEDs emit light proportional to the channel,
+/// while eyes perceive light roughly as its square root, so half the level still looks fairly bright.
+/// With [`Self::with_gamma`] the level is raised to the gamma first, so a level of 0.5 with gamma 2.2
+/// looks about half as bright.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{Brightness, Color, OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// // night mode
+/// client.set_brightness(Brightness::new(0.3).with_gamma(2.2));
+///
+/// let group = client.get_all_controllers().await?;
+/// group.set_brightness(0.5);
+/// for controller in &group {
+///     controller.set_all_leds(Color::new(255, 255, 255)).await?;
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "BrightnessFields")
+)]
+pub struct Brightness {
+    level: f32,
+    gamma: f32,
+}
+
+/// Fields of a [`Brightness`] as read from a file, checked by [`Brightness::new`] and [`Brightness::with_gamma`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct BrightnessFields {
+    level: f32,
+    gamma: f32,
+}
+
+#[cfg(feature = "serde")]
+impl From<BrightnessFields> for Brightness {
+    fn from(fields: BrightnessFields) -> Self {
+        Self::new(fields.level).with_gamma(fields.gamma)
+    }
+}
+
+impl Default for Brightness {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl From<f32> for Brightness {
+    fn from(level: f32) -> Self {
+        Self::new(level)
+    }
+}
+
+impl Brightness {
+    /// Full brightness, which leaves colors unchanged.
+    pub const FULL: Self = Self {
+        level: 1.0,
+        gamma: 1.0,
+    };
+
+    /// Creates a brightness that multiplies the channels by `level`, which is clamped to 0.0 to 1.0.
+    ///
+    /// NaN is treated as 0.0.
+    pub fn new(level: f32) -> Self {
+        Self {
+            level: if level.is_nan() {
+                0.0
+            } else {
+                level.clamp(0.0, 1.0)
+            },
+            gamma: 1.0,
+        }
+    }
+
+    /// Raises the level to `gamma` before scaling, so the level follows perceived brightness.
+    ///
+    /// Gamma must be positive and finite, other values are treated as 1.0.
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.gamma = if gamma.is_finite() && gamma > 0.0 {
+            gamma
+        } else {
+            1.0
+        };
+        self
+    }
+
+    /// Returns the level from 0.0 to 1.0.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Returns the gamma the level is raised to, 1.0 for plain scaling.
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    /// Returns the factor the channels are multiplied by.
+    pub fn factor(&self) -> f32 {
+        self.level.powf(self.gamma)
+    }
+
+    /// Returns `color` dimmed to this brightness.
+    pub fn apply(&self, color: Color) -> Color {
+        scale(color, self.factor())
+    }
+}
+
+/// Multiplies the channels of `color` by `factor`.
+pub(crate) fn scale(color: Color, factor: f32) -> Color {
+    let channel = |value: u8| (f32::from(value) * factor).clamp(0.0, 255.0).round() as u8;
+    Color::new(channel(color.r), channel(color.g), channel(color.b))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    #[test]
+    fn test_brightness() {
+        let color = Color::new(255, 100, 0);
+        assert_eq!(Brightness::FULL.apply(color), color);
+        assert_eq!(Brightness::new(0.5).apply(color), Color::new(128, 50, 0));
+        assert_eq!(Brightness::new(-1.0).apply(color), Color::new(0, 0, 0));
+        assert_eq!(Brightness::new(2.0), Brightness::FULL);
+        assert_eq!(
+            Brightness::new(0.5).with_gamma(2.0).apply(color),
+            Color::new(64, 25, 0)
+        );
+        // off stays off whatever the gamma
+        for gamma in [0.0, -2.0, f32::NAN, f32::INFINITY] {
+            let off = Brightness::new(0.0).with_gamma(gamma);
+            assert_eq!(off.gamma(), 1.0);
+            assert_eq!(off.apply(color), Color::default());
+        }
+        assert_eq!(Brightness::new(f32::NAN).apply(color), Color::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_brightness_serde() {
+        let parsed: Brightness =
+            serde_json::from_str(r#"{"level": 5.0, "gamma": 0.0}"#).expect("brightness parses");
+        assert_eq!(parsed, Brightness::FULL);
+        let json = serde_json::to_string(&Brightness::new(0.5).with_gamma(2.2)).unwrap();
+        let parsed: Brightness = serde_json::from_str(&json).expect("brightness parses");
+        assert_eq!(parsed, Brightness::new(0.5).with_gamma(2.2));
+    }
+
+    #[tokio::test]
+    async fn test_dimmed_updates() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Strip", DeviceType::LEDStrip)
+                    .with_zone(MockZone::linear("Strip", 2)),
+            )
+            .with_controller(
+                MockController::new("Fan", DeviceType::Cooler)
+                    .with_zone(MockZone::linear("Fan", 2)),
+            )
+            .start()
+            .await?;
+        let mut client = server.client().await?;
+        let group = client.get_all_controllers().await?;
+        let (strip, fan) = (&group.controllers()[0], &group.controllers()[1]);
+        let white = Color::new(200, 200, 200);
+
+        client.set_brightness(0.5);
+        group.set_brightness(0.5);
+        fan.set_brightness(1.0);
+        assert_eq!(strip.brightness(), Brightness::new(0.5));
+        strip.set_all_leds(white).await?;
+        fan.set_led(1, white).await?;
+        let mut cmd = group.cmd();
+        cmd.set_controller_leds(0, [white, white])?;
+        cmd.execute().await?;
+        // updates have no reply, so wait for one that comes after them
+        client.get_controller_count().await?;
+
+        assert_eq!(server.colors(0), Some(vec![Color::new(50, 50, 50); 2]));
+        assert_eq!(
+            server.colors(1),
+            Some(vec![Color::default(), Color::new(100, 100, 100)])
+        );
+
+        // the brightness belongs to the device, not to this copy of it
+        let strip = client.get_controller(0).await?;
+        assert_eq!(strip.brightness(), Brightness::new(0.5));
+        assert_eq!(
+            client.get_controller(1).await?.brightness(),
+            Brightness::FULL
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_brightness_follows_replugged_device() -> OpenRgbResult<()> {
+        let keyboard = |location: &str| {
+            MockController::new("K70", DeviceType::Keyboard)
+                .with_serial("1234")
+                .with_location(location)
+                .with_zone(MockZone::linear("Keys", 2))
+        };
+        let server = MockServer::builder()
+            .with_controller(keyboard("HID: /dev/hidraw1"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        client.get_controller(0).await?.set_brightness(0.5);
+
+        server.remove_controller(0);
+        server.add_controller(keyboard("HID: /dev/hidraw4"));
+        let replugged = client.get_controller(0).await?;
+        assert_eq!(replugged.location(), "HID: /dev/hidraw4");
+        assert_eq!(replugged.brightness(), Brightness::new(0.5));
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    Calibrations, DEFAULT_ADDR, DEFAULT_PROTOCOL, OpenRgbClient, OpenRgbError, OpenRgbProtocol,
+    OpenRgbResult, ReconnectPolicy, TcpOptions, Timeouts,
+};
+
+/// Environment variable with the host of the `OpenRGB` server, read by [`OpenRgbClientBuilder`].
+///
+/// An empty value counts as unset.
+pub const HOST_ENV: &str = "OPENRGB_HOST";
+
+/// Environment variable with the port of the `OpenRGB` server, read by [`OpenRgbClientBuilder`].
+pub const PORT_ENV: &str = "OPENRGB_PORT";
+
+/// Builder to configure an [`OpenRgbClient`] before connecting, see [`OpenRgbClient::builder`].
+///
+/// The host and port are taken from the first of:
+/// 1. [`Self::with_host`] and [`Self::with_port`],
+/// 2. the [`HOST_ENV`] and [`PORT_ENV`] environment variables,
+/// 3. `127.0.0.1:6742`, same as [`OpenRgbClient::connect`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use openrgb2::{OpenRgbClient, OpenRgbResult, ReconnectPolicy, Timeouts};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::builder()
+///     .with_host("192.168.1.20")
+///     .with_name("Lighting daemon")
+///     .with_timeouts(Timeouts::default().with_read(Duration::from_secs(5)))
+///     .with_reconnect_policy(ReconnectPolicy::default())
+///     .with_keepalive(true)
+///     .connect()
+///     .await?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenRgbClientBuilder {
+    host: Option<String>,
+    port: Option<u16>,
+    protocol_version: u32,
+    name: Option<String>,
+    timeouts: Timeouts,
+    reconnect_policy: Option<ReconnectPolicy>,
+    tcp: TcpOptions,
+    calibrations: Calibrations,
+}
+
+impl Default for OpenRgbClientBuilder {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: None,
+            protocol_version: DEFAULT_PROTOCOL,
+            name: None,
+            timeouts: Timeouts::default(),
+            reconnect_policy: None,
+            tcp: TcpOptions::default(),
+            calibrations: Calibrations::default(),
+        }
+    }
+}
+
+impl OpenRgbClientBuilder {
+    /// Sets the host name or IP address of the server.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Sets the port of the server.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets the highest protocol version to use, [`DEFAULT_PROTOCOL`] by default.
+    ///
+    /// The lowest of this version and the version of the server is used.
+    pub fn with_protocol_version(mut self, protocol_version: u32) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// Sets the client name shown in the `OpenRGB` SDK server tab, see [`OpenRgbClient::set_name`].
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the timeouts, the connect timeout also applies to the first connection.
+    ///
+    /// See [`OpenRgbClient::set_timeouts`].
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sets how to reconnect after the connection is lost, see [`OpenRgbClient::set_reconnect_policy`].
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(policy);
+        self
+    }
+
+    /// Sets the calibrations applied to the colors sent to each device, see [`OpenRgbClient::set_calibrations`].
+    pub fn with_calibrations(mut self, calibrations: Calibrations) -> Self {
+        self.calibrations = calibrations;
+        self
+    }
+
+    /// Sets `TCP_NODELAY`, which sends small packets such as LED updates right away. Enabled by default.
+    pub fn with_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp.nodelay = nodelay;
+        self
+    }
+
+    /// Sets `SO_KEEPALIVE`, to notice a server that disappeared without closing the connection.
+    /// Disabled by default.
+    pub fn with_keepalive(mut self, keepalive: bool) -> Self {
+        self.tcp.keepalive = keepalive;
+        self
+    }
+
+    /// Connects to the server and applies the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`PORT_ENV`] is not a valid port, or connecting fails.
+    pub async fn connect(self) -> OpenRgbResult<OpenRgbClient> {
+        let (host, port) = self.resolve_addr(|key| std::env::var(key).ok())?;
+        let proto = OpenRgbProtocol::connect_tcp(
+            (host.as_str(), port),
+            self.protocol_version,
+            self.tcp,
+            self.timeouts,
+        )
+        .await?;
+        proto.set_reconnect_policy(self.reconnect_policy);
+        proto.set_calibrations(self.calibrations);
+        if let Some(name) = self.name {
+            proto.set_name(name).await?;
+        }
+        Ok(OpenRgbClient { proto })
+    }
+
+    /// Returns the host and port to connect to, reading environment variables with `env`.
+    fn resolve_addr(&self, env: impl Fn(&str) -> Option<String>) -> OpenRgbResult<(String, u16)> {
+        let host = match &self.host {
+            Some(host) => host.clone(),
+            None => env(HOST_ENV)
+                .map(|host| host.trim().to_owned())
+                .filter(|host| !host.is_empty())
+                .unwrap_or_else(|| DEFAULT_ADDR.0.to_string()),
+        };
+        let port = match (self.port, env(PORT_ENV)) {
+            (Some(port), _) => port,
+            (None, Some(port)) => {
+                port.trim()
+                    .parse()
+                    .map_err(|_| OpenRgbError::ConnectionError {
+                        addr: host.clone(),
+                        source: std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            format!("{PORT_ENV} is not a valid port: {port:?}"),
+                        ),
+                    })?
+            }
+            (None, None) => DEFAULT_ADDR.1,
+        };
+        Ok((host, port))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockServer, RecordedPacket};
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| (*v).to_owned())
+        }
+    }
+
+    #[test]
+    fn test_resolve_addr() -> OpenRgbResult<()> {
+        let builder = OpenRgbClientBuilder::default();
+        assert_eq!(
+            builder.resolve_addr(env(&[]))?,
+            ("127.0.0.1".to_owned(), 6742)
+        );
+        let vars = [(HOST_ENV, "rgb.local"), (PORT_ENV, "6743")];
+        assert_eq!(
+            builder.resolve_addr(env(&vars))?,
+            ("rgb.local".to_owned(), 6743)
+        );
+        // an empty host is treated as unset, like a shell `export OPENRGB_HOST=`
+        for host in ["", "  ", " rgb.local\n"] {
+            let expected = if host.trim().is_empty() {
+                "127.0.0.1"
+            } else {
+                "rgb.local"
+            };
+            assert_eq!(
+                builder.resolve_addr(env(&[(HOST_ENV, host)]))?,
+                (expected.to_owned(), 6742),
+                "{host:?}"
+            );
+        }
+        let builder = builder.with_host("10.0.0.2").with_port(1234);
+        assert_eq!(
+            builder.resolve_addr(env(&vars))?,
+            ("10.0.0.2".to_owned(), 1234)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_port() {
+        let builder = OpenRgbClientBuilder::default();
+        assert!(builder.resolve_addr(env(&[(PORT_ENV, "rgb")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_connect() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_protocol_version(4)
+            .start()
+            .await?;
+        let mut client = OpenRgbClient::builder()
+            .with_host(server.addr().ip().to_string())
+            .with_port(server.addr().port())
+            .with_protocol_version(3)
+            .with_name("Builder")
+            .with_keepalive(true)
+            .connect()
+            .await?;
+
+        assert_eq!(client.get_protocol_version(), 3);
+        // the name is written before the request, so it has been handled once the reply arrives
+        client.get_controller_count().await?;
+        assert_eq!(
+            server.received(),
+            [RecordedPacket::SetClientName {
+                name: "Builder".to_owned()
+            }]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,610 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{
+    Color, Controller, OpenRgbError, OpenRgbResult, ParseCalibrationError,
+    protocol::data::ControllerData,
+};
+
+/// Order in which a device reads the red, green and blue bytes of a color.
+///
+/// Many addressable strips are wired for GRB, so behind a generic controller
+/// red shows up as green. Setting [`ChannelOrder::Grb`] swaps the bytes back before sending.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChannelOrder {
+    /// Red, green, blue, which needs no reordering.
+    #[default]
+    Rgb,
+    /// Red, blue, green.
+    Rbg,
+    /// Green, red, blue.
+    Grb,
+    /// Green, blue, red.
+    Gbr,
+    /// Blue, red, green.
+    Brg,
+    /// Blue, green, red.
+    Bgr,
+}
+
+impl ChannelOrder {
+    /// Reorders `color` so a device using this order shows it.
+    pub fn apply(self, color: Color) -> Color {
+        let Color { r, g, b } = color;
+        match self {
+            Self::Rgb => Color::new(r, g, b),
+            Self::Rbg => Color::new(r, b, g),
+            Self::Grb => Color::new(g, r, b),
+            Self::Gbr => Color::new(g, b, r),
+            Self::Brg => Color::new(b, r, g),
+            Self::Bgr => Color::new(b, g, r),
+        }
+    }
+}
+
+/// Correction applied to the colors sent to a device, so the same [`Color`] looks the same everywhere.
+///
+/// Each channel goes from 0.0 to 1.0 and is corrected in this order:
+/// 1. raised to the power of the gamma,
+/// 2. multiplied by the gain of the channel,
+/// 3. multiplied by the channel of the white point,
+///
+/// after which the channels are reordered for the device, see [`ChannelOrder`].
+/// The default calibration changes nothing.
+///
+/// # Example
+///
+/// ```
+/// # use openrgb2::{Calibration, ChannelOrder, Color};
+/// // a strip that is too blue and wired as GRB
+/// let calibration = Calibration::default()
+///     .with_gain(1.0, 1.0, 0.8)
+///     .with_channel_order(ChannelOrder::Grb);
+/// assert_eq!(calibration.apply(Color::new(255, 0, 255)), Color::new(0, 255, 204));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "CalibrationFields")
+)]
+pub struct Calibration {
+    gain: [f32; 3],
+    gamma: f32,
+    white_point: Color,
+    channel_order: ChannelOrder,
+}
+
+/// Fields of a [`Calibration`] as read from a file, checked by [`Calibration::with_gain`] and [`Calibration::with_gamma`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct CalibrationFields {
+    gain: [f32; 3],
+    gamma: f32,
+    white_point: Color,
+    channel_order: ChannelOrder,
+}
+
+#[cfg(feature = "serde")]
+impl From<CalibrationFields> for Calibration {
+    fn from(fields: CalibrationFields) -> Self {
+        let [r, g, b] = fields.gain;
+        Self::default()
+            .with_gain(r, g, b)
+            .with_gamma(fields.gamma)
+            .with_white_point(fields.white_point)
+            .with_channel_order(fields.channel_order)
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            gain: [1.0; 3],
+            gamma: 1.0,
+            white_point: Color::new(255, 255, 255),
+            channel_order: ChannelOrder::Rgb,
+        }
+    }
+}
+
+impl Calibration {
+    /// Sets the factors the red, green and blue channels are multiplied with, 1.0 by default.
+    ///
+    /// Negative factors count as 0.0, and factors that are not finite as 1.0.
+    pub fn with_gain(mut self, r: f32, g: f32, b: f32) -> Self {
+        self.gain = [r, g, b].map(|gain| if gain.is_finite() { gain.max(0.0) } else { 1.0 });
+        self
+    }
+
+    /// Sets the gamma the channels are raised to, 1.0 by default.
+    ///
+    /// LEDs are linear, so a gamma around 2.2 makes dark colors look like they do on a monitor.
+    /// Gammas that are not finite and above 0.0 count as 1.0.
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.gamma = if gamma.is_finite() && gamma > 0.0 {
+            gamma
+        } else {
+            1.0
+        };
+        self
+    }
+
+    /// Sets the color that is sent for white, white by default.
+    ///
+    /// Use the color that looks neutral on the device, such as `#ffe0c0` for LEDs that are too blue.
+    pub fn with_white_point(mut self, white_point: impl Into<Color>) -> Self {
+        self.white_point = white_point.into();
+        self
+    }
+
+    /// Sets the order in which the device reads the channels, see [`ChannelOrder`].
+    pub fn with_channel_order(mut self, channel_order: ChannelOrder) -> Self {
+        self.channel_order = channel_order;
+        self
+    }
+
+    /// Returns the gain of the red, green and blue channels.
+    pub fn gain(&self) -> [f32; 3] {
+        self.gain
+    }
+
+    /// Returns the gamma.
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    /// Returns the color that is sent for white.
+    pub fn white_point(&self) -> Color {
+        self.white_point
+    }
+
+    /// Returns the order in which the device reads the channels.
+    pub fn channel_order(&self) -> ChannelOrder {
+        self.channel_order
+    }
+
+    /// Returns the color to send for `color`.
+    pub fn apply(&self, color: Color) -> Color {
+        let channel = |value: u8, gain: f32, white: u8| {
+            let value = (f32::from(value) / 255.0).powf(self.gamma) * gain;
+            (value * f32::from(white)).clamp(0.0, 255.0).round() as u8
+        };
+        let [gain_r, gain_g, gain_b] = self.gain;
+        let white = self.white_point;
+        self.channel_order.apply(Color::new(
+            channel(color.r, gain_r, white.r),
+            channel(color.g, gain_g, white.g),
+            channel(color.b, gain_b, white.b),
+        ))
+    }
+}
+
+/// Selects the devices a [`Calibration`] applies to.
+///
+/// Fields that are not set match any device, so the default filter matches all devices.
+/// Set fields must be equal to what the device reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceFilter {
+    vendor: Option<String>,
+    name: Option<String>,
+    serial: Option<String>,
+    location: Option<String>,
+}
+
+impl DeviceFilter {
+    /// Only matches devices of `vendor`.
+    pub fn with_vendor(mut self, vendor: impl Into<String>) -> Self {
+        self.vendor = Some(vendor.into());
+        self
+    }
+
+    /// Only matches devices named `name`.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only matches the device with `serial`.
+    pub fn with_serial(mut self, serial: impl Into<String>) -> Self {
+        self.serial = Some(serial.into());
+        self
+    }
+
+    /// Only matches the device at `location`, such as a HID path or I2C address.
+    pub fn with_location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Returns true if `controller` is selected by this filter.
+    pub fn matches(&self, controller: &Controller) -> bool {
+        self.matches_data(controller.data())
+    }
+
+    fn matches_data(&self, data: &ControllerData) -> bool {
+        let matches =
+            |filter: &Option<String>, value: &str| filter.as_deref().is_none_or(|f| f == value);
+        matches(&self.vendor, data.vendor())
+            && matches(&self.name, data.name())
+            && matches(&self.serial, data.serial())
+            && matches(&self.location, data.location())
+    }
+}
+
+/// Calibrations for the devices of a setup, see [`crate::OpenRgbClient::set_calibrations`].
+///
+/// A device uses the calibration of the first filter that matches it, so list specific filters first.
+///
+/// # File format
+///
+/// [`Calibrations::load`] reads a text file with a `[device]` section per entry.
+/// `vendor`, `name`, `serial` and `location` select the devices, see [`DeviceFilter`],
+/// while `gain`, `gamma`, `white_point` and `channel_order` set the [`Calibration`].
+/// Lines starting with `#` or `;` are comments.
+///
+/// ```text
+/// # ARGB strip on a generic controller
+/// [device]
+/// name = ASUS Aura Addressable
+/// location = HID: /dev/hidraw3
+/// channel_order = grb
+///
+/// # everything else
+/// [device]
+/// gamma = 2.2
+/// gain = 1.0 0.9 0.85
+/// white_point = #fff0e0
+/// ```
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{Calibrations, Color, OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// client.set_calibrations(Calibrations::load("calibration.conf")?);
+///
+/// // sent as calibrated for each device
+/// for controller in &client.get_all_controllers().await? {
+///     controller.set_all_leds(Color::new(255, 255, 255)).await?;
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibrations {
+    entries: Vec<(DeviceFilter, Calibration)>,
+}
+
+impl Calibrations {
+    /// Adds `calibration` for the devices matching `filter`, after the calibrations added before.
+    pub fn with(mut self, filter: DeviceFilter, calibration: Calibration) -> Self {
+        self.entries.push((filter, calibration));
+        self
+    }
+
+    /// Returns the filters and their calibrations, in the order they are matched.
+    pub fn entries(&self) -> &[(DeviceFilter, Calibration)] {
+        &self.entries
+    }
+
+    /// Returns the calibration of `controller`, `None` if no filter matches it.
+    pub fn get(&self, controller: &Controller) -> Option<&Calibration> {
+        self.find(controller.data())
+    }
+
+    pub(crate) fn find(&self, data: &ControllerData) -> Option<&Calibration> {
+        self.entries
+            .iter()
+            .find(|(filter, _)| filter.matches_data(data))
+            .map(|(_, calibration)| calibration)
+    }
+
+    /// Reads calibrations from the file at `path`, see [the file format](Self#file-format).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::FileError`] if the file cannot be read,
+    /// or [`OpenRgbError::CalibrationError`] if it is not valid.
+    pub fn load(path: impl AsRef<Path>) -> OpenRgbResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| OpenRgbError::FileError {
+            path: path.to_owned(),
+            source,
+        })?;
+        Ok(text.parse()?)
+    }
+}
+
+impl FromStr for Calibrations {
+    type Err = ParseCalibrationError;
+
+    /// Parses calibrations in [the file format](Self#file-format).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut calibrations = Self::default();
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            let error = |message: String| ParseCalibrationError {
+                line: line_number,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if line.starts_with('[') {
+                if line != "[device]" {
+                    return Err(error(format!("unknown section {line}, expected [device]")));
+                }
+                calibrations = calibrations.with(DeviceFilter::default(), Calibration::default());
+                continue;
+            }
+            let Some((filter, calibration)) = calibrations.entries.last_mut() else {
+                return Err(error("settings must follow a [device] section".to_owned()));
+            };
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(format!("expected key = value, got {line:?}")));
+            };
+            let value = value.trim();
+            match key.trim() {
+                "vendor" => filter.vendor = Some(value.to_owned()),
+                "name" => filter.name = Some(value.to_owned()),
+                "serial" => filter.serial = Some(value.to_owned()),
+                "location" => filter.location = Some(value.to_owned()),
+                "gain" => {
+                    let gain = value
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|v| !v.is_empty())
+                        .map(|v| match parse_float(v)? {
+                            gain if gain < 0.0 => {
+                                Err(format!("gain must not be negative, got {v:?}"))
+                            }
+                            gain => Ok(gain),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(error)?;
+                    calibration.gain = gain.try_into().map_err(|_| {
+                        error(format!(
+                            "gain needs three values for red, green and blue, got {value:?}"
+                        ))
+                    })?;
+                }
+                "gamma" => {
+                    calibration.gamma = match parse_float(value).map_err(error)? {
+                        gamma if gamma > 0.0 => gamma,
+                        _ => return Err(error(format!("gamma must be above 0, got {value:?}"))),
+                    };
+                }
+                "white_point" => {
+                    calibration.white_point =
+                        crate::color::parse(value).map_err(|e| error(e.to_string()))?;
+                }
+                "channel_order" => {
+                    calibration.channel_order = parse_channel_order(value).map_err(error)?;
+                }
+                key => return Err(error(format!("unknown setting {key:?}"))),
+            }
+        }
+        Ok(calibrations)
+    }
+}
+
+/// Parses the channels as letters, such as `grb`, ignoring case.
+fn parse_channel_order(value: &str) -> Result<ChannelOrder, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "rgb" => Ok(ChannelOrder::Rgb),
+        "rbg" => Ok(ChannelOrder::Rbg),
+        "grb" => Ok(ChannelOrder::Grb),
+        "gbr" => Ok(ChannelOrder::Gbr),
+        "brg" => Ok(ChannelOrder::Brg),
+        "bgr" => Ok(ChannelOrder::Bgr),
+        _ => Err(format!("unknown channel order {value:?}")),
+    }
+}
+
+/// Parses a finite number, rejecting `NaN` and `inf`.
+fn parse_float(value: &str) -> Result<f32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|value: &f32| value.is_finite())
+        .ok_or_else(|| format!("expected a number, got {value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockServer, MockZone, RecordedPacket};
+    use crate::{DeviceType, OpenRgbClient};
+
+    use super::*;
+
+    #[test]
+    fn test_calibration() {
+        let color = Color::new(255, 128, 0);
+        assert_eq!(Calibration::default().apply(color), color);
+        assert_eq!(
+            Calibration::default().with_gamma(2.0).apply(color),
+            Color::new(255, 64, 0)
+        );
+        assert_eq!(
+            Calibration::default().with_gain(0.5, 2.0, 1.0).apply(color),
+            Color::new(128, 255, 0)
+        );
+        assert_eq!(
+            Calibration::default()
+                .with_white_point(Color::new(255, 200, 100))
+                .apply(Color::new(255, 255, 255)),
+            Color::new(255, 200, 100)
+        );
+        let c = Color::new(1, 2, 3);
+        assert_eq!(ChannelOrder::Gbr.apply(c), Color::new(2, 3, 1));
+        assert_eq!(ChannelOrder::Brg.apply(c), Color::new(3, 1, 2));
+        assert_eq!(ChannelOrder::Bgr.apply(c), Color::new(3, 2, 1));
+
+        for gamma in [0.0, -2.0, f32::NAN, f32::INFINITY] {
+            let calibration = Calibration::default().with_gamma(gamma);
+            assert_eq!(calibration.gamma(), 1.0, "{gamma}");
+            assert_eq!(calibration.apply(color), color, "{gamma}");
+        }
+        let calibration = Calibration::default().with_gain(-1.0, f32::NAN, f32::INFINITY);
+        assert_eq!(calibration.gain(), [0.0, 1.0, 1.0]);
+        assert_eq!(
+            calibration.apply(Color::new(255, 255, 255)),
+            Color::new(0, 255, 255)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_calibration_serde() {
+        let json = serde_json::to_string(&Calibration::default().with_gamma(2.2)).unwrap();
+        let parsed: Calibration = serde_json::from_str(&json).expect("calibration parses");
+        assert_eq!(parsed, Calibration::default().with_gamma(2.2));
+        let json = json.replace("2.2", "-1.0");
+        let parsed: Calibration = serde_json::from_str(&json).expect("calibration parses");
+        assert_eq!(parsed, Calibration::default());
+    }
+
+    #[test]
+    fn test_parse_calibrations() {
+        let calibrations: Calibrations = "
+            # strip
+            [device]
+            vendor = ASUS
+            name = Aura Addressable
+            channel_order = GRB
+
+            [device]
+            gain = 1.0, 0.9 0.5
+            gamma = 2.2
+            white_point = #fff0e0
+        "
+        .parse()
+        .expect("valid calibrations");
+        assert_eq!(
+            calibrations,
+            Calibrations::default()
+                .with(
+                    DeviceFilter::default()
+                        .with_vendor("ASUS")
+                        .with_name("Aura Addressable"),
+                    Calibration::default().with_channel_order(ChannelOrder::Grb)
+                )
+                .with(
+                    DeviceFilter::default(),
+                    Calibration::default()
+                        .with_gain(1.0, 0.9, 0.5)
+                        .with_gamma(2.2)
+                        .with_white_point(Color::new(255, 240, 224))
+                )
+        );
+
+        for (text, line) in [
+            ("gamma = 2", 1),
+            ("[device]\n\ngain = 1 2", 3),
+            ("[device]\ngamma = bright", 2),
+            ("[devices]", 1),
+            ("[device]\nbrightness = 1", 2),
+            ("[device]\nchannel_order = rgbw", 2),
+            ("[device]\ngamma = 0", 2),
+            ("[device]\ngamma = -2", 2),
+            ("[device]\ngamma = NaN", 2),
+            ("[device]\ngamma = inf", 2),
+            ("[device]\n\ngain = 1, -0.5, 1", 3),
+            ("[device]\ngain = 1 NaN 1", 2),
+            ("[device]\ngain = 1 1 inf", 2),
+        ] {
+            assert_eq!(
+                text.parse::<Calibrations>().map_err(|e| e.line),
+                Err(line),
+                "{text}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calibrated_updates() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Strip", DeviceType::LEDStrip)
+                    .with_vendor("Generic")
+                    .with_zone(MockZone::linear("Strip", 2)),
+            )
+            .with_controller(
+                MockController::new("Fan", DeviceType::Cooler)
+                    .with_zone(MockZone::linear("Fan", 1)),
+            )
+            .start()
+            .await?;
+        let mut client = OpenRgbClient::connect_to(server.addr(), 5).await?;
+        client.set_calibrations(Calibrations::default().with(
+            DeviceFilter::default().with_vendor("Generic"),
+            Calibration::default().with_channel_order(ChannelOrder::Grb),
+        ));
+        let group = client.get_all_controllers().await?;
+        let (strip, fan) = (&group.controllers()[0], &group.controllers()[1]);
+        assert!(strip.calibration().is_some());
+        assert!(fan.calibration().is_none());
+
+        let red = Color::new(255, 0, 0);
+        let green = Color::new(0, 255, 0);
+        strip.set_leds([red, red]).await?;
+        strip.set_led(1, red).await?;
+        strip.get_zone(0)?.set_leds([red, red]).await?;
+        let mut cmd = strip.cmd();
+        cmd.set_led(0, red)?;
+        cmd.execute().await?;
+        let mut cmd = group.cmd();
+        cmd.set_controller_leds(0, [red])?;
+        cmd.set_controller_leds(1, [red])?;
+        cmd.execute().await?;
+        // updates have no reply, so wait for one that comes after them
+        client.get_controller_count().await?;
+
+        let mut received = server.received();
+        // the group command writes both controllers at once, in any order
+        received.sort_by_key(|packet| match packet {
+            RecordedPacket::UpdateLeds { controller_id, .. } => *controller_id,
+            _ => 0,
+        });
+        assert_eq!(
+            received,
+            [
+                RecordedPacket::UpdateLeds {
+                    controller_id: 0,
+                    colors: vec![green, green]
+                },
+                RecordedPacket::UpdateSingleLed {
+                    controller_id: 0,
+                    led_id: 1,
+                    color: green
+                },
+                RecordedPacket::UpdateZoneLeds {
+                    controller_id: 0,
+                    zone_id: 0,
+                    colors: vec![green, green]
+                },
+                RecordedPacket::UpdateLeds {
+                    controller_id: 0,
+                    colors: vec![green]
+                },
+                RecordedPacket::UpdateLeds {
+                    controller_id: 0,
+                    colors: vec![green]
+                },
+                RecordedPacket::UpdateLeds {
+                    controller_id: 1,
+                    colors: vec![red]
+                },
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
-    Color, Controller, OpenRgbError, OpenRgbResult,
+    Color, Controller, KeyCode, OpenRgbError, OpenRgbResult,
     client::group::{ControllerGroup, ControllerIndex},
 };
 
@@ -60,12 +60,26 @@ impl<'a> CommandGroup<'a> {
         }
     }
 
-    /// Executes all commands in this group one after another.
+    /// Executes all commands in this group, writing the updates of all controllers at once.
     pub async fn execute(self) -> OpenRgbResult<()> {
-        for cmd in self.commands.into_values() {
-            cmd.execute().await?;
-        }
-        Ok(())
+        let Some(first) = self.group.controllers().first() else {
+            return Ok(());
+        };
+        let colors = self
+            .commands
+            .into_values()
+            .map(|cmd| {
+                (
+                    cmd.controller.id() as u32,
+                    cmd.controller.prepare_colors(cmd.colors),
+                )
+            })
+            .collect::<Vec<_>>();
+        let updates = colors
+            .iter()
+            .map(|(id, colors)| (*id, colors.as_slice()))
+            .collect::<Vec<_>>();
+        first.proto().update_leds_batch(&updates).await
     }
 
     /// Returns a mutable reference to the command for the given controller ID.
@@ -195,6 +209,36 @@ impl<'a> Command<'a> {
         })
     }
 
+    /// Adds a command to update the LED called `name`, see [`Controller::led_by_name()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::CommandError`] if the controller has no LED with that name.
+    pub fn set_led_by_name<C: Into<Color>>(&mut self, name: &str, color: C) -> OpenRgbResult<()> {
+        let led_id = self.controller.led_by_name(name).ok_or_else(|| {
+            OpenRgbError::CommandError(format!(
+                "No LED named {name:?} in controller {}",
+                self.controller.name()
+            ))
+        })?;
+        self.set_led(led_id, color)
+    }
+
+    /// Adds a command to update the LED of `key`, see [`Controller::led_for_key()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::CommandError`] if the controller has no LED for that key.
+    pub fn set_key<C: Into<Color>>(&mut self, key: KeyCode, color: C) -> OpenRgbResult<()> {
+        let led_id = self.controller.led_for_key(key).ok_or_else(|| {
+            OpenRgbError::CommandError(format!(
+                "No LED for key {key} in controller {}",
+                self.controller.name()
+            ))
+        })?;
+        self.set_led(led_id, color)
+    }
+
     /// Adds a command to update multiple LEDs in this controller.
     pub fn set_leds<C: Into<Color>>(
         &mut self,
@@ -281,7 +325,7 @@ impl<'a> Command<'a> {
             }
             SetLedCommand::Zone { zone_id, colors } => {
                 let zone = self.controller.get_zone(zone_id)?;
-                if colors.len() >= zone.num_leds() {
+                if colors.len() > zone.num_leds() {
                     tracing::warn!(
                         "Zone {} for controller {} was given {} colors, while its length is {}. This might become a hard error in the future.",
                         zone_id,
@@ -302,7 +346,7 @@ impl<'a> Command<'a> {
             } => {
                 let zone = self.controller.get_zone(zone_id)?;
                 let seg = zone.get_segment(segment_id)?;
-                if colors.len() >= seg.num_leds() {
+                if colors.len() > seg.num_leds() {
                     tracing::warn!(
                         "Segment {} for zone {} in controller {} was given {} colors, while its length is {}. This might become a hard error in the future.",
                         seg.name(),
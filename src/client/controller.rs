@@ -1,5 +1,5 @@
 use crate::{
-    DeviceType, Led, OpenRgbError, OpenRgbResult, ZoneData,
+    DeviceType, Led, ModeError, OpenRgbError, OpenRgbResult, ZoneData,
     client::command::Command,
     data::{ModeData, ModeFlag},
     protocol::{
@@ -8,7 +8,10 @@ use crate::{
     },
 };
 
-use super::Zone;
+use super::{
+    Brightness, Calibration, ControllerInfo, ControllerKey, KeyCode, ModeEditor, Zone,
+    brightness::scale, keycode,
+};
 
 /// An RGBController, which represents a single RGB device that can be controlled.
 ///
@@ -40,11 +43,25 @@ impl Controller {
         &self.proto
     }
 
+    pub(crate) fn data(&self) -> &ControllerData {
+        &self.data
+    }
+
     /// Returns the ID of this controller.
     pub fn id(&self) -> usize {
         self.id
     }
 
+    /// Returns a key that identifies this device across rescans and reconnects, unlike [`Self::id()`].
+    pub fn key(&self) -> ControllerKey {
+        ControllerKey::new(self.vendor(), self.name(), self.serial(), self.location())
+    }
+
+    /// Returns an owned copy of everything this controller reports, see [`ControllerInfo`].
+    pub fn info(&self) -> ControllerInfo {
+        ControllerInfo::from(self)
+    }
+
     delegate::delegate! {
         to self.data {
             /// Returns the name of this controller.
@@ -72,12 +89,13 @@ impl Controller {
             ///
             /// [`Self::set_controllable_mode()`] will set the controller to the mode named "direct"
             pub fn modes(&self) -> &[ModeData];
-            /// Returns the LEDs in this controller
-            #[allow(unused)]
+            /// Returns the LEDs in this controller, with names such as `"Key: Escape"` for keyboards.
             pub fn leds(&self) -> &[Led];
-            #[allow(unused)]
+            /// Returns the alternate names of the LEDs, only supported in protocol version 5 and above.
+            pub fn led_alt_names(&self) -> Option<&[String]>;
+            /// Returns the zones in this controller.
             pub fn zones(&self) -> &[ZoneData];
-            #[allow(unused)]
+            /// Returns the mode that is currently active on this controller.
             pub fn active_mode(&self) -> Option<&ModeData>;
         }
     }
@@ -126,6 +144,25 @@ impl Controller {
         Ok(())
     }
 
+    /// Returns an editor for the mode named `name`, ignoring case, to change its settings and apply it.
+    ///
+    /// The editor starts from the settings this controller last reported for the mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModeError::NotFound`] if this controller has no such mode.
+    pub fn mode(&self, name: &str) -> Result<ModeEditor<'_>, ModeError> {
+        let mode = self
+            .modes()
+            .iter()
+            .find(|m| m.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ModeError::NotFound {
+                controller: self.name().to_owned(),
+                mode: name.to_owned(),
+            })?;
+        Ok(ModeEditor::new(self, mode.clone()))
+    }
+
     fn get_mode_if_contains(&self, pat: &str) -> Option<&ModeData> {
         self.modes()
             .iter()
@@ -145,17 +182,88 @@ impl Controller {
         Ok(zone)
     }
 
+    /// Returns the calibration applied to the colors sent to this controller,
+    /// see [`crate::OpenRgbClient::set_calibrations`].
+    pub fn calibration(&self) -> Option<Calibration> {
+        self.proto.calibrations().find(&self.data).copied()
+    }
+
+    /// Sets the brightness of this controller, see [`Brightness`].
+    ///
+    /// It is multiplied with the brightness of the client, see [`crate::OpenRgbClient::set_brightness`],
+    /// and applies to colors sent afterwards. The client keeps it by [`Self::key`],
+    /// so it also applies to this device when it is fetched again.
+    pub fn set_brightness(&self, brightness: impl Into<Brightness>) {
+        self.proto
+            .set_controller_brightness(self.key(), brightness.into());
+    }
+
+    /// Returns the brightness of this controller, without the brightness of the client.
+    pub fn brightness(&self) -> Brightness {
+        self.proto.controller_brightness(&self.key())
+    }
+
+    /// Dims `colors` to the brightness of the client and this controller,
+    /// then applies the calibration of this controller, if any.
+    pub(crate) fn prepare_colors(&self, mut colors: Vec<Color>) -> Vec<Color> {
+        let factor = self.proto.brightness().factor() * self.brightness().factor();
+        let calibration = self.calibration();
+        if factor == 1.0 && calibration.is_none() {
+            return colors;
+        }
+        for color in &mut colors {
+            let dimmed = scale(*color, factor);
+            *color = calibration.map_or(dimmed, |c| c.apply(dimmed));
+        }
+        colors
+    }
+
     /// Returns an iterator over all available zones in this controller.
     pub fn get_all_zones<'a>(&'a self) -> impl Iterator<Item = Zone<'a>> {
         self.zones().iter().map(|z| Zone::new(self, z))
     }
 
+    /// Returns the index of the LED called `name`, such as `"Key: W"`.
+    ///
+    /// Names are first compared ignoring case, then also ignoring whitespace, the `"Key:"` prefix
+    /// and notes in parentheses, and finally as a [`KeyCode`], so `"Esc"` finds `"Key: Escape"`.
+    /// Alternate names from [`Self::led_alt_names()`] are searched too.
+    pub fn led_by_name(&self, name: &str) -> Option<usize> {
+        let normalized = keycode::normalize(name);
+        self.named_leds()
+            .find(|(_, led)| led.eq_ignore_ascii_case(name))
+            .or_else(|| {
+                self.named_leds()
+                    .find(|(_, led)| keycode::normalize(led) == normalized)
+            })
+            .map(|(index, _)| index)
+            .or_else(|| self.led_for_key(KeyCode::from_name(name)?))
+    }
+
+    /// Returns the index of the LED of `key`, if this controller has a LED named after it.
+    pub fn led_for_key(&self, key: KeyCode) -> Option<usize> {
+        self.named_leds()
+            .find(|(_, led)| KeyCode::from_name(led) == Some(key))
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the names and alternate names of the LEDs with their index.
+    fn named_leds(&self) -> impl Iterator<Item = (usize, &str)> {
+        let names = self.leds().iter().map(|led| led.name.as_str());
+        let alt_names = self.led_alt_names().unwrap_or_default().iter();
+        names
+            .enumerate()
+            .chain(alt_names.map(String::as_str).enumerate())
+            .filter(|(_, name)| !name.is_empty())
+    }
+
     /// Sets a single LED to the given `color`.
     ///
     /// When doing many writes in rapid succession, it is recommended to use the [`Self::cmd()`] method instead.
     pub async fn set_led<C: Into<Color>>(&self, led: usize, color: C) -> OpenRgbResult<()> {
+        let color = self.prepare_colors(vec![color.into()])[0];
         self.proto
-            .update_led(self.id as u32, led as i32, &color.into())
+            .update_led(self.id as u32, led as i32, &color)
             .await
     }
 
@@ -172,7 +280,7 @@ impl Controller {
         &self,
         colors: impl IntoIterator<Item = C>,
     ) -> OpenRgbResult<()> {
-        let color_v = colors.into_iter().map(Into::into).collect::<Vec<_>>();
+        let color_v = self.prepare_colors(colors.into_iter().map(Into::into).collect());
         self.proto.update_leds(self.id as u32, &color_v).await
     }
 
@@ -182,7 +290,7 @@ impl Controller {
         zone_id: usize,
         colors: impl IntoIterator<Item = C>,
     ) -> OpenRgbResult<()> {
-        let color_v = colors.into_iter().map(Into::into).collect::<Vec<_>>();
+        let color_v = self.prepare_colors(colors.into_iter().map(Into::into).collect());
         self.proto
             .update_zone_leds(self.id as u32, zone_id as u32, &color_v)
             .await
@@ -287,7 +395,8 @@ impl Controller {
 
 #[cfg(test)]
 mod tests {
-    use crate::OpenRgbClient;
+    use crate::mock::{MockController, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbClient};
 
     use super::*;
 
@@ -317,4 +426,44 @@ mod tests {
         cmd.execute().await?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_led_by_name() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Keyboard", DeviceType::Keyboard).with_zone(
+                    MockZone::linear("Keys", 4)
+                        .with_led_names(["Key: Esc", "Key: W", "Key: Left Ctrl", "Key: Num 1"])
+                        .with_led_alt_names(["", "", "", "Key: Keypad End"]),
+                ),
+            )
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let keyboard = client.get_controller(0).await?;
+
+        assert_eq!(keyboard.led_by_name("Key: W"), Some(1));
+        assert_eq!(keyboard.led_by_name("key: w"), Some(1));
+        assert_eq!(keyboard.led_by_name("W"), Some(1));
+        assert_eq!(keyboard.led_by_name("Key: Escape"), Some(0));
+        assert_eq!(keyboard.led_by_name("left control"), Some(2));
+        assert_eq!(keyboard.led_by_name("Key: Keypad End"), Some(3));
+        assert_eq!(keyboard.led_by_name("Key: Q"), None);
+        assert_eq!(keyboard.led_for_key(KeyCode::Numpad1), Some(3));
+        assert_eq!(keyboard.led_for_key(KeyCode::Space), None);
+
+        let red = Color::new(255, 0, 0);
+        let mut cmd = keyboard.cmd();
+        cmd.set_led_by_name("Key: W", red)?;
+        cmd.set_key(KeyCode::LeftControl, red)?;
+        assert!(cmd.set_led_by_name("Key: Q", red).is_err());
+        assert!(cmd.set_key(KeyCode::Space, red).is_err());
+        cmd.execute().await?;
+        client.get_controller(0).await?;
+        assert_eq!(
+            server.colors(0),
+            Some(vec![Color::default(), red, red, Color::default()])
+        );
+        Ok(())
+    }
 }
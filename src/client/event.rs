@@ -0,0 +1,21 @@
+/// Notifications that the OpenRGB server sends without being asked.
+///
+/// Use [`crate::OpenRgbClient::events()`] to subscribe to these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ServerEvent {
+    /// The device list of the server changed, for example because a device was plugged in or a rescan finished.
+    ///
+    /// Controller indices may have shifted, so controllers should be fetched again.
+    DeviceListUpdated,
+
+    /// The connection to the server was lost.
+    ///
+    /// If a [`crate::ReconnectPolicy`] is set, the client starts reconnecting right away.
+    Disconnected,
+
+    /// The client reconnected to the server after losing its connection.
+    ///
+    /// The server may have restarted with a different device list, so controllers should be fetched again.
+    Reconnected,
+}
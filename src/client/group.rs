@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
 use crate::{
-    Controller, OpenRgbError, OpenRgbResult, client::command::CommandGroup, data::DeviceType,
+    Brightness, Controller, ControllerKey, LightingSnapshot, OpenRgbError, OpenRgbResult,
+    RestoreReport, client::command::CommandGroup, data::DeviceType,
 };
 
 /// Trait for things that can index into a `ControllerGroup`.
@@ -101,6 +102,11 @@ impl ControllerGroup {
         idx.index(self)
     }
 
+    /// Returns the controller in this group identified by `key`, see [`ControllerKey`].
+    pub fn find(&self, key: &ControllerKey) -> Option<&Controller> {
+        key.find_in(&self.controllers)
+    }
+
     /// Splits the controllers in this group by their device type.
     /// Returns one group per device type.
     pub fn split_per_type(self) -> HashMap<DeviceType, ControllerGroup> {
@@ -138,7 +144,7 @@ impl ControllerGroup {
     /// Creates a new `CommandGroup` for this controller group.
     ///
     /// See `Controller::cmd()` for more information.
-    pub fn cmd(&self) -> CommandGroup {
+    pub fn cmd(&self) -> CommandGroup<'_> {
         CommandGroup::new(self)
     }
 
@@ -158,6 +164,34 @@ impl ControllerGroup {
         Ok(())
     }
 
+    /// Sets the brightness of every controller in this group, see [`Controller::set_brightness`].
+    pub fn set_brightness(&self, brightness: impl Into<Brightness>) {
+        let brightness = brightness.into();
+        for controller in &self.controllers {
+            controller.set_brightness(brightness);
+        }
+    }
+
+    /// Captures the active mode and colors of every controller in this group.
+    ///
+    /// The snapshot uses the data from when the controllers were fetched,
+    /// so fetch the group right before taking a snapshot.
+    pub fn snapshot(&self) -> LightingSnapshot {
+        LightingSnapshot::capture(self)
+    }
+
+    /// Sets the modes and colors in `snapshot` on the controllers in this group, matching devices by [`ControllerKey`].
+    ///
+    /// Devices in the snapshot that are not in this group are skipped and listed in the report.
+    /// Colors are only restored for devices whose mode shows per-LED colors.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if communication with the `OpenRGB` SDK server fails.
+    pub async fn restore(&self, snapshot: &LightingSnapshot) -> OpenRgbResult<RestoreReport> {
+        snapshot.restore(self).await
+    }
+
     /// Turns off all LEDs in all controllers in this group.
     pub async fn turn_off_leds(&self) -> OpenRgbResult<()> {
         for controller in &self.controllers {
@@ -187,10 +221,52 @@ impl<'a> IntoIterator for &'a ControllerGroup {
 
 #[cfg(test)]
 mod tests {
-    use crate::OpenRgbClient;
+    use crate::mock::{MockController, MockServer, MockZone, RecordedPacket};
+    use crate::{Color, DeviceType, OpenRgbClient};
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_cmd_group_mock() -> OpenRgbResult<()> {
+        let strip = |name| {
+            MockController::new(name, DeviceType::LEDStrip).with_zone(MockZone::linear(name, 2))
+        };
+        let server = MockServer::builder()
+            .with_controller(strip("First"))
+            .with_controller(strip("Second"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let group = client.get_all_controllers().await?;
+
+        let mut cmd = group.cmd();
+        cmd.set_controller_leds(0, [Color::new(255, 0, 0); 2])?;
+        cmd.set_controller_led(1, 1, Color::new(0, 0, 255))?;
+        cmd.execute().await?;
+
+        // a request makes sure the server handled all updates
+        client.get_controller(0).await?;
+        let mut received = server.received();
+        received.sort_by_key(|p| match p {
+            RecordedPacket::UpdateLeds { controller_id, .. } => *controller_id,
+            _ => u32::MAX,
+        });
+        assert_eq!(
+            received,
+            [
+                RecordedPacket::UpdateLeds {
+                    controller_id: 0,
+                    colors: vec![Color::new(255, 0, 0); 2],
+                },
+                RecordedPacket::UpdateLeds {
+                    controller_id: 1,
+                    colors: vec![Color::default(), Color::new(0, 0, 255)],
+                },
+            ]
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     #[ignore = "can only test with openrgb running"]
     async fn test_group() -> OpenRgbResult<()> {
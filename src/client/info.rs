@@ -0,0 +1,280 @@
+use flagset::FlagSet;
+
+use crate::{
+    Color, Controller, ControllerFlags, ControllerKey, DeviceType, Led, ModeData, ModeError,
+    ProtocolOption, ZoneData, protocol::data::ControllerData,
+};
+
+/// Everything a controller reports about itself, detached from the connection.
+///
+/// Returned by [`Controller::info()`]. Unlike a [`Controller`], this cannot send updates,
+/// but it can be kept around, compared, and serialized with the `serde` feature,
+/// for example to keep an inventory of the devices of a server.
+/// Flags are serialized as lists of names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControllerInfo {
+    id: usize,
+    device_type: DeviceType,
+    name: String,
+    vendor: String,
+    description: String,
+    version: String,
+    serial: String,
+    location: String,
+    active_mode: Option<usize>,
+    modes: Vec<ModeData>,
+    zones: Vec<ZoneData>,
+    leds: Vec<Led>,
+    colors: Vec<Color>,
+    led_alt_names: Option<Vec<String>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::data::serde_flags::option"))]
+    flags: Option<FlagSet<ControllerFlags>>,
+}
+
+impl ControllerInfo {
+    /// Returns the id the controller had when this was taken, see [`Controller::id()`].
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns a key that identifies this device, see [`ControllerKey`].
+    pub fn key(&self) -> ControllerKey {
+        ControllerKey::new(&self.vendor, &self.name, &self.serial, &self.location)
+    }
+
+    /// Returns the type of the controller.
+    pub fn device_type(&self) -> DeviceType {
+        self.device_type
+    }
+
+    /// Returns the name of the controller.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the vendor of the controller.
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    /// Returns a description for the controller.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the version of the controller.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns the serial number of the controller.
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    /// Returns the location of the controller.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Returns the mode that was active.
+    pub fn active_mode(&self) -> Option<&ModeData> {
+        self.modes.get(self.active_mode?)
+    }
+
+    /// Returns the modes supported by the controller.
+    pub fn modes(&self) -> &[ModeData] {
+        &self.modes
+    }
+
+    /// Returns the zones of the controller.
+    pub fn zones(&self) -> &[ZoneData] {
+        &self.zones
+    }
+
+    /// Returns the LEDs of the controller.
+    pub fn leds(&self) -> &[Led] {
+        &self.leds
+    }
+
+    /// Returns the colors of the LEDs.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Returns the number of LEDs in all zones of the controller.
+    pub fn num_leds(&self) -> usize {
+        self.zones.iter().map(ZoneData::leds_count).sum()
+    }
+
+    /// Returns the alternate names for LEDs, only supported in protocol version 5 and above.
+    pub fn led_alt_names(&self) -> Option<&[String]> {
+        self.led_alt_names.as_deref()
+    }
+
+    /// Returns the flags of the controller, only supported in protocol version 5 and above.
+    pub fn flags(&self) -> Option<FlagSet<ControllerFlags>> {
+        self.flags
+    }
+
+    /// Makes the mode named `name`, ignoring case, the active mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModeError::NotFound`] if the controller has no such mode.
+    pub fn set_active_mode(&mut self, name: &str) -> Result<(), ModeError> {
+        let id = self
+            .modes
+            .iter()
+            .position(|m| m.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ModeError::NotFound {
+                controller: self.name.clone(),
+                mode: name.to_owned(),
+            })?;
+        self.active_mode = Some(id);
+        Ok(())
+    }
+
+    /// Returns the modes, to change their settings.
+    pub fn modes_mut(&mut self) -> &mut [ModeData] {
+        &mut self.modes
+    }
+
+    /// Returns the colors of the LEDs, to change them.
+    pub fn colors_mut(&mut self) -> &mut [Color] {
+        &mut self.colors
+    }
+
+    pub(crate) fn from_data(id: usize, data: &ControllerData) -> Self {
+        Self {
+            id,
+            device_type: data.device_type(),
+            name: data.name().to_owned(),
+            vendor: data.vendor().to_owned(),
+            description: data.description().to_owned(),
+            version: data.version().to_owned(),
+            serial: data.serial().to_owned(),
+            location: data.location().to_owned(),
+            active_mode: usize::try_from(data.active_mode)
+                .ok()
+                .filter(|&mode| mode < data.modes().len()),
+            modes: data.modes().to_vec(),
+            zones: data.zones().to_vec(),
+            leds: data.leds().to_vec(),
+            colors: data.colors().to_vec(),
+            led_alt_names: data.led_alt_names().map(<[String]>::to_vec),
+            flags: data.flags(),
+        }
+    }
+
+    /// Converts back to the data sent by the server with `protocol_version`.
+    ///
+    /// Values that are missing because they were read with an older protocol version are filled with defaults.
+    pub(crate) fn to_data(&self, protocol_version: u32) -> ControllerData {
+        let version = protocol_version as usize;
+        let mut modes = self.modes.clone();
+        for mode in &mut modes {
+            fill_default(&mut mode.brightness_min, version);
+            fill_default(&mut mode.brightness_max, version);
+            fill_default(&mut mode.brightness, version);
+        }
+        let mut zones = self.zones.clone();
+        for zone in &mut zones {
+            fill_default(&mut zone.segments, version);
+            fill_default(&mut zone.flags, version);
+        }
+        ControllerData {
+            device_type: self.device_type,
+            name: self.name.clone(),
+            vendor: self.vendor.clone(),
+            description: self.description.clone(),
+            version: self.version.clone(),
+            serial: self.serial.clone(),
+            location: self.location.clone(),
+            active_mode: self.active_mode.map_or(-1, |mode| mode as i32),
+            modes,
+            zones,
+            leds: self.leds.clone(),
+            colors: self.colors.clone(),
+            led_alt_names: ProtocolOption::new(
+                self.led_alt_names.clone().unwrap_or_default(),
+                version,
+            ),
+            flags: ProtocolOption::new(self.flags.unwrap_or_default(), version),
+            id: self.id as u32,
+            num_leds: self.num_leds(),
+        }
+    }
+}
+
+/// Sets a value that is missing but supported by `protocol_version` to its default.
+fn fill_default<const VER: usize, T: Default>(
+    option: &mut ProtocolOption<VER, T>,
+    protocol_version: usize,
+) {
+    if option.value().is_none() {
+        *option = ProtocolOption::new(T::default(), protocol_version);
+    }
+}
+
+impl From<&Controller> for ControllerInfo {
+    fn from(controller: &Controller) -> Self {
+        Self::from_data(controller.id(), controller.data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockMode, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    fn keyboard() -> MockController {
+        MockController::new("K70", DeviceType::Keyboard)
+            .with_vendor("Corsair")
+            .with_serial("1234")
+            .with_mode(MockMode::direct())
+            .with_mode(MockMode::new("Rainbow"))
+            .with_zone(MockZone::linear("Keys", 4))
+            .with_zone(MockZone::linear("Logo", 1))
+    }
+
+    #[tokio::test]
+    async fn test_controller_info() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(keyboard())
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let controller = client.get_controller(0).await?;
+        let info = ControllerInfo::from(&controller);
+        assert_eq!(info.key(), controller.key());
+        assert_eq!(info.num_leds(), 5);
+        assert_eq!(info.active_mode(), controller.active_mode());
+        assert_eq!(info.zones(), controller.zones());
+        assert_eq!(info.flags(), controller.data().flags());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn test_controller_info_serde() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(keyboard())
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let info = client.get_controller(0).await?.info();
+
+        let json = serde_json::to_value(&info).expect("info serializes");
+        assert_eq!(json["device_type"], "Keyboard");
+        assert_eq!(json["zones"][1]["zone_type"], "Linear");
+        assert_eq!(json["modes"][0]["flags"][0], "HasPerLEDColor");
+        assert!(json["zones"][0]["flags"].is_array());
+        let parsed: ControllerInfo = serde_json::from_value(json).expect("info deserializes");
+        assert_eq!(parsed, info);
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+use crate::Controller;
+
+/// Identifies a device across rescans and reconnects, unlike [`Controller::id()`].
+///
+/// The id of a controller is its index in the device list of the server,
+/// which changes when [`crate::OpenRgbClient::rescan_devices`] reorders devices or a device is replugged.
+/// A key is built from the vendor, name, serial and location of the device instead,
+/// and can be stored to find the same device again with [`crate::OpenRgbClient::find_controller`].
+///
+/// Devices match if their vendor and name are equal, and then:
+/// 1. if the key has a serial, their serials are equal,
+/// 2. otherwise, if the key has a location, their locations are equal,
+/// 3. otherwise the vendor and name are all there is to go on.
+///
+/// When several devices match, the one with the same location is preferred,
+/// since some devices report the same serial, and then the one with the lowest id.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// let key = client.get_controller(0).await?.key();
+///
+/// client.rescan_devices().await?;
+/// if let Some(controller) = client.find_controller(&key).await? {
+///     println!("{key} now has id {}", controller.id());
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControllerKey {
+    vendor: String,
+    name: String,
+    serial: String,
+    location: String,
+}
+
+impl ControllerKey {
+    /// Creates a key from the details reported by a device, empty strings are treated as unknown.
+    pub fn new(
+        vendor: impl Into<String>,
+        name: impl Into<String>,
+        serial: impl Into<String>,
+        location: impl Into<String>,
+    ) -> Self {
+        Self {
+            vendor: vendor.into(),
+            name: name.into(),
+            serial: serial.into().trim().to_owned(),
+            location: location.into(),
+        }
+    }
+
+    /// Returns the vendor of the device.
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    /// Returns the name of the device.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the serial number of the device, empty if it has none.
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    /// Returns the location of the device, such as a HID path or I2C address.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Returns true if `controller` is the device this key identifies.
+    pub fn matches(&self, controller: &Controller) -> bool {
+        self.match_score(&controller.key()).is_some()
+    }
+
+    /// Returns the controller in `controllers` this key identifies, see the [type docs](Self).
+    pub(crate) fn find_in<'a, I>(&self, controllers: I) -> Option<&'a Controller>
+    where
+        I: IntoIterator<Item = &'a Controller>,
+    {
+        let mut best: Option<(u8, &Controller)> = None;
+        for controller in controllers {
+            match self.match_score(&controller.key()) {
+                Some(score) if best.is_none_or(|(best, _)| score > best) => {
+                    best = Some((score, controller));
+                }
+                _ => {}
+            }
+        }
+        best.map(|(_, controller)| controller)
+    }
+
+    /// Returns the value of the key in `entries` that identifies the device with this key best,
+    /// preferring later entries when several match equally well.
+    pub(crate) fn find_entry<'a, T>(&self, entries: &'a [(ControllerKey, T)]) -> Option<&'a T> {
+        let mut best: Option<(u8, &T)> = None;
+        for (key, value) in entries.iter().rev() {
+            match key.match_score(self) {
+                Some(score) if best.is_none_or(|(best, _)| score > best) => {
+                    best = Some((score, value));
+                }
+                _ => {}
+            }
+        }
+        best.map(|(_, value)| value)
+    }
+
+    /// Returns how closely `other` matches this key, higher is better, `None` if it is another device.
+    fn match_score(&self, other: &ControllerKey) -> Option<u8> {
+        if self.vendor != other.vendor || self.name != other.name {
+            return None;
+        }
+        let same_location = self.location == other.location;
+        if !self.serial.is_empty() {
+            (self.serial == other.serial).then_some(1 + u8::from(same_location))
+        } else if !self.location.is_empty() {
+            same_location.then_some(1)
+        } else {
+            Some(0)
+        }
+    }
+}
+
+impl fmt::Display for ControllerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.vendor.is_empty() {
+            write!(f, "{}", self.name)?;
+        } else {
+            write!(f, "{} {}", self.vendor, self.name)?;
+        }
+        if !self.serial.is_empty() {
+            write!(f, " (serial {})", self.serial)
+        } else if !self.location.is_empty() {
+            write!(f, " (at {})", self.location)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl From<&Controller> for ControllerKey {
+    fn from(controller: &Controller) -> Self {
+        controller.key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockServer};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    fn keyboard(serial: &str, location: &str) -> MockController {
+        MockController::new("K70", DeviceType::Keyboard)
+            .with_vendor("Corsair")
+            .with_serial(serial)
+            .with_location(location)
+    }
+
+    #[test]
+    fn test_match_score() {
+        let key = ControllerKey::new("Corsair", "K70", "1234", "HID: /dev/hidraw1");
+        let moved = ControllerKey::new("Corsair", "K70", "1234", "HID: /dev/hidraw4");
+        let other = ControllerKey::new("Corsair", "K70", "5678", "HID: /dev/hidraw1");
+        assert_eq!(key.match_score(&key), Some(2));
+        assert_eq!(key.match_score(&moved), Some(1));
+        assert_eq!(key.match_score(&other), None);
+
+        let no_serial =
+            ControllerKey::new("ASUS", "Aura DRAM", "", "I2C: /dev/i2c-1, address 0x71");
+        let next_stick =
+            ControllerKey::new("ASUS", "Aura DRAM", "", "I2C: /dev/i2c-1, address 0x72");
+        assert_eq!(no_serial.match_score(&no_serial), Some(1));
+        assert_eq!(no_serial.match_score(&next_stick), None);
+
+        let bare = ControllerKey::new("", "Strip", "", "");
+        assert_eq!(
+            bare.match_score(&ControllerKey::new("", "Strip", "", "COM3")),
+            Some(0)
+        );
+        assert_eq!(bare.to_string(), "Strip");
+
+        let entries = [(key.clone(), 1), (moved.clone(), 2), (no_serial.clone(), 3)];
+        assert_eq!(key.find_entry(&entries), Some(&1));
+        // a replugged device is still found by its serial
+        let replugged = ControllerKey::new("Corsair", "K70", "1234", "HID: /dev/hidraw7");
+        assert_eq!(replugged.find_entry(&entries[..1]), Some(&1));
+        assert_eq!(other.find_entry(&entries), None);
+        assert_eq!(key.to_string(), "Corsair K70 (serial 1234)");
+    }
+
+    #[tokio::test]
+    async fn test_find_controller() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(keyboard("", "HID: /dev/hidraw1"))
+            .with_controller(keyboard("", "HID: /dev/hidraw2"))
+            .with_controller(keyboard("ABC", "HID: /dev/hidraw3"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let second = client.get_controller(1).await?.key();
+        let with_serial = client.get_controller(2).await?.key();
+
+        // replugging moves the devices to the end of the list, and to another location
+        server.remove_controller(0);
+        server.add_controller(keyboard("", "HID: /dev/hidraw1"));
+        server.remove_controller(1);
+        server.add_controller(keyboard("ABC", "HID: /dev/hidraw5"));
+
+        let found = client
+            .find_controller(&second)
+            .await?
+            .expect("still connected");
+        assert_eq!(found.id(), 0);
+        assert_eq!(found.location(), "HID: /dev/hidraw2");
+        let found = client
+            .find_controller(&with_serial)
+            .await?
+            .expect("replugged");
+        assert_eq!(found.id(), 2);
+
+        server.remove_controller(0);
+        assert!(client.find_controller(&second).await?.is_none());
+        Ok(())
+    }
+}
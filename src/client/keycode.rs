@@ -0,0 +1,576 @@
+/// A key of a keyboard, to find its LED whatever the vendor named it.
+///
+/// OpenRGB names keyboard LEDs like `"Key: Left Control"`, but some drivers use other names,
+/// such as `"Key: Left Ctrl"` or `"Key: Esc"`. [`KeyCode::from_name`] understands the common variants,
+/// and [`crate::Controller::led_for_key`] finds the LED of a key on any keyboard.
+///
+/// The keys are named after their position on a US layout.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{Color, KeyCode, OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// let keyboard = client.get_controller(0).await?;
+///
+/// let mut cmd = keyboard.cmd();
+/// for key in [KeyCode::W, KeyCode::A, KeyCode::S, KeyCode::D] {
+///     cmd.set_key(key, Color::new(255, 0, 0))?;
+/// }
+/// cmd.execute().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum KeyCode {
+    /// Escape.
+    Escape,
+    /// F1.
+    F1,
+    /// F2.
+    F2,
+    /// F3.
+    F3,
+    /// F4.
+    F4,
+    /// F5.
+    F5,
+    /// F6.
+    F6,
+    /// F7.
+    F7,
+    /// F8.
+    F8,
+    /// F9.
+    F9,
+    /// F10.
+    F10,
+    /// F11.
+    F11,
+    /// F12.
+    F12,
+    /// Print Screen.
+    PrintScreen,
+    /// Scroll Lock.
+    ScrollLock,
+    /// Pause/Break.
+    Pause,
+    /// `` ` ``, left of `1`.
+    Backtick,
+    /// `1` in the number row.
+    Digit1,
+    /// `2` in the number row.
+    Digit2,
+    /// `3` in the number row.
+    Digit3,
+    /// `4` in the number row.
+    Digit4,
+    /// `5` in the number row.
+    Digit5,
+    /// `6` in the number row.
+    Digit6,
+    /// `7` in the number row.
+    Digit7,
+    /// `8` in the number row.
+    Digit8,
+    /// `9` in the number row.
+    Digit9,
+    /// `0` in the number row.
+    Digit0,
+    /// `-`.
+    Minus,
+    /// `=`.
+    Equals,
+    /// Backspace.
+    Backspace,
+    /// Tab.
+    Tab,
+    /// A.
+    A,
+    /// B.
+    B,
+    /// C.
+    C,
+    /// D.
+    D,
+    /// E.
+    E,
+    /// F.
+    F,
+    /// G.
+    G,
+    /// H.
+    H,
+    /// I.
+    I,
+    /// J.
+    J,
+    /// K.
+    K,
+    /// L.
+    L,
+    /// M.
+    M,
+    /// N.
+    N,
+    /// O.
+    O,
+    /// P.
+    P,
+    /// Q.
+    Q,
+    /// R.
+    R,
+    /// S.
+    S,
+    /// T.
+    T,
+    /// U.
+    U,
+    /// V.
+    V,
+    /// W.
+    W,
+    /// X.
+    X,
+    /// Y.
+    Y,
+    /// Z.
+    Z,
+    /// `[`.
+    LeftBracket,
+    /// `]`.
+    RightBracket,
+    /// `\`, above Enter on ANSI layouts.
+    Backslash,
+    /// Caps Lock.
+    CapsLock,
+    /// `;`.
+    Semicolon,
+    /// `'`.
+    Quote,
+    /// Enter.
+    Enter,
+    /// Left Shift.
+    LeftShift,
+    /// `,`.
+    Comma,
+    /// `.`.
+    Period,
+    /// `/`.
+    Slash,
+    /// Right Shift.
+    RightShift,
+    /// Left Control.
+    LeftControl,
+    /// Left Windows, also called Super or Command.
+    LeftWindows,
+    /// Left Alt.
+    LeftAlt,
+    /// Space.
+    Space,
+    /// Right Alt, also called AltGr.
+    RightAlt,
+    /// Fn, usually right of the space bar.
+    RightFn,
+    /// Right Windows.
+    RightWindows,
+    /// Menu, also called Application.
+    Menu,
+    /// Right Control.
+    RightControl,
+    /// Insert.
+    Insert,
+    /// Home.
+    Home,
+    /// Page Up.
+    PageUp,
+    /// Delete.
+    Delete,
+    /// End.
+    End,
+    /// Page Down.
+    PageDown,
+    /// Up Arrow.
+    Up,
+    /// Left Arrow.
+    Left,
+    /// Down Arrow.
+    Down,
+    /// Right Arrow.
+    Right,
+    /// Num Lock.
+    NumLock,
+    /// `/` on the number pad.
+    NumpadDivide,
+    /// `*` on the number pad.
+    NumpadMultiply,
+    /// `-` on the number pad.
+    NumpadMinus,
+    /// `+` on the number pad.
+    NumpadPlus,
+    /// Enter on the number pad.
+    NumpadEnter,
+    /// `1` on the number pad.
+    Numpad1,
+    /// `2` on the number pad.
+    Numpad2,
+    /// `3` on the number pad.
+    Numpad3,
+    /// `4` on the number pad.
+    Numpad4,
+    /// `5` on the number pad.
+    Numpad5,
+    /// `6` on the number pad.
+    Numpad6,
+    /// `7` on the number pad.
+    Numpad7,
+    /// `8` on the number pad.
+    Numpad8,
+    /// `9` on the number pad.
+    Numpad9,
+    /// `0` on the number pad.
+    Numpad0,
+    /// `.` on the number pad.
+    NumpadPeriod,
+}
+
+impl KeyCode {
+    /// Returns the name OpenRGB gives the LED of this key, such as `"Key: Left Control"`.
+    pub fn name(self) -> &'static str {
+        KEYS.iter()
+            .chain(NUMPAD_KEYS)
+            .find(|(key, _, _)| *key == self)
+            .map(|(_, name, _)| *name)
+            .expect("every key has a name")
+    }
+
+    /// Returns the key for an LED name, such as `"Key: Left Ctrl"`, `"left control"` or `"Esc"`.
+    ///
+    /// Case, whitespace, the `"Key:"` prefix and notes in parentheses such as `"(ANSI)"` are ignored.
+    /// Number pad keys may start with `"Number Pad"`, `"Numpad"`, `"Keypad"`, `"Num"` or `"KP"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = normalize(name);
+        let numpad = NUMPAD_PREFIXES
+            .iter()
+            .filter_map(|prefix| name.strip_prefix(prefix))
+            .find_map(|rest| find(NUMPAD_KEYS, rest));
+        numpad.or_else(|| find(KEYS, &name))
+    }
+}
+
+impl std::fmt::Display for KeyCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Returns `name` in lowercase without whitespace, the `"key:"` prefix and notes in parentheses.
+pub(crate) fn normalize(name: &str) -> String {
+    let name = name.trim();
+    let name = match name.get(..4) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("key:") => &name[4..],
+        _ => name,
+    };
+    let mut depth = 0_usize;
+    let without_notes = name
+        .chars()
+        .filter(|c| match c {
+            '(' => {
+                depth += 1;
+                false
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            _ => depth == 0,
+        })
+        .collect::<String>();
+    // keep keys like "(" that are nothing but a note
+    let name = match without_notes.trim().is_empty() {
+        true => name,
+        false => &without_notes,
+    };
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn find(keys: &[(KeyCode, &str, &[&str])], name: &str) -> Option<KeyCode> {
+    keys.iter()
+        .find(|(_, _, aliases)| aliases.contains(&name))
+        .map(|(key, _, _)| *key)
+}
+
+const NUMPAD_PREFIXES: &[&str] = &["numberpad", "numpad", "keypad", "num", "kp"];
+
+/// Keys with their OpenRGB name and their normalized names.
+const KEYS: &[(KeyCode, &str, &[&str])] = &[
+    (KeyCode::Escape, "Key: Escape", &["escape", "esc"]),
+    (KeyCode::F1, "Key: F1", &["f1"]),
+    (KeyCode::F2, "Key: F2", &["f2"]),
+    (KeyCode::F3, "Key: F3", &["f3"]),
+    (KeyCode::F4, "Key: F4", &["f4"]),
+    (KeyCode::F5, "Key: F5", &["f5"]),
+    (KeyCode::F6, "Key: F6", &["f6"]),
+    (KeyCode::F7, "Key: F7", &["f7"]),
+    (KeyCode::F8, "Key: F8", &["f8"]),
+    (KeyCode::F9, "Key: F9", &["f9"]),
+    (KeyCode::F10, "Key: F10", &["f10"]),
+    (KeyCode::F11, "Key: F11", &["f11"]),
+    (KeyCode::F12, "Key: F12", &["f12"]),
+    (
+        KeyCode::PrintScreen,
+        "Key: Print Screen",
+        &["printscreen", "prtsc", "prtscn", "prtscr", "printscr"],
+    ),
+    (
+        KeyCode::ScrollLock,
+        "Key: Scroll Lock",
+        &["scrolllock", "scrlk", "scroll"],
+    ),
+    (
+        KeyCode::Pause,
+        "Key: Pause/Break",
+        &["pause/break", "pause", "break"],
+    ),
+    (
+        KeyCode::Backtick,
+        "Key: `",
+        &["`", "grave", "backtick", "tilde", "~"],
+    ),
+    (KeyCode::Digit1, "Key: 1", &["1"]),
+    (KeyCode::Digit2, "Key: 2", &["2"]),
+    (KeyCode::Digit3, "Key: 3", &["3"]),
+    (KeyCode::Digit4, "Key: 4", &["4"]),
+    (KeyCode::Digit5, "Key: 5", &["5"]),
+    (KeyCode::Digit6, "Key: 6", &["6"]),
+    (KeyCode::Digit7, "Key: 7", &["7"]),
+    (KeyCode::Digit8, "Key: 8", &["8"]),
+    (KeyCode::Digit9, "Key: 9", &["9"]),
+    (KeyCode::Digit0, "Key: 0", &["0"]),
+    (KeyCode::Minus, "Key: -", &["-", "minus"]),
+    (KeyCode::Equals, "Key: =", &["=", "equals", "equal"]),
+    (
+        KeyCode::Backspace,
+        "Key: Backspace",
+        &["backspace", "bksp", "back"],
+    ),
+    (KeyCode::Tab, "Key: Tab", &["tab"]),
+    (KeyCode::A, "Key: A", &["a"]),
+    (KeyCode::B, "Key: B", &["b"]),
+    (KeyCode::C, "Key: C", &["c"]),
+    (KeyCode::D, "Key: D", &["d"]),
+    (KeyCode::E, "Key: E", &["e"]),
+    (KeyCode::F, "Key: F", &["f"]),
+    (KeyCode::G, "Key: G", &["g"]),
+    (KeyCode::H, "Key: H", &["h"]),
+    (KeyCode::I, "Key: I", &["i"]),
+    (KeyCode::J, "Key: J", &["j"]),
+    (KeyCode::K, "Key: K", &["k"]),
+    (KeyCode::L, "Key: L", &["l"]),
+    (KeyCode::M, "Key: M", &["m"]),
+    (KeyCode::N, "Key: N", &["n"]),
+    (KeyCode::O, "Key: O", &["o"]),
+    (KeyCode::P, "Key: P", &["p"]),
+    (KeyCode::Q, "Key: Q", &["q"]),
+    (KeyCode::R, "Key: R", &["r"]),
+    (KeyCode::S, "Key: S", &["s"]),
+    (KeyCode::T, "Key: T", &["t"]),
+    (KeyCode::U, "Key: U", &["u"]),
+    (KeyCode::V, "Key: V", &["v"]),
+    (KeyCode::W, "Key: W", &["w"]),
+    (KeyCode::X, "Key: X", &["x"]),
+    (KeyCode::Y, "Key: Y", &["y"]),
+    (KeyCode::Z, "Key: Z", &["z"]),
+    (KeyCode::LeftBracket, "Key: [", &["[", "leftbracket"]),
+    (KeyCode::RightBracket, "Key: ]", &["]", "rightbracket"]),
+    (KeyCode::Backslash, "Key: \\ (ANSI)", &["\\", "backslash"]),
+    (KeyCode::CapsLock, "Key: Caps Lock", &["capslock", "caps"]),
+    (KeyCode::Semicolon, "Key: ;", &[";", "semicolon"]),
+    (KeyCode::Quote, "Key: '", &["'", "quote", "apostrophe"]),
+    (KeyCode::Enter, "Key: Enter", &["enter", "return"]),
+    (
+        KeyCode::LeftShift,
+        "Key: Left Shift",
+        &["leftshift", "lshift", "shift"],
+    ),
+    (KeyCode::Comma, "Key: ,", &[",", "comma"]),
+    (KeyCode::Period, "Key: .", &[".", "period", "dot"]),
+    (KeyCode::Slash, "Key: /", &["/", "slash"]),
+    (
+        KeyCode::RightShift,
+        "Key: Right Shift",
+        &["rightshift", "rshift"],
+    ),
+    (
+        KeyCode::LeftControl,
+        "Key: Left Control",
+        &["leftcontrol", "leftctrl", "lctrl", "control", "ctrl"],
+    ),
+    (
+        KeyCode::LeftWindows,
+        "Key: Left Windows",
+        &[
+            "leftwindows",
+            "leftwin",
+            "lwin",
+            "windows",
+            "win",
+            "leftsuper",
+            "super",
+            "leftgui",
+            "leftmeta",
+            "leftcommand",
+        ],
+    ),
+    (
+        KeyCode::LeftAlt,
+        "Key: Left Alt",
+        &["leftalt", "lalt", "alt"],
+    ),
+    (KeyCode::Space, "Key: Space", &["space", "spacebar"]),
+    (
+        KeyCode::RightAlt,
+        "Key: Right Alt",
+        &["rightalt", "ralt", "altgr"],
+    ),
+    (
+        KeyCode::RightFn,
+        "Key: Right Fn",
+        &["rightfn", "fn", "function"],
+    ),
+    (
+        KeyCode::RightWindows,
+        "Key: Right Windows",
+        &[
+            "rightwindows",
+            "rightwin",
+            "rwin",
+            "rightsuper",
+            "rightgui",
+            "rightmeta",
+        ],
+    ),
+    (
+        KeyCode::Menu,
+        "Key: Menu",
+        &["menu", "application", "apps", "contextmenu"],
+    ),
+    (
+        KeyCode::RightControl,
+        "Key: Right Control",
+        &["rightcontrol", "rightctrl", "rctrl"],
+    ),
+    (KeyCode::Insert, "Key: Insert", &["insert", "ins"]),
+    (KeyCode::Home, "Key: Home", &["home"]),
+    (KeyCode::PageUp, "Key: Page Up", &["pageup", "pgup"]),
+    (KeyCode::Delete, "Key: Delete", &["delete", "del"]),
+    (KeyCode::End, "Key: End", &["end"]),
+    (
+        KeyCode::PageDown,
+        "Key: Page Down",
+        &["pagedown", "pgdn", "pgdown"],
+    ),
+    (KeyCode::Up, "Key: Up Arrow", &["uparrow", "up", "arrowup"]),
+    (
+        KeyCode::Left,
+        "Key: Left Arrow",
+        &["leftarrow", "left", "arrowleft"],
+    ),
+    (
+        KeyCode::Down,
+        "Key: Down Arrow",
+        &["downarrow", "down", "arrowdown"],
+    ),
+    (
+        KeyCode::Right,
+        "Key: Right Arrow",
+        &["rightarrow", "right", "arrowright"],
+    ),
+    (KeyCode::NumLock, "Key: Num Lock", &["numlock"]),
+];
+
+/// Number pad keys with their OpenRGB name and their normalized names after the number pad prefix.
+const NUMPAD_KEYS: &[(KeyCode, &str, &[&str])] = &[
+    (
+        KeyCode::NumpadDivide,
+        "Key: Number Pad /",
+        &["/", "divide", "slash"],
+    ),
+    (
+        KeyCode::NumpadMultiply,
+        "Key: Number Pad *",
+        &["*", "multiply", "asterisk"],
+    ),
+    (
+        KeyCode::NumpadMinus,
+        "Key: Number Pad -",
+        &["-", "minus", "subtract"],
+    ),
+    (
+        KeyCode::NumpadPlus,
+        "Key: Number Pad +",
+        &["+", "plus", "add"],
+    ),
+    (
+        KeyCode::NumpadEnter,
+        "Key: Number Pad Enter",
+        &["enter", "return"],
+    ),
+    (KeyCode::Numpad1, "Key: Number Pad 1", &["1"]),
+    (KeyCode::Numpad2, "Key: Number Pad 2", &["2"]),
+    (KeyCode::Numpad3, "Key: Number Pad 3", &["3"]),
+    (KeyCode::Numpad4, "Key: Number Pad 4", &["4"]),
+    (KeyCode::Numpad5, "Key: Number Pad 5", &["5"]),
+    (KeyCode::Numpad6, "Key: Number Pad 6", &["6"]),
+    (KeyCode::Numpad7, "Key: Number Pad 7", &["7"]),
+    (KeyCode::Numpad8, "Key: Number Pad 8", &["8"]),
+    (KeyCode::Numpad9, "Key: Number Pad 9", &["9"]),
+    (KeyCode::Numpad0, "Key: Number Pad 0", &["0"]),
+    (
+        KeyCode::NumpadPeriod,
+        "Key: Number Pad .",
+        &[".", "period", "decimal", "del", "delete"],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(
+            KeyCode::from_name("Key: Left Control"),
+            Some(KeyCode::LeftControl)
+        );
+        assert_eq!(
+            KeyCode::from_name("key: left ctrl"),
+            Some(KeyCode::LeftControl)
+        );
+        assert_eq!(KeyCode::from_name("Esc"), Some(KeyCode::Escape));
+        assert_eq!(KeyCode::from_name("Key: Enter (ISO)"), Some(KeyCode::Enter));
+        assert_eq!(
+            KeyCode::from_name("Key: Number Pad 1"),
+            Some(KeyCode::Numpad1)
+        );
+        assert_eq!(
+            KeyCode::from_name("Key: KP Enter"),
+            Some(KeyCode::NumpadEnter)
+        );
+        assert_eq!(KeyCode::from_name("Key: Num Lock"), Some(KeyCode::NumLock));
+        assert_eq!(KeyCode::from_name("Key: 1"), Some(KeyCode::Digit1));
+        assert_eq!(KeyCode::from_name("Logo"), None);
+        for (key, name, _) in KEYS.iter().chain(NUMPAD_KEYS) {
+            assert_eq!(KeyCode::from_name(name), Some(*key), "{name}");
+        }
+    }
+}
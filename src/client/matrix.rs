@@ -0,0 +1,296 @@
+use array2d::Array2D;
+
+use crate::{Color, Command, Controller, OpenRgbError, OpenRgbResult, data::ZoneData};
+
+/// A pixel buffer for a [`crate::ZoneType::Matrix`] zone, such as the keys of a keyboard.
+///
+/// Pixels are addressed by column `x` and row `y` of the zone matrix and mapped to the LEDs of the zone.
+/// Positions without an LED, such as the gaps around the space bar, are ignored when drawing.
+/// Drawing only changes the buffer, the frame is sent with [`Self::execute`],
+/// or added to a [`Command`] with [`Self::add_to`], as one update of the controller.
+///
+/// Created with [`crate::Zone::matrix_view`], starting with all pixels black.
+/// The colors a controller reports are already calibrated and dimmed,
+/// so they are not used as a start, as sending them again would apply that twice.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{Color, OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// let keyboard = client.get_controller(0).await?;
+/// let zone = keyboard.get_zone(0)?;
+/// let mut view = zone.matrix_view()?;
+///
+/// view.fill(Color::new(0, 0, 0));
+/// // top left key and a bar below it
+/// view.set_pixel(0, 0, Color::new(255, 0, 0))?;
+/// view.fill_rect(0, 1, view.width(), 1, Color::new(0, 0, 255));
+/// view.execute().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MatrixView<'a> {
+    controller: &'a Controller,
+    zone: &'a ZoneData,
+    matrix: &'a Array2D<u32>,
+    offset: usize,
+    colors: Vec<Color>,
+}
+
+impl<'a> MatrixView<'a> {
+    pub(crate) fn new(controller: &'a Controller, zone: &'a ZoneData) -> OpenRgbResult<Self> {
+        let Some(matrix) = zone.matrix() else {
+            return Err(OpenRgbError::CommandError(format!(
+                "Zone {} of controller {} has no matrix",
+                zone.name(),
+                controller.name()
+            )));
+        };
+        let offset = controller.get_zone_led_offset(zone.id())?;
+        Ok(Self {
+            controller,
+            zone,
+            matrix,
+            offset,
+            colors: vec![Color::default(); zone.leds_count()],
+        })
+    }
+
+    /// Returns the ID of the zone.
+    pub fn zone_id(&self) -> usize {
+        self.zone.id()
+    }
+
+    /// Returns the number of columns of the matrix.
+    pub fn width(&self) -> usize {
+        self.matrix.num_columns()
+    }
+
+    /// Returns the number of rows of the matrix.
+    pub fn height(&self) -> usize {
+        self.matrix.num_rows()
+    }
+
+    /// Returns the index in the zone of the LED at `x`, `y`, `None` if there is no LED.
+    pub fn zone_led(&self, x: usize, y: usize) -> Option<usize> {
+        let led = *self.matrix.get(y, x)? as usize;
+        (led < self.colors.len()).then_some(led)
+    }
+
+    /// Returns the index in the controller of the LED at `x`, `y`, `None` if there is no LED.
+    ///
+    /// This is the index for [`Controller::set_led`] and [`Command::set_led`].
+    pub fn led(&self, x: usize, y: usize) -> Option<usize> {
+        self.zone_led(x, y).map(|led| self.offset + led)
+    }
+
+    /// Returns the color of the pixel at `x`, `y`, `None` if there is no LED.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+        self.zone_led(x, y).map(|led| self.colors[led])
+    }
+
+    /// Sets the pixel at `x`, `y` to `color`. Nothing happens if there is no LED at that position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is outside the matrix.
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: impl Into<Color>) -> OpenRgbResult<()> {
+        if x >= self.width() || y >= self.height() {
+            return Err(OpenRgbError::CommandError(format!(
+                "Pixel ({x}, {y}) out of bounds for zone {} with {}x{} matrix",
+                self.zone.name(),
+                self.width(),
+                self.height()
+            )));
+        }
+        if let Some(led) = self.zone_led(x, y) {
+            self.colors[led] = color.into();
+        }
+        Ok(())
+    }
+
+    /// Sets all pixels to `color`.
+    pub fn fill(&mut self, color: impl Into<Color>) {
+        self.colors.fill(color.into());
+    }
+
+    /// Sets the pixels of the rectangle with its top left corner at `x`, `y` to `color`.
+    ///
+    /// The part of the rectangle outside the matrix is skipped.
+    pub fn fill_rect(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        color: impl Into<Color>,
+    ) {
+        let color = color.into();
+        let columns = x..x.saturating_add(width).min(self.width());
+        for row in y..y.saturating_add(height).min(self.height()) {
+            for column in columns.clone() {
+                if let Some(led) = self.zone_led(column, row) {
+                    self.colors[led] = color;
+                }
+            }
+        }
+    }
+
+    /// Returns the pixels of row `y` from left to right, `None` where there is no LED.
+    ///
+    /// Empty if `y` is outside the matrix.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = Option<Color>> + '_ {
+        let columns = if y < self.height() { self.width() } else { 0 };
+        (0..columns).map(move |x| self.pixel(x, y))
+    }
+
+    /// Returns the pixels of column `x` from top to bottom, `None` where there is no LED.
+    ///
+    /// Empty if `x` is outside the matrix.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = Option<Color>> + '_ {
+        let rows = if x < self.width() { self.height() } else { 0 };
+        (0..rows).map(move |y| self.pixel(x, y))
+    }
+
+    /// Returns the rows of the matrix from top to bottom, see [`Self::row`].
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Option<Color>> + '_> + '_ {
+        (0..self.height()).map(|y| self.row(y))
+    }
+
+    /// Returns the columns of the matrix from left to right, see [`Self::column`].
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Option<Color>> + '_> + '_ {
+        (0..self.width()).map(|x| self.column(x))
+    }
+
+    /// Returns the colors of the LEDs of the zone, in the order of the zone.
+    ///
+    /// Useful to draw on several controllers with a [`crate::CommandGroup`].
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    pub(crate) fn colors_mut(&mut self) -> &mut [Color] {
+        &mut self.colors
+    }
+
+    /// Adds the pixels to `cmd`, which must be a command for the controller of the zone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the zone is not found in the controller of `cmd`.
+    pub fn add_to(&self, cmd: &mut Command<'_>) -> OpenRgbResult<()> {
+        cmd.set_zone_leds(self.zone_id(), self.colors.iter().copied())
+    }
+
+    /// Returns a command that updates the zone to the pixels.
+    ///
+    /// Other LEDs of the controller can be added to the command before executing it.
+    pub fn cmd(&self) -> OpenRgbResult<Command<'a>> {
+        let mut cmd = self.controller.cmd();
+        self.add_to(&mut cmd)?;
+        Ok(cmd)
+    }
+
+    /// Sends the pixels to the controller, as a single update of its LEDs.
+    pub async fn execute(&self) -> OpenRgbResult<()> {
+        self.cmd()?.execute().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DeviceType;
+    use crate::mock::{MockController, MockServer, MockZone, RecordedPacket};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_matrix_view() -> OpenRgbResult<()> {
+        // 3x2 keyboard with a gap at the bottom right, LEDs numbered column by column
+        let keys = Array2D::from_row_major(&[0, 2, 4, 1, 3, u32::MAX], 2, 3).unwrap();
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Keyboard", DeviceType::Keyboard)
+                    .with_zone(MockZone::linear("Logo", 1))
+                    .with_zone(MockZone::matrix("Keys", 1, 1).with_matrix(keys)),
+            )
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let keyboard = client.get_controller(0).await?;
+        assert!(keyboard.get_zone(0)?.matrix_view().is_err());
+        let zone = keyboard.get_zone(1)?;
+        let mut view = zone.matrix_view()?;
+        assert_eq!((view.width(), view.height()), (3, 2));
+        assert_eq!((view.led(1, 1), view.led(2, 1)), (Some(4), None));
+
+        let (red, blue, black) = (
+            Color::new(255, 0, 0),
+            Color::new(0, 0, 255),
+            Color::default(),
+        );
+        view.fill_rect(1, 0, 5, 5, red);
+        view.set_pixel(0, 1, blue)?;
+        view.set_pixel(2, 1, blue)?;
+        assert!(view.set_pixel(3, 0, blue).is_err());
+        assert_eq!(
+            view.rows().map(Iterator::collect).collect::<Vec<Vec<_>>>(),
+            [
+                vec![Some(black), Some(red), Some(red)],
+                vec![Some(blue), Some(red), None]
+            ]
+        );
+        assert_eq!(
+            view.column(0).collect::<Vec<_>>(),
+            [Some(black), Some(blue)]
+        );
+        assert_eq!(view.row(2).count(), 0);
+
+        view.execute().await?;
+        client.get_controller(0).await?;
+        assert_eq!(
+            server.received(),
+            [RecordedPacket::UpdateLeds {
+                controller_id: 0,
+                colors: vec![black, black, blue, red, red, red],
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_matrix_view_dimmed() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Keyboard", DeviceType::Keyboard)
+                    .with_zone(MockZone::matrix("Keys", 2, 1)),
+            )
+            .start()
+            .await?;
+        let client = server.client().await?;
+        client.set_brightness(0.5);
+        let red = Color::new(255, 0, 0);
+        client.get_controller(0).await?.set_leds([red, red]).await?;
+
+        // the synced colors are dimmed already and must not be dimmed again
+        let keyboard = client.get_controller(0).await?;
+        assert_eq!(keyboard.colors(), [Color::new(128, 0, 0); 2]);
+        let zone = keyboard.get_zone(0)?;
+        let mut view = zone.matrix_view()?;
+        assert_eq!(view.colors(), [Color::default(); 2]);
+        view.set_pixel(0, 0, red)?;
+        view.execute().await?;
+        view.execute().await?;
+        client.get_controller(0).await?;
+        assert_eq!(
+            server.colors(0),
+            Some(vec![Color::new(128, 0, 0), Color::default()])
+        );
+        Ok(())
+    }
+}
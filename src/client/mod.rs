@@ -1,20 +1,42 @@
 //! Wrapper around the OpenRGB client to make it friendlier to use.
 
+mod brightness;
+mod builder;
+mod calibration;
 mod command;
 mod controller;
+mod event;
 mod group;
+mod info;
+mod key;
+mod keycode;
+mod matrix;
+mod mode;
+mod model;
+mod profile;
+mod reconnect;
 mod segment;
+mod snapshot;
+mod timeout;
 mod zone;
 
-pub use {command::*, controller::*, group::*, segment::*, zone::*};
+pub use {
+    brightness::*, builder::*, calibration::*, command::*, controller::*, event::*, group::*,
+    info::*, key::*, keycode::*, matrix::*, mode::*, model::*, profile::*, reconnect::*,
+    segment::*, snapshot::*, timeout::*, zone::*,
+};
 
-use tokio::net::ToSocketAddrs;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::ToSocketAddrs,
+    sync::broadcast,
+};
 
 use crate::{
     DEFAULT_PROTOCOL, OpenRgbError, PluginData,
     data::DeviceType,
     error::OpenRgbResult,
-    protocol::{DEFAULT_ADDR, OpenRgbProtocol},
+    protocol::{DEFAULT_ADDR, OpenRgbProtocol, connector},
 };
 
 /// Client for the `OpenRGB` SDK server that provides methods to interact with `OpenRGB`.
@@ -29,9 +51,17 @@ pub struct OpenRgbClient {
 }
 
 impl OpenRgbClient {
+    /// Returns a builder to configure the address, client name, timeouts and more before connecting.
+    ///
+    /// See [`OpenRgbClientBuilder`].
+    pub fn builder() -> OpenRgbClientBuilder {
+        OpenRgbClientBuilder::default()
+    }
+
     /// Connect to default `OpenRGB` server.
     ///
-    /// Use [`OpenRgbClient::connect_to`] to connect to a specific server.
+    /// Use [`OpenRgbClient::connect_to`] to connect to a specific server,
+    /// or [`OpenRgbClient::builder`] to configure the connection.
     ///
     /// # Example
     ///
@@ -74,21 +104,215 @@ impl OpenRgbClient {
         let client = OpenRgbProtocol::connect_to(addr, protocol_version).await?;
         Ok(Self { proto: client })
     }
+
+    /// Connect to `OpenRGB` server listening on a Unix domain socket, for example one forwarded over SSH.
+    ///
+    /// Reconnecting opens the socket at `path` again.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use openrgb2::{OpenRgbClient, OpenRgbResult};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> OpenRgbResult<()> {
+    /// // forwarded with `ssh -L /tmp/openrgb.sock:localhost:6742 host`
+    /// let client = OpenRgbClient::connect_unix("/tmp/openrgb.sock").await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    pub async fn connect_unix(path: impl AsRef<std::path::Path>) -> OpenRgbResult<Self> {
+        let client = OpenRgbProtocol::connect_unix(path, DEFAULT_PROTOCOL).await?;
+        Ok(Self { proto: client })
+    }
+
+    /// Use an already connected stream to talk to the `OpenRGB` server.
+    ///
+    /// Any stream works, such as a [`tokio::io::duplex`] pipe in tests.
+    /// Since the stream cannot be reopened, reconnecting always fails,
+    /// use [`OpenRgbClient::connect_with`] if the client should be able to reconnect.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use openrgb2::{OpenRgbClient, OpenRgbResult};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> OpenRgbResult<()> {
+    /// let stream = tokio::net::TcpStream::connect("127.0.0.1:6742").await?;
+    /// let client = OpenRgbClient::from_stream(stream).await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_stream(
+        stream: impl AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    ) -> OpenRgbResult<Self> {
+        let client = OpenRgbProtocol::from_stream(stream, DEFAULT_PROTOCOL).await?;
+        Ok(Self { proto: client })
+    }
+
+    /// Connect to `OpenRGB` server using streams opened by `connect`.
+    ///
+    /// `connect` is called once right away, and again every time the client reconnects.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use openrgb2::{OpenRgbClient, OpenRgbResult};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> OpenRgbResult<()> {
+    /// let client = OpenRgbClient::connect_with(|| async {
+    ///     let stream = tokio::net::TcpStream::connect("127.0.0.1:6742").await?;
+    ///     stream.set_nodelay(true)?;
+    ///     Ok(stream)
+    /// })
+    /// .await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_with<F, Fut, S>(connect: F) -> OpenRgbResult<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::io::Result<S>> + Send + 'static,
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let client = OpenRgbProtocol::new(
+            "custom connector".to_owned(),
+            connector(connect),
+            DEFAULT_PROTOCOL,
+            Timeouts::default(),
+        )
+        .await?;
+        Ok(Self { proto: client })
+    }
 }
 
 impl OpenRgbClient {
+    /// Subscribes to events pushed by the `OpenRGB` server, such as [`ServerEvent::DeviceListUpdated`].
+    ///
+    /// Only events sent after subscribing are received.
+    /// A receiver that falls too far behind skips the oldest events, see [`broadcast::Receiver::recv()`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use openrgb2::{OpenRgbClient, OpenRgbResult, ServerEvent};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> OpenRgbResult<()> {
+    /// let client = OpenRgbClient::connect().await?;
+    /// let mut events = client.events();
+    /// while let Ok(event) = events.recv().await {
+    ///     if event == ServerEvent::DeviceListUpdated {
+    ///         let controllers = client.get_all_controllers().await?;
+    ///         println!("{} controllers connected", controllers.len());
+    ///     }
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn events(&self) -> broadcast::Receiver<ServerEvent> {
+        self.proto.subscribe()
+    }
+
+    /// Returns a [`DeviceModel`] with snapshots of all controllers that are kept up to date.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if communication with the `OpenRGB` SDK server fails.
+    pub async fn device_model(&self) -> OpenRgbResult<DeviceModel> {
+        DeviceModel::new(self.proto.clone()).await
+    }
+
+    /// Sets how this client reconnects after losing its connection to the `OpenRGB` server,
+    /// for example because the server was restarted. `None` disables reconnecting, which is the default.
+    ///
+    /// The policy applies to this client and every [`Controller`] obtained from it.
+    /// While reconnecting, calls wait until the connection is back or the policy gives up.
+    /// A call that failed because the connection was lost is sent again once, after reconnecting.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use openrgb2::{OpenRgbClient, OpenRgbResult, ReconnectPolicy};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> OpenRgbResult<()> {
+    /// let client = OpenRgbClient::connect().await?;
+    /// client.set_reconnect_policy(Some(ReconnectPolicy::default().with_restore_state(true)));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_reconnect_policy(&self, policy: Option<ReconnectPolicy>) {
+        self.proto.set_reconnect_policy(policy);
+    }
+
+    /// Sets deadlines for reading and writing, and for connecting when reconnecting.
+    ///
+    /// The timeouts apply to this client and every [`Controller`] obtained from it.
+    /// A call that times out fails with [`OpenRgbError::Timeout`] and closes the connection,
+    /// so the next call reconnects if a [`ReconnectPolicy`] is set, or fails otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use openrgb2::{OpenRgbClient, OpenRgbResult, Timeouts};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> OpenRgbResult<()> {
+    /// let client = OpenRgbClient::connect().await?;
+    /// client.set_timeouts(Timeouts::default().with_read(Duration::from_secs(5)));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_timeouts(&self, timeouts: Timeouts) {
+        self.proto.set_timeouts(timeouts);
+    }
+
+    /// Sets the calibrations applied to the colors sent to each device, replacing the previous ones.
+    ///
+    /// They apply to this client and every [`Controller`] obtained from it,
+    /// whenever LEDs are set through a controller, zone, segment or command.
+    /// See [`Calibrations`] to load them from a file.
+    pub fn set_calibrations(&self, calibrations: Calibrations) {
+        self.proto.set_calibrations(calibrations);
+    }
+
+    /// Sets the brightness of all devices, see [`Brightness`].
+    ///
+    /// It applies to this client and every [`Controller`] obtained from it,
+    /// multiplied with the brightness of the controller, see [`Controller::set_brightness`].
+    /// Colors that were already sent are not changed, so set the LEDs again to dim them.
+    pub fn set_brightness(&self, brightness: impl Into<Brightness>) {
+        self.proto.set_brightness(brightness.into());
+    }
+
+    /// Returns the brightness of all devices.
+    pub fn brightness(&self) -> Brightness {
+        self.proto.brightness()
+    }
+
     /// Returns all available `OpenRGB` controllers as a `ControllerGroup`.
     ///
     /// # Errors
     ///
     /// This function returns an error if communication with the `OpenRGB` SDK server fails.
     pub async fn get_all_controllers(&self) -> OpenRgbResult<ControllerGroup> {
-        let count = self.proto.get_controller_count().await? as usize;
-        let mut controllers = Vec::with_capacity(count);
-        for id in 0..count {
-            let controller = self.get_controller(id).await?;
-            controllers.push(controller);
-        }
+        let count = self.proto.get_controller_count().await?;
+        let controllers = self
+            .proto
+            .get_controllers(0..count)
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(id, data)| Controller::new(id, self.proto.clone(), data))
+            .collect();
         Ok(ControllerGroup::new(controllers))
     }
 
@@ -121,6 +345,19 @@ impl OpenRgbClient {
         let c_data = self.proto.get_controller(i as u32).await?;
         Ok(Controller::new(i, self.proto.clone(), c_data))
     }
+
+    /// Finds the controller identified by `key`, with its current index as [`Controller::id()`].
+    ///
+    /// Returns `None` if no connected device matches, see [`ControllerKey`] for how devices are matched.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if communication with the `OpenRGB` SDK server fails.
+    pub async fn find_controller(&self, key: &ControllerKey) -> OpenRgbResult<Option<Controller>> {
+        let group = self.get_all_controllers().await?;
+        let id = group.find(key).map(Controller::id);
+        Ok(id.and_then(|id| group.into_iter().find(|c| c.id() == id)))
+    }
 }
 
 // delegation if it would exist
@@ -132,7 +369,7 @@ impl OpenRgbClient {
 
     /// Sets the name for this client's connection.
     ///
-    /// This is viewable in the `OpenRGB` SDK server tab
+    /// This is viewable in the `OpenRGB` SDK server tab. The name is sent again after reconnecting.
     pub async fn set_name(&mut self, name: impl Into<String>) -> OpenRgbResult<()> {
         self.proto.set_name(name).await
     }
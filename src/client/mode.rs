@@ -0,0 +1,275 @@
+use flagset::FlagSet;
+
+use crate::{
+    Color, ColorMode, Controller, Direction, ModeData, ModeError, ModeFlag, ModeSetting,
+    OpenRgbResult,
+};
+
+/// Changes the settings of a mode before applying it, returned by [`Controller::mode()`].
+///
+/// Each change is checked against the flags and ranges the controller reports for the mode,
+/// so unsupported settings fail right away with a [`ModeError`] instead of being ignored by the device.
+/// Nothing is sent until [`Self::apply()`] is called.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{Color, Direction, OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// let controller = client.get_controller(0).await?;
+///
+/// let mut wave = controller.mode("Wave")?;
+/// wave.set_speed(3)?;
+/// wave.set_direction(Direction::Right)?;
+/// wave.set_colors([Color::new(255, 0, 0), Color::new(0, 0, 255)])?;
+/// wave.apply().await?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ModeEditor<'a> {
+    controller: &'a Controller,
+    mode: ModeData,
+}
+
+impl<'a> ModeEditor<'a> {
+    pub(crate) fn new(controller: &'a Controller, mode: ModeData) -> Self {
+        Self { controller, mode }
+    }
+
+    /// Returns the mode with the changes made so far.
+    pub fn mode(&self) -> &ModeData {
+        &self.mode
+    }
+
+    /// Sets the speed, which must be within [`ModeData::speed_min()`] and [`ModeData::speed_max()`].
+    pub fn set_speed(&mut self, speed: u32) -> Result<(), ModeError> {
+        self.require(ModeFlag::HasSpeed, ModeSetting::Speed)?;
+        let (min, max) = (self.mode.speed_min, self.mode.speed_max);
+        self.check_range(ModeSetting::Speed, speed, min, max)?;
+        self.mode.speed = speed;
+        Ok(())
+    }
+
+    /// Sets the brightness, which must be within [`ModeData::brightness_min()`] and [`ModeData::brightness_max()`].
+    ///
+    /// Brightness needs protocol version 3 or newer.
+    pub fn set_brightness(&mut self, brightness: u32) -> Result<(), ModeError> {
+        self.require(ModeFlag::HasBrightness, ModeSetting::Brightness)?;
+        let (Some(&min), Some(&max)) = (
+            self.mode.brightness_min.value(),
+            self.mode.brightness_max.value(),
+        ) else {
+            return Err(self.unsupported(ModeSetting::Brightness));
+        };
+        self.check_range(ModeSetting::Brightness, brightness, min, max)?;
+        self.mode.brightness.replace(brightness);
+        Ok(())
+    }
+
+    /// Sets the direction, which the mode must support with [`ModeFlag::HasDirectionLR`],
+    /// [`ModeFlag::HasDirectionUD`] or [`ModeFlag::HasDirectionHV`].
+    pub fn set_direction(&mut self, direction: Direction) -> Result<(), ModeError> {
+        let flag = match direction {
+            Direction::Left | Direction::Right => ModeFlag::HasDirectionLR,
+            Direction::Up | Direction::Down => ModeFlag::HasDirectionUD,
+            Direction::Horizontal | Direction::Vertical => ModeFlag::HasDirectionHV,
+        };
+        if !self.mode.flags.contains(flag) {
+            return Err(ModeError::UnsupportedDirection {
+                mode: self.mode.name.clone(),
+                direction,
+            });
+        }
+        self.mode.direction = direction;
+        Ok(())
+    }
+
+    /// Sets the mode specific colors, and switches to [`ColorMode::ModeSpecific`].
+    ///
+    /// The mode must have [`ModeFlag::HasModeSpecificColor`],
+    /// and the number of colors must be within [`ModeData::colors_min()`] and [`ModeData::colors_max()`].
+    pub fn set_colors<C: Into<Color>>(
+        &mut self,
+        colors: impl IntoIterator<Item = C>,
+    ) -> Result<(), ModeError> {
+        self.require(ModeFlag::HasModeSpecificColor, ModeSetting::Colors)?;
+        let colors = colors.into_iter().map(Into::into).collect::<Vec<_>>();
+        let (min, max) = (self.mode.colors_min, self.mode.colors_max);
+        self.check_range(ModeSetting::Colors, colors.len() as u32, min, max)?;
+        self.mode.colors = colors;
+        self.mode.color_mode = ColorMode::ModeSpecific;
+        Ok(())
+    }
+
+    /// Sets how the mode picks its colors, which must be supported by the mode flags:
+    /// - [`ColorMode::PerLED`] needs [`ModeFlag::HasPerLEDColor`],
+    /// - [`ColorMode::ModeSpecific`] needs [`ModeFlag::HasModeSpecificColor`],
+    /// - [`ColorMode::Random`] needs [`ModeFlag::HasRandomColor`].
+    pub fn set_color_mode(&mut self, color_mode: ColorMode) -> Result<(), ModeError> {
+        let flag: Option<FlagSet<ModeFlag>> = match color_mode {
+            ColorMode::None => None,
+            ColorMode::PerLED => Some(ModeFlag::HasPerLEDColor.into()),
+            ColorMode::ModeSpecific => Some(ModeFlag::HasModeSpecificColor.into()),
+            ColorMode::Random => Some(ModeFlag::HasRandomColor.into()),
+        };
+        if flag.is_some_and(|flag| !self.mode.flags.contains(flag)) {
+            return Err(ModeError::UnsupportedColorMode {
+                mode: self.mode.name.clone(),
+                color_mode,
+            });
+        }
+        self.mode.color_mode = color_mode;
+        Ok(())
+    }
+
+    /// Sends the mode to the controller, which makes it the active mode.
+    pub async fn apply(self) -> OpenRgbResult<()> {
+        self.controller.set_mode(&self.mode).await
+    }
+
+    fn require(&self, flag: ModeFlag, setting: ModeSetting) -> Result<(), ModeError> {
+        match self.mode.flags.contains(flag) {
+            true => Ok(()),
+            false => Err(self.unsupported(setting)),
+        }
+    }
+
+    fn unsupported(&self, setting: ModeSetting) -> ModeError {
+        ModeError::Unsupported {
+            mode: self.mode.name.clone(),
+            setting,
+        }
+    }
+
+    /// Checks that `value` is between `min` and `max`, which some devices report the wrong way around.
+    fn check_range(
+        &self,
+        setting: ModeSetting,
+        value: u32,
+        min: u32,
+        max: u32,
+    ) -> Result<(), ModeError> {
+        let (min, max) = (min.min(max), min.max(max));
+        if (min..=max).contains(&value) {
+            return Ok(());
+        }
+        Err(ModeError::OutOfRange {
+            mode: self.mode.name.clone(),
+            setting,
+            value,
+            min,
+            max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockMode, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    fn keyboard() -> MockController {
+        MockController::new("Keyboard", DeviceType::Keyboard)
+            .with_mode(MockMode::direct())
+            .with_mode(
+                MockMode::new("Wave")
+                    .with_direction(Direction::Left)
+                    .with_speed(4, 0, 2)
+                    .with_brightness(0, 100, 100)
+                    .with_colors(1, 2, [Color::new(255, 0, 0)]),
+            )
+            .with_zone(MockZone::linear("Keys", 4))
+    }
+
+    #[tokio::test]
+    async fn test_mode_editor() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(keyboard())
+            .start()
+            .await?;
+        let mut client = server.client().await?;
+        let controller = client.get_controller(0).await?;
+
+        let mut wave = controller.mode("wave")?;
+        // inverted range, as some devices report for speed
+        wave.set_speed(3)?;
+        wave.set_brightness(40)?;
+        wave.set_direction(Direction::Right)?;
+        wave.set_colors([Color::new(0, 255, 0), Color::new(0, 0, 255)])?;
+        wave.apply().await?;
+
+        // a request makes sure the server handled the update
+        client.get_controller_count().await?;
+        let active = server.active_mode(0).expect("controller exists");
+        assert_eq!(active.name(), "Wave");
+        assert_eq!(active.speed(), Some(3));
+        assert_eq!(active.brightness(), Some(40));
+        assert_eq!(active.brightness_min(), Some(0));
+        assert_eq!(active.brightness_max(), Some(100));
+        assert_eq!(active.direction(), Some(Direction::Right));
+        assert_eq!(
+            active.colors(),
+            [Color::new(0, 255, 0), Color::new(0, 0, 255)]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mode_editor_errors() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(keyboard())
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let controller = client.get_controller(0).await?;
+
+        assert!(matches!(
+            controller.mode("Rainbow"),
+            Err(ModeError::NotFound { .. })
+        ));
+        let mut wave = controller.mode("Wave")?;
+        assert_eq!(
+            wave.set_speed(5),
+            Err(ModeError::OutOfRange {
+                mode: "Wave".to_owned(),
+                setting: ModeSetting::Speed,
+                value: 5,
+                min: 0,
+                max: 4,
+            })
+        );
+        assert!(matches!(
+            wave.set_direction(Direction::Up),
+            Err(ModeError::UnsupportedDirection { .. })
+        ));
+        assert!(matches!(
+            wave.set_colors([Color::default(); 3]),
+            Err(ModeError::OutOfRange {
+                setting: ModeSetting::Colors,
+                ..
+            })
+        ));
+        assert!(matches!(
+            wave.set_color_mode(ColorMode::Random),
+            Err(ModeError::UnsupportedColorMode { .. })
+        ));
+        let mut direct = controller.mode("Direct")?;
+        assert_eq!(
+            direct.set_speed(1),
+            Err(ModeError::Unsupported {
+                mode: "Direct".to_owned(),
+                setting: ModeSetting::Speed,
+            })
+        );
+        // nothing was sent
+        assert!(server.received().is_empty());
+        Ok(())
+    }
+}
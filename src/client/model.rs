@@ -0,0 +1,402 @@
+use std::sync::{Arc, Mutex as StdMutex, Weak};
+
+use tokio::{
+    sync::{
+        broadcast::{self, error::RecvError},
+        watch,
+    },
+    task::JoinHandle,
+};
+
+use crate::{
+    Controller, ControllerData, ControllerKey, ControllerUpdate, OpenRgbProtocol, OpenRgbResult,
+    ServerEvent,
+};
+
+/// Channel with the latest snapshot of a single controller.
+type Channel = watch::Sender<Arc<Controller>>;
+
+/// Live view of all controllers, kept up to date without calling [`Controller::sync_controller_data()`].
+///
+/// Created with [`crate::OpenRgbClient::device_model()`].
+/// The model holds a snapshot of every controller, which is replaced when:
+/// - this client, or a [`Controller`] obtained from it, changes the colors, mode or zones of a controller,
+/// - the server reports [`ServerEvent::DeviceListUpdated`], or the client reconnects,
+///   after which all controllers are fetched again.
+///
+/// Changes made by other clients or by `OpenRGB` itself are only picked up by [`Self::refresh()`],
+/// since the server does not report them.
+///
+/// Snapshots are shared, so handing them out is cheap, and a snapshot never changes once handed out.
+/// Use [`Self::watch()`] to be told when a controller gets a new snapshot.
+/// Clones share the same model, which stops updating once all clones are dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// let model = client.device_model().await?;
+/// let mut keyboard = model.watch(0).expect("controller 0 exists");
+/// while keyboard.changed().await.is_ok() {
+///     let snapshot = keyboard.borrow_and_update().clone();
+///     println!("{} now shows {:?}", snapshot.name(), snapshot.colors());
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DeviceModel {
+    inner: Arc<ModelInner>,
+}
+
+impl std::fmt::Debug for DeviceModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceModel")
+            .field("controllers", &self.inner.controllers.borrow().len())
+            .finish()
+    }
+}
+
+struct ModelInner {
+    proto: OpenRgbProtocol,
+    state: StdMutex<ModelState>,
+    /// Snapshots of all controllers, in the same order as `channels`.
+    controllers: watch::Sender<Vec<Arc<Controller>>>,
+    /// Refreshes the model on server events.
+    task: JoinHandle<()>,
+}
+
+/// Controllers of the model, and the updates that may be missing from a refresh in progress.
+#[derive(Default)]
+struct ModelState {
+    /// One channel per controller, in the order of the device list of the server.
+    channels: Vec<Channel>,
+    /// Number of updates applied so far.
+    generation: u64,
+    /// Number of refreshes fetching controllers.
+    refreshing: usize,
+    /// Updates applied while a refresh was fetching, with their generation.
+    ///
+    /// The fetched data may predate them, so they are applied to it again.
+    journal: Vec<(u64, u32, ControllerUpdate)>,
+}
+
+impl Drop for ModelInner {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl DeviceModel {
+    /// Fetches all controllers, and starts following updates sent through `proto`.
+    pub(crate) async fn new(proto: OpenRgbProtocol) -> OpenRgbResult<Self> {
+        // subscribed before fetching, so no device list update is missed
+        let events = proto.subscribe();
+        let inner = Arc::new_cyclic(|model: &Weak<ModelInner>| {
+            let listener = model.clone();
+            proto.add_update_listener(Box::new(move |controller_id, update| {
+                match listener.upgrade() {
+                    Some(model) => {
+                        model.apply(controller_id, update);
+                        true
+                    }
+                    None => false,
+                }
+            }));
+            ModelInner {
+                proto: proto.clone(),
+                state: StdMutex::new(ModelState::default()),
+                controllers: watch::Sender::new(Vec::new()),
+                task: tokio::spawn(refresh_on_events(model.clone(), events)),
+            }
+        });
+        inner.refresh().await?;
+        Ok(Self { inner })
+    }
+
+    /// Returns the latest snapshot of every controller, in the order of their ids.
+    pub fn controllers(&self) -> Vec<Arc<Controller>> {
+        self.inner.controllers.borrow().clone()
+    }
+
+    /// Returns the latest snapshot of the controller with the given id.
+    pub fn get(&self, controller_id: usize) -> Option<Arc<Controller>> {
+        self.inner.controllers.borrow().get(controller_id).cloned()
+    }
+
+    /// Returns the latest snapshot of the controller identified by `key`, see [`ControllerKey`].
+    pub fn find(&self, key: &ControllerKey) -> Option<Arc<Controller>> {
+        let controllers = self.inner.controllers.borrow();
+        let found = key.find_in(controllers.iter().map(Arc::as_ref))?;
+        controllers.get(found.id()).cloned()
+    }
+
+    /// Returns the number of controllers.
+    pub fn len(&self) -> usize {
+        self.inner.controllers.borrow().len()
+    }
+
+    /// Returns true if the server has no controllers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a receiver for the snapshots of the controller with the given id.
+    ///
+    /// The receiver follows the device when its id changes after a device list update,
+    /// and is closed once the device is gone.
+    pub fn watch(&self, controller_id: usize) -> Option<watch::Receiver<Arc<Controller>>> {
+        let state = self.inner.state.lock().expect("model lock poisoned");
+        state
+            .channels
+            .get(controller_id)
+            .map(watch::Sender::subscribe)
+    }
+
+    /// Returns a receiver for the snapshots of all controllers, which changes whenever any controller does.
+    pub fn watch_all(&self) -> watch::Receiver<Vec<Arc<Controller>>> {
+        self.inner.controllers.subscribe()
+    }
+
+    /// Fetches all controllers again, to pick up changes this client did not make.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if communication with the `OpenRGB` SDK server fails.
+    pub async fn refresh(&self) -> OpenRgbResult<()> {
+        self.inner.refresh().await
+    }
+}
+
+impl ModelInner {
+    async fn refresh(&self) -> OpenRgbResult<()> {
+        let refresh = self.begin_refresh();
+        let count = self.proto.get_controller_count().await?;
+        let fetched = self.proto.get_controllers(0..count).await?;
+        self.finish_refresh(&refresh, fetched);
+        Ok(())
+    }
+
+    /// Starts journaling updates, until the returned guard is dropped.
+    fn begin_refresh(&self) -> RefreshGuard<'_> {
+        let mut state = self.state.lock().expect("model lock poisoned");
+        state.refreshing += 1;
+        RefreshGuard {
+            model: self,
+            generation: state.generation,
+        }
+    }
+
+    /// Replaces the snapshots with `fetched`, after applying the updates since `refresh` began to it.
+    fn finish_refresh(&self, refresh: &RefreshGuard<'_>, fetched: Vec<ControllerData>) {
+        let mut state = self.state.lock().expect("model lock poisoned");
+        let state = &mut *state;
+        let mut previous = state.channels.drain(..).map(Some).collect::<Vec<_>>();
+        let mut snapshots = Vec::with_capacity(fetched.len());
+        for (id, mut data) in fetched.into_iter().enumerate() {
+            let updates = state
+                .journal
+                .iter()
+                .filter(|(generation, controller_id, _)| {
+                    *generation > refresh.generation && *controller_id as usize == id
+                });
+            for (_, _, update) in updates {
+                if let Err(e) = update.clone().apply(&mut data) {
+                    tracing::debug!("Not applying update to refreshed controller {id}: {e}");
+                }
+            }
+            let controller = Arc::new(Controller::new(id, self.proto.clone(), data));
+            let channel = match take_channel(&mut previous, &controller.key()) {
+                Some(channel) => {
+                    channel.send_if_modified(|current| {
+                        let modified = current.id() != id || current.data() != controller.data();
+                        if modified {
+                            *current = controller;
+                        }
+                        modified
+                    });
+                    channel
+                }
+                None => watch::Sender::new(controller),
+            };
+            snapshots.push(channel.borrow().clone());
+            state.channels.push(channel);
+        }
+        // channels left in `previous` belong to devices that are gone, dropping them closes their receivers
+        self.controllers.send_replace(snapshots);
+    }
+
+    /// Applies an update this client sent to the snapshot of the controller.
+    fn apply(&self, controller_id: u32, update: &ControllerUpdate) {
+        let mut state = self.state.lock().expect("model lock poisoned");
+        state.generation += 1;
+        if state.refreshing > 0 {
+            let generation = state.generation;
+            state
+                .journal
+                .push((generation, controller_id, update.clone()));
+        }
+        let Some(channel) = state.channels.get(controller_id as usize) else {
+            return;
+        };
+        let current = channel.borrow().clone();
+        let mut data = current.data().clone();
+        if let Err(e) = update.clone().apply(&mut data) {
+            tracing::debug!("Not applying update to controller {controller_id}: {e}");
+            return;
+        }
+        if data == *current.data() {
+            return;
+        }
+        let snapshot = Arc::new(Controller::new(current.id(), self.proto.clone(), data));
+        channel.send_replace(snapshot.clone());
+        self.controllers.send_modify(|controllers| {
+            if let Some(slot) = controllers.get_mut(controller_id as usize) {
+                *slot = snapshot;
+            }
+        });
+    }
+}
+
+/// Refresh in progress, which clears the journal once no other refresh needs it.
+struct RefreshGuard<'a> {
+    model: &'a ModelInner,
+    /// Generation of the last update the fetched data may already contain.
+    generation: u64,
+}
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.model.state.lock().expect("model lock poisoned");
+        state.refreshing -= 1;
+        if state.refreshing == 0 {
+            state.journal.clear();
+        }
+    }
+}
+
+/// Takes the channel of the controller identified by `key` out of `previous`.
+fn take_channel(previous: &mut [Option<Channel>], key: &ControllerKey) -> Option<Channel> {
+    let snapshots = previous
+        .iter()
+        .enumerate()
+        .filter_map(|(i, channel)| Some((i, channel.as_ref()?.borrow().clone())))
+        .collect::<Vec<_>>();
+    let found = key.find_in(snapshots.iter().map(|(_, c)| c.as_ref()))?;
+    let (index, _) = snapshots
+        .iter()
+        .find(|(_, c)| std::ptr::eq(c.as_ref(), found))?;
+    previous[*index].take()
+}
+
+/// Fetches all controllers again whenever the device list may have changed, until the model is dropped.
+async fn refresh_on_events(model: Weak<ModelInner>, mut events: broadcast::Receiver<ServerEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(ServerEvent::DeviceListUpdated | ServerEvent::Reconnected)
+            | Err(RecvError::Lagged(_)) => {}
+            Ok(_) => continue,
+            Err(RecvError::Closed) => return,
+        }
+        let Some(model) = model.upgrade() else {
+            return;
+        };
+        if let Err(e) = model.refresh().await {
+            tracing::warn!("Refreshing device model failed: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockMode, MockServer, MockZone};
+    use crate::{Color, DeviceType};
+
+    use super::*;
+
+    fn strip(name: &str) -> MockController {
+        MockController::new(name, DeviceType::LEDStrip)
+            .with_mode(MockMode::direct())
+            .with_mode(MockMode::new("Breathing"))
+            .with_zone(MockZone::linear(name, 3))
+    }
+
+    #[tokio::test]
+    async fn test_model_follows_updates() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip("First"))
+            .with_controller(strip("Second"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let model = client.device_model().await?;
+        assert_eq!(model.len(), 2);
+        let mut second = model.watch(1).expect("second controller");
+
+        let controller = client.get_controller(1).await?;
+        controller.set_leds([Color::new(255, 0, 0); 3]).await?;
+        assert!(second.has_changed().expect("still connected"));
+        assert_eq!(
+            second.borrow_and_update().colors(),
+            [Color::new(255, 0, 0); 3]
+        );
+        controller.set_mode(&controller.modes()[1]).await?;
+        let snapshot = model.get(1).expect("second controller");
+        assert_eq!(snapshot.active_mode().map(|m| m.name()), Some("Breathing"));
+        assert_eq!(snapshot.colors(), [Color::new(255, 0, 0); 3]);
+        assert_eq!(model.get(0).expect("first").colors(), [Color::default(); 3]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_model_follows_device_list() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip("First"))
+            .with_controller(strip("Second"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let model = client.device_model().await?;
+        let mut first = model.watch(0).expect("first controller");
+        let mut second = model.watch(1).expect("second controller");
+        let mut all = model.watch_all();
+
+        server.remove_controller(0);
+        all.changed().await.expect("model refreshed");
+        assert_eq!(model.len(), 1);
+        assert!(first.changed().await.is_err());
+        second.changed().await.expect("second controller moved");
+        assert_eq!(second.borrow().id(), 0);
+        assert_eq!(second.borrow().name(), "Second");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_newer_updates() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip("First"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let model = client.device_model().await?;
+        let controller = client.get_controller(0).await?;
+
+        // an update sent while a refresh is fetching, which the fetched data may predate
+        let refresh = model.inner.begin_refresh();
+        let fetched = model.inner.proto.get_controllers(0..1).await?;
+        controller.set_leds([Color::new(0, 0, 255); 3]).await?;
+        model.inner.finish_refresh(&refresh, fetched);
+        drop(refresh);
+        assert_eq!(
+            model.get(0).expect("first").colors(),
+            [Color::new(0, 0, 255); 3]
+        );
+        assert!(model.inner.state.lock().unwrap().journal.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::Path;
+
+use crate::{
+    ControllerInfo, DEFAULT_PROTOCOL, OpenRgbError, OpenRgbResult, ReceivedMessage, WriteMessage,
+    protocol::data::ControllerData,
+};
+
+/// Magic bytes at the start of every profile file, including the null terminator.
+const HEADER: &[u8; 16] = b"OPENRGB_PROFILE\0";
+
+/// Profile in `OpenRGB`'s `.orp` file format, read and written without a server.
+///
+/// A profile file holds a header with a version, followed by the controllers
+/// encoded the same way the server sends them, using that version as protocol version.
+/// `OpenRGB` keeps these files in its configuration directory,
+/// where [`crate::OpenRgbClient::load_profile`] finds them by file name without the extension.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{Color, OpenRgbResult, ProfileFile};
+/// #
+/// # fn main() -> OpenRgbResult<()> {
+/// let mut profile = ProfileFile::load("Evening.orp")?;
+/// for controller in profile.controllers_mut() {
+///     controller.colors_mut().fill(Color::new(255, 120, 0));
+/// }
+/// profile.save("Evening.orp")?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileFile {
+    version: u32,
+    controllers: Vec<ControllerInfo>,
+}
+
+impl ProfileFile {
+    /// Creates a profile with `controllers`, written with version [`DEFAULT_PROTOCOL`].
+    ///
+    /// `OpenRGB` 0.9 only reads profiles up to version 4, see [`Self::with_version`].
+    pub fn new(controllers: impl IntoIterator<Item = ControllerInfo>) -> Self {
+        Self {
+            version: DEFAULT_PROTOCOL,
+            controllers: controllers.into_iter().collect(),
+        }
+    }
+
+    /// Sets the version the profile is written with.
+    ///
+    /// Values newer than the version, such as zone flags for versions below 5, are left out.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Returns the version of the profile.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the controllers in the profile.
+    pub fn controllers(&self) -> &[ControllerInfo] {
+        &self.controllers
+    }
+
+    /// Returns the controllers in the profile, to change them.
+    pub fn controllers_mut(&mut self) -> &mut Vec<ControllerInfo> {
+        &mut self.controllers
+    }
+
+    /// Parses a profile from the contents of a `.orp` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::ProtocolError`] if `bytes` is not a valid profile.
+    pub fn from_bytes(bytes: &[u8]) -> OpenRgbResult<Self> {
+        let Some(body) = bytes.strip_prefix(HEADER) else {
+            return Err(invalid("missing OPENRGB_PROFILE header"));
+        };
+        let mut msg = ReceivedMessage::new(body, 0);
+        let version = msg.read_u32()?;
+        let mut remaining = &body[size_of::<u32>()..];
+        let mut controllers = Vec::new();
+        while !remaining.is_empty() {
+            // every controller starts with its size, including the size itself
+            let size = ReceivedMessage::new(remaining, version).read_u32()? as usize;
+            if size < size_of::<u32>() || size > remaining.len() {
+                return Err(invalid(format!(
+                    "controller {} has size {size}, but {} bytes are left",
+                    controllers.len(),
+                    remaining.len()
+                )));
+            }
+            let (controller, rest) = remaining.split_at(size);
+            let data = ReceivedMessage::new(controller, version).read_value::<ControllerData>()?;
+            controllers.push(ControllerInfo::from_data(controllers.len(), &data));
+            remaining = rest;
+        }
+        Ok(Self {
+            version,
+            controllers,
+        })
+    }
+
+    /// Encodes the profile in the `.orp` file format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a controller cannot be encoded, such as one with more than `u16::MAX` modes.
+    pub fn to_bytes(&self) -> OpenRgbResult<Vec<u8>> {
+        let mut msg = WriteMessage::new(self.version);
+        msg.write_slice(HEADER);
+        msg.write_u32(self.version);
+        for controller in &self.controllers {
+            msg.write_value(&controller.to_data(self.version))?;
+        }
+        Ok(msg.bytes().to_vec())
+    }
+
+    /// Reads a profile from the `.orp` file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::FileError`] if the file cannot be read,
+    /// or [`OpenRgbError::ProtocolError`] if it is not a valid profile.
+    pub fn load(path: impl AsRef<Path>) -> OpenRgbResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| OpenRgbError::FileError {
+            path: path.to_owned(),
+            source,
+        })?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Writes the profile to the `.orp` file at `path`, replacing it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::FileError`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> OpenRgbResult<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_bytes()?).map_err(|source| OpenRgbError::FileError {
+            path: path.to_owned(),
+            source,
+        })
+    }
+}
+
+fn invalid(reason: impl std::fmt::Display) -> OpenRgbError {
+    OpenRgbError::ProtocolError(format!("Invalid profile file: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockMode, MockServer, MockZone};
+    use crate::{Color, DeviceType};
+
+    use super::*;
+
+    fn edge() -> MockController {
+        MockController::new("Edge", DeviceType::LEDStrip)
+            .with_vendor("Corsair")
+            .with_mode(MockMode::direct())
+            .with_mode(MockMode::new("Rainbow").with_speed(0, 0, 4))
+            .with_zone(MockZone::linear("Edge", 8).with_segment("Left", 0, 4))
+    }
+
+    async fn profile(protocol_version: u32) -> OpenRgbResult<ProfileFile> {
+        let server = MockServer::builder()
+            .with_protocol_version(protocol_version)
+            .with_controller(edge())
+            .with_controller(MockController::new("Fan", DeviceType::Cooler))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let group = client.get_all_controllers().await?;
+        Ok(ProfileFile::new(group.iter().map(|c| c.info())).with_version(protocol_version))
+    }
+
+    #[tokio::test]
+    async fn test_profile_roundtrip() -> OpenRgbResult<()> {
+        let mut profile = profile(DEFAULT_PROTOCOL).await?;
+        profile.controllers_mut()[0].set_active_mode("rainbow")?;
+        profile.controllers_mut()[0]
+            .colors_mut()
+            .fill(Color::new(0, 0, 255));
+
+        let bytes = profile.to_bytes()?;
+        assert_eq!(&bytes[..16], HEADER);
+        assert_eq!(bytes[16..20], DEFAULT_PROTOCOL.to_le_bytes());
+        let parsed = ProfileFile::from_bytes(&bytes)?;
+        assert_eq!(parsed, profile);
+        let edge = &parsed.controllers()[0];
+        assert_eq!(edge.active_mode().map(|m| m.name()), Some("Rainbow"));
+        assert_eq!(edge.zones()[0].segments().map(<[_]>::len), Some(1));
+
+        let path = std::env::temp_dir().join(format!("openrgb2-{}.orp", std::process::id()));
+        profile.save(&path)?;
+        let loaded = ProfileFile::load(&path);
+        std::fs::remove_file(&path)?;
+        assert_eq!(loaded?, profile);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_profile_versions() -> OpenRgbResult<()> {
+        // read with version 3, so segments and flags are missing
+        let old = profile(3).await?;
+        let parsed = ProfileFile::from_bytes(&old.to_bytes()?)?;
+        assert_eq!(parsed, old);
+        assert_eq!(parsed.controllers()[0].zones()[0].segments(), None);
+
+        // and filled with defaults when written with a newer version
+        let upgraded = ProfileFile::from_bytes(&old.with_version(5).to_bytes()?)?;
+        assert_eq!(
+            upgraded.controllers()[0].zones()[0].segments(),
+            Some(&[][..])
+        );
+        assert_eq!(upgraded.controllers()[1].flags(), Some(Default::default()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_profile() {
+        assert!(ProfileFile::from_bytes(b"OPENRGB_PROFILE").is_err());
+        let mut bytes = HEADER.to_vec();
+        bytes.extend(4u32.to_le_bytes());
+        assert_eq!(
+            ProfileFile::from_bytes(&bytes)
+                .ok()
+                .map(|p| p.controllers().len()),
+            Some(0)
+        );
+        bytes.extend(100u32.to_le_bytes());
+        assert!(ProfileFile::from_bytes(&bytes).is_err());
+        assert!(matches!(
+            ProfileFile::load("/nonexistent/profile.orp"),
+            Err(OpenRgbError::FileError { .. })
+        ));
+    }
+}
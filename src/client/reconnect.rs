@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+/// Controls how [`crate::OpenRgbClient`] reconnects after losing its connection to the `OpenRGB` server.
+///
+/// Attempts are retried with exponential backoff: the first retry waits [`Self::initial_delay()`],
+/// every retry after that waits [`Self::multiplier()`] times as long, up to [`Self::max_delay()`].
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use openrgb2::ReconnectPolicy;
+/// let policy = ReconnectPolicy::default()
+///     .with_initial_delay(Duration::from_millis(250))
+///     .with_max_delay(Duration::from_secs(10))
+///     .with_max_attempts(20)
+///     .with_restore_state(true);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+    max_attempts: Option<u32>,
+    restore_state: bool,
+}
+
+impl Default for ReconnectPolicy {
+    /// Retries forever, starting at 500ms and backing off to at most 30s between attempts.
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            max_attempts: None,
+            restore_state: false,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Sets the delay before the first retry.
+    pub fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets the maximum delay between two retries.
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets the factor by which the delay grows after every failed retry.
+    ///
+    /// Values below 1.0 are treated as 1.0, which retries at a fixed interval.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Sets the maximum number of connection attempts before giving up, per disconnect.
+    ///
+    /// After giving up, the next call on the client will start reconnecting again.
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Sets whether the last mode and colors sent to each controller are re-applied after reconnecting.
+    ///
+    /// Controllers are matched by their index, so this assumes the server still has the same devices.
+    pub fn with_restore_state(mut self, restore: bool) -> Self {
+        self.restore_state = restore;
+        self
+    }
+
+    /// Returns the delay before the first retry.
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Returns the maximum delay between two retries.
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Returns the factor by which the delay grows after every failed retry.
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    /// Returns the maximum number of connection attempts, `None` if retrying forever.
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Returns whether the last mode and colors are re-applied after reconnecting.
+    pub fn restore_state(&self) -> bool {
+        self.restore_state
+    }
+
+    /// Returns the delays to wait between consecutive connection attempts.
+    pub(crate) fn delays(&self) -> impl Iterator<Item = Duration> + Send + 'static {
+        let (initial, max, multiplier) = (self.initial_delay, self.max_delay, self.multiplier);
+        let retries = self.max_attempts.map(|n| n.saturating_sub(1) as usize);
+        std::iter::successors(Some(initial.min(max)), move |d| {
+            // saturates at `max` instead of panicking on overflow, such as with an infinite multiplier
+            let next = Duration::try_from_secs_f64(d.as_secs_f64() * multiplier);
+            Some(next.map_or(max, |next| next.min(max)))
+        })
+        .take(retries.unwrap_or(usize::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delays() {
+        let policy = ReconnectPolicy::default()
+            .with_initial_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(500))
+            .with_max_attempts(6);
+        let delays = policy.delays().map(|d| d.as_millis()).collect::<Vec<_>>();
+        assert_eq!(delays, [100, 200, 400, 500, 500]);
+    }
+
+    #[test]
+    fn test_delays_unlimited() {
+        let policy = ReconnectPolicy::default().with_multiplier(0.5);
+        assert!(
+            policy
+                .delays()
+                .take(100)
+                .all(|d| d == policy.initial_delay())
+        );
+    }
+
+    #[test]
+    fn test_delays_overflow() {
+        for multiplier in [1e20, f64::INFINITY] {
+            let policy = ReconnectPolicy::default()
+                .with_max_delay(Duration::MAX)
+                .with_multiplier(multiplier)
+                .with_max_attempts(4);
+            let delays = policy.delays().collect::<Vec<_>>();
+            assert_eq!(delays[1..], [Duration::MAX; 2]);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{Color, Controller, ControllerGroup, ControllerKey, ModeData, ModeFlag, OpenRgbResult};
+
+/// Modes and colors of a group of controllers at one point in time.
+///
+/// Taken with [`ControllerGroup::snapshot()`] and put back with [`ControllerGroup::restore()`].
+/// Unlike `OpenRGB` profiles, which live on the server, a snapshot is a plain value
+/// that can be inspected and compared, and stored as JSON or TOML with the `serde` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb2::{Color, OpenRgbClient, OpenRgbResult};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// let group = client.get_all_controllers().await?;
+/// let snapshot = group.snapshot();
+///
+/// // game mode
+/// for controller in &group {
+///     controller.set_all_leds(Color::new(255, 0, 0)).await?;
+/// }
+///
+/// let report = group.restore(&snapshot).await?;
+/// for key in report.unmatched() {
+///     println!("{key} is gone");
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LightingSnapshot {
+    devices: Vec<DeviceSnapshot>,
+}
+
+impl LightingSnapshot {
+    /// Returns the snapshots of the single devices.
+    pub fn devices(&self) -> &[DeviceSnapshot] {
+        &self.devices
+    }
+
+    /// Returns the snapshot of the device identified by `key`, see [`ControllerKey`].
+    pub fn get(&self, key: &ControllerKey) -> Option<&DeviceSnapshot> {
+        self.devices.iter().find(|device| device.key == *key)
+    }
+
+    pub(crate) fn capture(group: &ControllerGroup) -> Self {
+        Self {
+            devices: group.iter().map(DeviceSnapshot::capture).collect(),
+        }
+    }
+
+    /// Applies this snapshot to the controllers in `group`, see [`ControllerGroup::restore()`].
+    pub(crate) async fn restore(&self, group: &ControllerGroup) -> OpenRgbResult<RestoreReport> {
+        let mut report = RestoreReport::default();
+        let mut available = group.iter().collect::<Vec<_>>();
+        let mut leds = Vec::new();
+        for device in &self.devices {
+            let Some(controller) = device.key.find_in(available.iter().copied()) else {
+                report.unmatched.push(device.key.clone());
+                continue;
+            };
+            available.retain(|c| !std::ptr::eq(*c, controller));
+
+            if let Some(mode) = &device.mode {
+                device.restore_mode(controller, mode).await?;
+            }
+            if device.restores_colors() {
+                leds.push((controller.id() as u32, device.colors.as_slice()));
+            }
+            report.restored.push(device.key.clone());
+        }
+        if let Some(first) = group.iter().next() {
+            if !leds.is_empty() {
+                first.proto().update_leds_batch(&leds).await?;
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Mode and colors of a single device in a [`LightingSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceSnapshot {
+    key: ControllerKey,
+    mode: Option<ModeData>,
+    colors: Vec<Color>,
+}
+
+impl DeviceSnapshot {
+    fn capture(controller: &Controller) -> Self {
+        Self {
+            key: controller.key(),
+            mode: controller.active_mode().cloned(),
+            colors: controller.colors().to_vec(),
+        }
+    }
+
+    /// Returns the key that identifies the device.
+    pub fn key(&self) -> &ControllerKey {
+        &self.key
+    }
+
+    /// Returns the mode that was active, `None` if the device reported none.
+    pub fn mode(&self) -> Option<&ModeData> {
+        self.mode.as_ref()
+    }
+
+    /// Returns the colors of all LEDs.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Colors are only restored if the mode shows them, since they would be ignored otherwise.
+    fn restores_colors(&self) -> bool {
+        !self.colors.is_empty()
+            && self
+                .mode
+                .as_ref()
+                .is_none_or(|mode| mode.flags().contains(ModeFlag::HasPerLEDColor))
+    }
+
+    /// Sets `mode` on `controller`, looking it up by name since mode ids are not guaranteed to be stable.
+    async fn restore_mode(&self, controller: &Controller, mode: &ModeData) -> OpenRgbResult<()> {
+        let Some(current) = controller.modes().iter().find(|m| m.name() == mode.name()) else {
+            tracing::warn!(
+                "{} has no {:?} mode anymore, keeping its current mode",
+                self.key,
+                mode.name()
+            );
+            return Ok(());
+        };
+        let mut mode = mode.clone();
+        mode.set_id(current.id());
+        controller.set_mode(&mode).await
+    }
+}
+
+/// Outcome of [`ControllerGroup::restore()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    restored: Vec<ControllerKey>,
+    unmatched: Vec<ControllerKey>,
+}
+
+impl RestoreReport {
+    /// Returns the devices that were restored.
+    pub fn restored(&self) -> &[ControllerKey] {
+        &self.restored
+    }
+
+    /// Returns the devices in the snapshot that are not in the group, and were skipped.
+    pub fn unmatched(&self) -> &[ControllerKey] {
+        &self.unmatched
+    }
+
+    /// Returns true if every device in the snapshot was restored.
+    pub fn is_complete(&self) -> bool {
+        self.unmatched.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockMode, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    fn strip(name: &str, serial: &str) -> MockController {
+        MockController::new(name, DeviceType::LEDStrip)
+            .with_serial(serial)
+            .with_mode(MockMode::direct())
+            .with_mode(MockMode::new("Rainbow"))
+            .with_zone(MockZone::linear(name, 2))
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restore() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip("Desk", "1"))
+            .with_controller(strip("Shelf", "2"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let group = client.get_all_controllers().await?;
+        let desk = group.get_controller(0)?;
+        desk.set_leds([Color::new(255, 0, 0), Color::new(0, 255, 0)])
+            .await?;
+        group.get_controller(1)?.mode("Rainbow")?.apply().await?;
+
+        let snapshot = client.get_all_controllers().await?.snapshot();
+        assert_eq!(snapshot.devices().len(), 2);
+        let desk_snapshot = snapshot.get(&desk.key()).expect("desk was captured");
+        assert_eq!(desk_snapshot.mode().map(ModeData::name), Some("Direct"));
+        assert_eq!(
+            desk_snapshot.colors(),
+            [Color::new(255, 0, 0), Color::new(0, 255, 0)]
+        );
+
+        // game mode, while the shelf is unplugged and the desk moves to another id
+        for controller in &group {
+            controller.set_all_leds(Color::new(0, 0, 255)).await?;
+        }
+        // a request makes sure the server handled the updates before the device list changes
+        client.get_controller(0).await?;
+        server.remove_controller(1);
+        server.add_controller(strip("Lamp", "3"));
+        server.remove_controller(0);
+        server.add_controller(strip("Desk", "1"));
+
+        let group = client.get_all_controllers().await?;
+        let report = group.restore(&snapshot).await?;
+        assert_eq!(report.restored(), [desk.key()]);
+        assert_eq!(
+            report.unmatched(),
+            [group_key(&snapshot, "Shelf")],
+            "the shelf is gone"
+        );
+        assert!(!report.is_complete());
+
+        client.get_controller(0).await?;
+        assert_eq!(
+            server.colors(1),
+            Some(vec![Color::new(255, 0, 0), Color::new(0, 255, 0)])
+        );
+        assert_eq!(
+            server.active_mode(1).map(|m| m.name().to_owned()),
+            Some("Direct".to_owned())
+        );
+        assert_eq!(server.colors(0), Some(vec![Color::default(); 2]));
+        Ok(())
+    }
+
+    fn group_key(snapshot: &LightingSnapshot, name: &str) -> ControllerKey {
+        snapshot
+            .devices()
+            .iter()
+            .map(DeviceSnapshot::key)
+            .find(|key| key.name() == name)
+            .expect("device was captured")
+            .clone()
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn test_snapshot_serde() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip("Desk", "1"))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let snapshot = client.get_all_controllers().await?.snapshot();
+
+        let json = serde_json::to_value(&snapshot).expect("snapshot serializes");
+        assert_eq!(json["devices"][0]["key"]["serial"], "1");
+        assert_eq!(json["devices"][0]["mode"]["flags"][0], "HasPerLEDColor");
+        let parsed: LightingSnapshot = serde_json::from_value(json).expect("snapshot deserializes");
+        assert_eq!(parsed, snapshot);
+        Ok(())
+    }
+}
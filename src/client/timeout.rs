@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// Deadlines for talking to the `OpenRGB` server, see [`crate::OpenRgbClient::set_timeouts`].
+///
+/// All timeouts are disabled by default, so calls wait as long as the server takes.
+/// When a deadline passes the call fails with [`crate::OpenRgbError::Timeout`],
+/// and the connection is closed so a late reply cannot be mistaken for the reply to a later request.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use openrgb2::Timeouts;
+/// let timeouts = Timeouts::default()
+///     .with_connect(Duration::from_secs(2))
+///     .with_read(Duration::from_secs(5))
+///     .with_write(Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timeouts {
+    connect: Option<Duration>,
+    read: Option<Duration>,
+    write: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Sets how long opening a connection and negotiating the protocol version may take.
+    pub fn with_connect(mut self, timeout: Duration) -> Self {
+        self.connect = Some(timeout);
+        self
+    }
+
+    /// Sets how long to wait for the reply to a request, after it was written.
+    pub fn with_read(mut self, timeout: Duration) -> Self {
+        self.read = Some(timeout);
+        self
+    }
+
+    /// Sets how long writing a single packet may take.
+    ///
+    /// Time spent waiting for earlier packets to be written is not counted.
+    pub fn with_write(mut self, timeout: Duration) -> Self {
+        self.write = Some(timeout);
+        self
+    }
+
+    /// Returns how long opening a connection may take, `None` if it may take forever.
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect
+    }
+
+    /// Returns how long to wait for a reply, `None` if waiting forever.
+    pub fn read(&self) -> Option<Duration> {
+        self.read
+    }
+
+    /// Returns how long writing a packet may take, `None` if it may take forever.
+    pub fn write(&self) -> Option<Duration> {
+        self.write
+    }
+}
@@ -1,7 +1,7 @@
 use array2d::Array2D;
 
 use crate::{
-    Color, Command, Controller, OpenRgbError, OpenRgbResult, ZoneType,
+    Color, Command, Controller, MatrixView, OpenRgbError, OpenRgbResult, ZoneType,
     client::segment::Segment,
     data::{SegmentData, ZoneData},
 };
@@ -53,8 +53,11 @@ impl<'a> Zone<'a> {
             #[call(leds_count)]
             pub fn num_leds(&self) -> usize;
 
+            /// Returns the segments in this zone, `None` if protocol version is lower than 4.
             pub fn segments(&self) -> Option<&[SegmentData]>;
-            #[allow(unused)]
+            /// Returns the LED matrix of this zone, if it is a [`ZoneType::Matrix`] zone.
+            ///
+            /// Positions without an LED are `u32::MAX`, see [`Self::matrix_view()`] to draw on the matrix.
             pub fn matrix(&self) -> Option<&Array2D<u32>>;
         }
     }
@@ -90,6 +93,15 @@ impl<'a> Zone<'a> {
             .expect("Zone id should be valid")
     }
 
+    /// Returns a [`MatrixView`] to draw on this zone by `(x, y)` position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this zone has no matrix.
+    pub fn matrix_view(&self) -> OpenRgbResult<MatrixView<'a>> {
+        MatrixView::new(self.controller, self.zone_data)
+    }
+
     /// Creates a new [`Command`] for the controller of this zone.
     ///
     /// The command must be executed by calling `.execute()`
@@ -141,7 +153,7 @@ impl<'a> Zone<'a> {
         colors: impl IntoIterator<Item = C>,
     ) -> OpenRgbResult<()> {
         let mut color_v = colors.into_iter().map(Into::into).collect::<Vec<_>>();
-        if color_v.len() >= self.num_leds() {
+        if color_v.len() > self.num_leds() {
             tracing::warn!(
                 "Zone {} for controller {} was given {} colors, while its length is {}. This might become a hard error in the future.",
                 self.name(),
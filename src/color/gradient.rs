@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use super::{Oklab, ParseColorError, parse};
+use crate::Color;
+
+/// Colors at positions from 0.0 to 1.0, blended in [`Oklab`] between them.
+///
+/// # Example
+///
+/// ```
+/// # use openrgb2::Color;
+/// # use openrgb2::color::Gradient;
+/// let fire = Gradient::new([Color::new(255, 0, 0), Color::new(255, 255, 0)]);
+/// assert_eq!(fire.sample(1.0), Color::new(255, 255, 0));
+///
+/// // one color per LED, from the first to the last stop
+/// let leds = fire.samples(30).collect::<Vec<_>>();
+/// assert_eq!(leds[0], Color::new(255, 0, 0));
+///
+/// // or from text, such as a config file
+/// let night: Gradient = "#000020, midnightblue, #4b0082".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Creates a gradient with `colors` spread evenly from 0.0 to 1.0.
+    pub fn new<C: Into<Color>>(colors: impl IntoIterator<Item = C>) -> Self {
+        let colors = colors.into_iter().map(Into::into).collect::<Vec<_>>();
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        Self {
+            stops: colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, color)| (i as f32 / last, color))
+                .collect(),
+        }
+    }
+
+    /// Creates a gradient from colors at positions, which are clamped to 0.0 to 1.0.
+    pub fn from_stops<C: Into<Color>>(stops: impl IntoIterator<Item = (f32, C)>) -> Self {
+        let mut stops = stops
+            .into_iter()
+            .map(|(position, color)| (position.clamp(0.0, 1.0), color.into()))
+            .collect::<Vec<_>>();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    /// Returns the positions and colors of the stops, sorted by position.
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Returns the color at `t`, which is clamped to 0.0 to 1.0.
+    ///
+    /// Before the first and after the last stop, the color of that stop is returned.
+    /// An empty gradient is black.
+    pub fn sample(&self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let next = self.stops.partition_point(|(position, _)| *position < t);
+        match (
+            next.checked_sub(1).map(|i| self.stops[i]),
+            self.stops.get(next),
+        ) {
+            (Some((from_pos, from)), Some(&(to_pos, to))) => {
+                let t = (t - from_pos) / (to_pos - from_pos);
+                Oklab::from(from).lerp(Oklab::from(to), t).into()
+            }
+            (Some((_, color)), None) | (None, Some(&(_, color))) => color,
+            (None, None) => Color::default(),
+        }
+    }
+
+    /// Returns `count` colors sampled evenly from 0.0 to 1.0, such as one for each LED of a zone.
+    pub fn samples(&self, count: usize) -> impl Iterator<Item = Color> + '_ {
+        let last = count.saturating_sub(1).max(1) as f32;
+        (0..count).map(move |i| self.sample(i as f32 / last))
+    }
+}
+
+/// Parses colors separated by commas, spread evenly like [`Gradient::new`].
+impl FromStr for Gradient {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient() {
+        let (red, green, blue) = (
+            Color::new(255, 0, 0),
+            Color::new(0, 255, 0),
+            Color::new(0, 0, 255),
+        );
+        let gradient = Gradient::new([red, green, blue]);
+        assert_eq!(gradient.stops()[1], (0.5, green));
+        assert_eq!(gradient.sample(-1.0), red);
+        assert_eq!(gradient.sample(0.5), green);
+        assert_eq!(gradient.sample(0.25), crate::color::lerp(red, green, 0.5));
+        assert_eq!(
+            gradient.samples(5).collect::<Vec<_>>(),
+            [0.0, 0.25, 0.5, 0.75, 1.0].map(|t| gradient.sample(t))
+        );
+
+        let stops = Gradient::from_stops([(0.8, blue), (0.2, red)]);
+        assert_eq!(stops.sample(0.1), red);
+        assert_eq!(stops.sample(0.9), blue);
+        assert_eq!(Gradient::new([green]).sample(0.7), green);
+        assert_eq!(Gradient::default().sample(0.5), Color::default());
+    }
+
+    #[test]
+    fn test_parse_gradient() {
+        assert_eq!(
+            "red, #00ff00,blue".parse(),
+            Ok(Gradient::new([
+                Color::new(255, 0, 0),
+                Color::new(0, 255, 0),
+                Color::new(0, 0, 255),
+            ]))
+        );
+        assert!("red,,blue".parse::<Gradient>().is_err());
+    }
+}
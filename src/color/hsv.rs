@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use super::{ParseColorError, parse, to_f32, to_u8};
+use crate::Color;
+
+/// Color as hue, saturation and value, the way most color pickers show it.
+///
+/// Hue is in degrees and wraps around, so `-60.0` and `300.0` are the same magenta.
+/// Saturation and value go from 0.0 to 1.0.
+///
+/// # Example
+///
+/// ```
+/// # use openrgb2::Color;
+/// # use openrgb2::color::Hsv;
+/// let rainbow = (0..8).map(|i| Color::from(Hsv::new(i as f32 * 45.0, 1.0, 1.0)));
+/// assert_eq!(rainbow.last(), Some(Color::new(255, 0, 191)));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsv {
+    /// Hue in degrees.
+    pub h: f32,
+    /// Saturation from 0.0 to 1.0.
+    pub s: f32,
+    /// Value from 0.0 to 1.0.
+    pub v: f32,
+}
+
+impl Hsv {
+    /// Creates a color from hue in degrees, saturation and value.
+    pub const fn new(h: f32, s: f32, v: f32) -> Self {
+        Self { h, s, v }
+    }
+}
+
+impl From<Hsv> for Color {
+    fn from(hsv: Hsv) -> Self {
+        let chroma = hsv.v * hsv.s;
+        from_hue(hsv.h, chroma, hsv.v - chroma)
+    }
+}
+
+impl From<Color> for Hsv {
+    fn from(color: Color) -> Self {
+        let (h, min, max) = to_hue(color);
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+        Self { h, s, v: max }
+    }
+}
+
+impl FromStr for Hsv {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).map(Self::from)
+    }
+}
+
+/// Color as hue, saturation and lightness, as used by CSS `hsl()`.
+///
+/// Hue is in degrees and wraps around. Saturation and lightness go from 0.0 to 1.0,
+/// fully saturated colors have a lightness of 0.5.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsl {
+    /// Hue in degrees.
+    pub h: f32,
+    /// Saturation from 0.0 to 1.0.
+    pub s: f32,
+    /// Lightness from 0.0 to 1.0.
+    pub l: f32,
+}
+
+impl Hsl {
+    /// Creates a color from hue in degrees, saturation and lightness.
+    pub const fn new(h: f32, s: f32, l: f32) -> Self {
+        Self { h, s, l }
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Self {
+        let chroma = (1.0 - (2.0 * hsl.l - 1.0).abs()) * hsl.s;
+        from_hue(hsl.h, chroma, hsl.l - chroma / 2.0)
+    }
+}
+
+impl From<Color> for Hsl {
+    fn from(color: Color) -> Self {
+        let (h, min, max) = to_hue(color);
+        let l = (max + min) / 2.0;
+        let s = if l > 0.0 && l < 1.0 {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        } else {
+            0.0
+        };
+        Self { h, s, l }
+    }
+}
+
+impl FromStr for Hsl {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).map(Self::from)
+    }
+}
+
+/// Builds a color from `hue` and `chroma`, with `min` added to all channels.
+fn from_hue(hue: f32, chroma: f32, min: f32) -> Color {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    Color::new(to_u8(r + min), to_u8(g + min), to_u8(b + min))
+}
+
+/// Returns the hue in degrees, and the smallest and largest channel of `color`.
+fn to_hue(color: Color) -> (f32, f32, f32) {
+    let (r, g, b) = (to_f32(color.r), to_f32(color.g), to_f32(color.b));
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let hue = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    (hue, min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hsv() {
+        assert_eq!(Color::from(Hsv::new(0.0, 1.0, 1.0)), Color::new(255, 0, 0));
+        assert_eq!(
+            Color::from(Hsv::new(120.0, 1.0, 1.0)),
+            Color::new(0, 255, 0)
+        );
+        assert_eq!(
+            Color::from(Hsv::new(-120.0, 1.0, 1.0)),
+            Color::new(0, 0, 255)
+        );
+        assert_eq!(
+            Color::from(Hsv::new(60.0, 0.5, 0.5)),
+            Color::new(128, 128, 64)
+        );
+        assert_eq!(
+            Color::from(Hsv::new(90.0, 0.0, 1.0)),
+            Color::new(255, 255, 255)
+        );
+
+        assert_eq!(Hsv::from(Color::new(0, 0, 255)), Hsv::new(240.0, 1.0, 1.0));
+        assert_eq!(Hsv::from(Color::new(0, 0, 0)), Hsv::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_hsl() {
+        assert_eq!(Color::from(Hsl::new(0.0, 1.0, 0.5)), Color::new(255, 0, 0));
+        assert_eq!(
+            Color::from(Hsl::new(180.0, 1.0, 0.25)),
+            Color::new(0, 128, 128)
+        );
+        assert_eq!(
+            Color::from(Hsl::new(0.0, 1.0, 1.0)),
+            Color::new(255, 255, 255)
+        );
+
+        assert_eq!(
+            Hsl::from(Color::new(255, 0, 255)),
+            Hsl::new(300.0, 1.0, 0.5)
+        );
+        assert_eq!("white".parse::<Hsl>(), Ok(Hsl::new(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for color in [
+            Color::new(12, 200, 99),
+            Color::new(255, 128, 0),
+            Color::new(1, 2, 3),
+            Color::new(250, 10, 240),
+        ] {
+            assert_eq!(Color::from(Hsv::from(color)), color);
+            assert_eq!(Color::from(Hsl::from(color)), color);
+        }
+    }
+}
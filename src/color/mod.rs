@@ -0,0 +1,54 @@
+//! Color spaces, color temperature, parsing and gradients for building effects.
+//!
+//! Every type here converts into [`Color`], so it can be passed straight to
+//! [`crate::Controller::set_leds`] and the other methods taking `impl Into<Color>`.
+//!
+//! * [`Hsv`] and [`Hsl`] for picking hues,
+//! * [`LinearRgb`] for mixing light physically, such as adding or scaling colors,
+//! * [`Oklab`] for perceptually even blending, used by [`lerp`] and [`Gradient`],
+//! * [`Kelvin`] for white light of a color temperature,
+//! * [`parse`] and the `FromStr` implementations for hex codes such as `#ff8800` and CSS color names.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use openrgb2::color::{Gradient, Hsv, Kelvin};
+//! use openrgb2::{Color, OpenRgbClient, OpenRgbResult};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let client = OpenRgbClient::connect().await?;
+//! let controller = client.get_controller(0).await?;
+//!
+//! controller.set_all_leds(Hsv::new(200.0, 1.0, 1.0)).await?;
+//! controller.set_all_leds(Kelvin(2700)).await?;
+//!
+//! let sunset: Gradient = "orangered, gold, midnightblue".parse().unwrap();
+//! controller.set_leds(sunset.samples(controller.num_leds())).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Color`]: crate::Color
+
+mod gradient;
+mod hsv;
+mod oklab;
+mod parse;
+mod temperature;
+
+pub use gradient::*;
+pub use hsv::*;
+pub use oklab::*;
+pub use parse::*;
+pub use temperature::*;
+
+/// Converts a channel from 0.0 to 1.0 to a byte, clamping values outside of that range.
+fn to_u8(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Converts a byte to a channel from 0.0 to 1.0.
+fn to_f32(channel: u8) -> f32 {
+    f32::from(channel) / 255.0
+}
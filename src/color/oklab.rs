@@ -0,0 +1,210 @@
+use std::str::FromStr;
+
+use super::{ParseColorError, parse, to_f32, to_u8};
+use crate::Color;
+
+/// Color with linear sRGB channels from 0.0 to 1.0, proportional to the emitted light.
+///
+/// [`Color`] channels are gamma encoded, so doubling a channel does not double the light.
+/// Mix, add and scale colors in linear RGB, and convert back when sending them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearRgb {
+    /// Red channel.
+    pub r: f32,
+    /// Green channel.
+    pub g: f32,
+    /// Blue channel.
+    pub b: f32,
+}
+
+impl LinearRgb {
+    /// Creates a color from linear channels.
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Multiplies all channels by `factor`.
+    pub fn scale(self, factor: f32) -> Self {
+        Self::new(self.r * factor, self.g * factor, self.b * factor)
+    }
+}
+
+impl From<LinearRgb> for Color {
+    fn from(rgb: LinearRgb) -> Self {
+        Color::new(encode(rgb.r), encode(rgb.g), encode(rgb.b))
+    }
+}
+
+impl From<Color> for LinearRgb {
+    fn from(color: Color) -> Self {
+        Self::new(decode(color.r), decode(color.g), decode(color.b))
+    }
+}
+
+impl FromStr for LinearRgb {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).map(Self::from)
+    }
+}
+
+/// Converts a linear channel to a gamma encoded sRGB byte.
+fn encode(channel: f32) -> u8 {
+    let encoded = if channel <= 0.003_130_8 {
+        12.92 * channel
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    };
+    to_u8(encoded)
+}
+
+/// Converts a gamma encoded sRGB byte to a linear channel.
+fn decode(channel: u8) -> f32 {
+    let channel = to_f32(channel);
+    if channel <= 0.040_45 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Color in the [Oklab](https://bottosson.github.io/posts/oklab/) perceptual color space.
+///
+/// Equal distances in Oklab look like equal differences in color,
+/// so blending in Oklab avoids the dark and grey middle of blending RGB channels.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oklab {
+    /// Perceived lightness from 0.0 to 1.0.
+    pub l: f32,
+    /// Green to red axis.
+    pub a: f32,
+    /// Blue to yellow axis.
+    pub b: f32,
+}
+
+impl Oklab {
+    /// Creates a color from lightness and the `a` and `b` axes.
+    pub const fn new(l: f32, a: f32, b: f32) -> Self {
+        Self { l, a, b }
+    }
+
+    /// Blends from `self` at `t = 0.0` to `other` at `t = 1.0`.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self::new(
+            self.l + (other.l - self.l) * t,
+            self.a + (other.a - self.a) * t,
+            self.b + (other.b - self.b) * t,
+        )
+    }
+}
+
+impl From<LinearRgb> for Oklab {
+    fn from(rgb: LinearRgb) -> Self {
+        let l = (0.412_221_47 * rgb.r + 0.536_332_54 * rgb.g + 0.051_445_995 * rgb.b).cbrt();
+        let m = (0.211_903_5 * rgb.r + 0.680_699_5 * rgb.g + 0.107_396_96 * rgb.b).cbrt();
+        let s = (0.088_302_46 * rgb.r + 0.281_718_85 * rgb.g + 0.629_978_7 * rgb.b).cbrt();
+        Self::new(
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+}
+
+impl From<Oklab> for LinearRgb {
+    fn from(lab: Oklab) -> Self {
+        let l = (lab.l + 0.396_337_78 * lab.a + 0.215_803_76 * lab.b).powi(3);
+        let m = (lab.l - 0.105_561_346 * lab.a - 0.063_854_17 * lab.b).powi(3);
+        let s = (lab.l - 0.089_484_18 * lab.a - 1.291_485_5 * lab.b).powi(3);
+        Self::new(
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        )
+    }
+}
+
+impl From<Oklab> for Color {
+    fn from(lab: Oklab) -> Self {
+        LinearRgb::from(lab).into()
+    }
+}
+
+impl From<Color> for Oklab {
+    fn from(color: Color) -> Self {
+        LinearRgb::from(color).into()
+    }
+}
+
+impl FromStr for Oklab {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).map(Self::from)
+    }
+}
+
+/// Blends from `from` at `t = 0.0` to `to` at `t = 1.0` in [`Oklab`], so the steps look even.
+///
+/// `t` is clamped to 0.0 to 1.0.
+///
+/// # Example
+///
+/// ```
+/// # use openrgb2::Color;
+/// # use openrgb2::color::lerp;
+/// let red = Color::new(255, 0, 0);
+/// let blue = Color::new(0, 0, 255);
+/// assert_eq!(lerp(red, blue, 0.0), red);
+/// assert_eq!(lerp(red, blue, 0.5), Color::new(140, 83, 162));
+/// ```
+pub fn lerp(from: impl Into<Color>, to: impl Into<Color>, t: f32) -> Color {
+    let (from, to) = (Oklab::from(from.into()), Oklab::from(to.into()));
+    from.lerp(to, t.clamp(0.0, 1.0)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_rgb() {
+        assert_eq!(
+            LinearRgb::from(Color::new(255, 0, 0)),
+            LinearRgb::new(1.0, 0.0, 0.0)
+        );
+        assert!((LinearRgb::from(Color::new(188, 188, 188)).g - 0.5).abs() < 0.005);
+        assert_eq!(
+            Color::from(LinearRgb::new(0.5, 0.5, 0.5)),
+            Color::new(188, 188, 188)
+        );
+        assert_eq!(
+            Color::from(LinearRgb::new(2.0, -1.0, 0.0)),
+            Color::new(255, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_oklab() {
+        let white = Oklab::from(Color::new(255, 255, 255));
+        assert!((white.l - 1.0).abs() < 1e-3);
+        assert!(white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+
+        for value in 0..=255 {
+            let color = Color::new(value, 255 - value, value / 2);
+            assert_eq!(Color::from(Oklab::from(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_lerp() {
+        let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+        assert_eq!(lerp(black, white, -1.0), black);
+        assert_eq!(lerp(black, white, 2.0), white);
+        // perceptual middle grey is brighter than the linear middle
+        assert_eq!(lerp(black, white, 0.5), Color::new(99, 99, 99));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::Color;
+
+/// White light of a color temperature in Kelvin, from candle light at 1000 K to blue sky at 40000 K.
+///
+/// Temperatures outside of that range are clamped to it.
+/// The conversion follows Tanner Helland's fit of the black body spectrum,
+/// which is close enough for LEDs that are not calibrated anyway.
+///
+/// # Example
+///
+/// ```
+/// # use openrgb2::Color;
+/// # use openrgb2::color::Kelvin;
+/// let warm_white = Color::from(Kelvin(2700));
+/// assert_eq!(warm_white, Color::new(255, 167, 87));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kelvin(pub u32);
+
+impl From<Kelvin> for Color {
+    fn from(Kelvin(kelvin): Kelvin) -> Self {
+        let t = kelvin.clamp(1000, 40000) as f32 / 100.0;
+        let r = if t <= 66.0 {
+            255.0
+        } else {
+            329.698_73 * (t - 60.0).powf(-0.133_204_76)
+        };
+        let g = if t <= 66.0 {
+            99.470_8 * t.ln() - 161.119_57
+        } else {
+            288.122_17 * (t - 60.0).powf(-0.075_514_846)
+        };
+        let b = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (t - 10.0).ln() - 305.044_8
+        };
+        Color::new(channel(r), channel(g), channel(b))
+    }
+}
+
+fn channel(value: f32) -> u8 {
+    value.clamp(0.0, 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kelvin() {
+        assert_eq!(Color::from(Kelvin(6600)), Color::new(255, 255, 255));
+        assert_eq!(Color::from(Kelvin(1000)), Color::new(255, 68, 0));
+        assert_eq!(Color::from(Kelvin(0)), Color::from(Kelvin(1000)));
+        let cold = Color::from(Kelvin(20000));
+        assert!(cold.b == 255 && cold.r < cold.g && cold.g < cold.b);
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use super::{Effect, Frame, phase};
+use crate::Color;
+use crate::color::{Gradient, Hsv, LinearRgb};
+
+/// Cycles through all hues, spread along each zone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rainbow {
+    period: Duration,
+    spread: f32,
+}
+
+impl Rainbow {
+    /// Creates a rainbow that cycles through all hues once every `period`.
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            spread: 1.0,
+        }
+    }
+
+    /// Sets how many times the hues repeat along a zone, 1.0 by default.
+    ///
+    /// With 0.0 all LEDs have the same hue.
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+}
+
+impl Effect for Rainbow {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        let offset = phase(t, self.period);
+        for mut zone in frame.zones_mut() {
+            for (position, color) in zone.positions_mut() {
+                let hue = (offset + position * self.spread) * 360.0;
+                *color = Hsv::new(hue, 1.0, 1.0).into();
+            }
+        }
+    }
+}
+
+/// Fades a color in and out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breathing {
+    color: Color,
+    period: Duration,
+}
+
+impl Breathing {
+    /// Creates an effect that fades `color` in and out once every `period`, starting dark.
+    pub fn new(color: impl Into<Color>, period: Duration) -> Self {
+        Self {
+            color: color.into(),
+            period,
+        }
+    }
+}
+
+impl Effect for Breathing {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        let level = (1.0 - (phase(t, self.period) * std::f32::consts::TAU).cos()) / 2.0;
+        // fade the light rather than the encoded color, so it looks smooth
+        frame.fill(LinearRgb::from(self.color).scale(level));
+    }
+}
+
+/// Moves the colors of a gradient along each zone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorWave {
+    gradient: Gradient,
+    period: Duration,
+}
+
+impl ColorWave {
+    /// Creates a wave that moves `gradient` along each zone once every `period`.
+    ///
+    /// The gradient runs forth and back along the zone, so the end of the wave matches its start.
+    pub fn new(gradient: Gradient, period: Duration) -> Self {
+        Self { gradient, period }
+    }
+}
+
+impl Effect for ColorWave {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        let offset = phase(t, self.period);
+        for mut zone in frame.zones_mut() {
+            for (position, color) in zone.positions_mut() {
+                let x = (position - offset).rem_euclid(1.0);
+                // triangle wave from 0.0 to 1.0 and back
+                *color = self.gradient.sample(1.0 - (2.0 * x - 1.0).abs());
+            }
+        }
+    }
+}
+
+/// Shows a gradient along each zone, without moving it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticGradient {
+    gradient: Gradient,
+}
+
+impl StaticGradient {
+    /// Creates an effect that shows `gradient` from the first to the last LED of each zone.
+    pub fn new(gradient: Gradient) -> Self {
+        Self { gradient }
+    }
+}
+
+impl Effect for StaticGradient {
+    fn render(&mut self, _t: Duration, frame: &mut Frame) {
+        for mut zone in frame.zones_mut() {
+            for (position, color) in zone.positions_mut() {
+                *color = self.gradient.sample(position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    async fn frame() -> OpenRgbResult<Frame> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Strip", DeviceType::LEDStrip)
+                    .with_zone(MockZone::linear("Left", 3))
+                    .with_zone(MockZone::linear("Right", 5)),
+            )
+            .start()
+            .await?;
+        let group = server.client().await?.get_all_controllers().await?;
+        Ok(Frame::new(&group))
+    }
+
+    #[tokio::test]
+    async fn test_builtin_effects() -> OpenRgbResult<()> {
+        let mut frame = frame().await?;
+        let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+        let zone_colors = |frame: &mut Frame| {
+            frame
+                .zones_mut()
+                .map(|zone| zone.colors().to_vec())
+                .collect::<Vec<_>>()
+        };
+
+        Rainbow::new(Duration::from_secs(3)).render(Duration::from_secs(1), &mut frame);
+        let zones = zone_colors(&mut frame);
+        assert_eq!(zones[0][0], Color::new(0, 255, 0));
+        assert_eq!(zones[1][0], Color::new(0, 255, 0));
+        assert_eq!(zones[1][4], Color::new(0, 255, 0));
+        assert_eq!(zones[0][1], Color::new(255, 0, 255));
+
+        let mut breathing = Breathing::new(red, Duration::from_secs(2));
+        breathing.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.controllers()[0].colors(), [Color::default(); 8]);
+        breathing.render(Duration::from_secs(1), &mut frame);
+        assert_eq!(frame.controllers()[0].colors(), [red; 8]);
+
+        let gradient = Gradient::new([red, blue]);
+        StaticGradient::new(gradient.clone()).render(Duration::ZERO, &mut frame);
+        let zones = zone_colors(&mut frame);
+        assert_eq!((zones[0][0], zones[0][2]), (red, blue));
+        assert_eq!((zones[1][0], zones[1][4]), (red, blue));
+
+        let mut wave = ColorWave::new(gradient, Duration::from_secs(4));
+        wave.render(Duration::ZERO, &mut frame);
+        let zones = zone_colors(&mut frame);
+        assert_eq!((zones[1][0], zones[1][2], zones[1][4]), (red, blue, red));
+        wave.render(Duration::from_secs(2), &mut frame);
+        let zones = zone_colors(&mut frame);
+        assert_eq!((zones[1][0], zones[1][2], zones[1][4]), (blue, red, blue));
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{Color, ControllerGroup, DeviceType, OpenRgbResult, ZoneData};
+
+/// Colors for all LEDs of a [`ControllerGroup`], drawn by an [`super::Effect`].
+///
+/// Holds a [`ControllerFrame`] for each controller, in the order of the group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    controllers: Vec<ControllerFrame>,
+}
+
+impl Frame {
+    /// Creates a black frame shaped like the controllers in `group` and their zones.
+    pub fn new(group: &ControllerGroup) -> Self {
+        Self {
+            controllers: group
+                .iter()
+                .map(|controller| ControllerFrame {
+                    id: controller.id(),
+                    name: controller.name().to_owned(),
+                    device_type: controller.device_type(),
+                    zones: controller.zones().to_vec(),
+                    colors: vec![Color::default(); controller.num_leds()],
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the frames of the single controllers.
+    pub fn controllers(&self) -> &[ControllerFrame] {
+        &self.controllers
+    }
+
+    /// Returns the frames of the single controllers, to draw on them.
+    pub fn controllers_mut(&mut self) -> &mut [ControllerFrame] {
+        &mut self.controllers
+    }
+
+    /// Returns the zones of all controllers, to draw on them.
+    pub fn zones_mut(&mut self) -> impl Iterator<Item = ZoneFrame<'_>> {
+        self.controllers
+            .iter_mut()
+            .flat_map(ControllerFrame::zones_mut)
+    }
+
+    /// Returns the total number of LEDs of all controllers.
+    pub fn num_leds(&self) -> usize {
+        self.controllers.iter().map(|c| c.colors.len()).sum()
+    }
+
+    /// Sets all LEDs of all controllers to `color`.
+    pub fn fill(&mut self, color: impl Into<Color>) {
+        let color = color.into();
+        for controller in &mut self.controllers {
+            controller.fill(color);
+        }
+    }
+
+    /// Sends the frame to the controllers of `group`, which it must have been created from.
+    pub(crate) async fn send(&self, group: &ControllerGroup) -> OpenRgbResult<()> {
+        let mut cmd = group.cmd();
+        for (index, controller) in self.controllers.iter().enumerate() {
+            cmd.set_controller_leds(index, controller.colors.iter().copied())?;
+        }
+        cmd.execute().await
+    }
+}
+
+/// Colors for all LEDs of one controller in a [`Frame`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControllerFrame {
+    id: usize,
+    name: String,
+    device_type: DeviceType,
+    zones: Vec<ZoneData>,
+    colors: Vec<Color>,
+}
+
+impl ControllerFrame {
+    /// Returns the id of the controller, see [`crate::Controller::id()`].
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns the name of the controller.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of the controller.
+    pub fn device_type(&self) -> DeviceType {
+        self.device_type
+    }
+
+    /// Returns the zones of the controller.
+    pub fn zones(&self) -> &[ZoneData] {
+        &self.zones
+    }
+
+    /// Returns the colors of all LEDs of the controller.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Returns the colors of all LEDs of the controller, to draw on them.
+    pub fn colors_mut(&mut self) -> &mut [Color] {
+        &mut self.colors
+    }
+
+    /// Returns the zones of the controller with their part of the colors, to draw on them.
+    pub fn zones_mut(&mut self) -> impl Iterator<Item = ZoneFrame<'_>> {
+        let mut rest = self.colors.as_mut_slice();
+        self.zones.iter().map(move |zone| {
+            let len = zone.leds_count().min(rest.len());
+            let (colors, tail) = std::mem::take(&mut rest).split_at_mut(len);
+            rest = tail;
+            ZoneFrame { zone, colors }
+        })
+    }
+
+    /// Sets all LEDs of the controller to `color`.
+    pub fn fill(&mut self, color: impl Into<Color>) {
+        self.colors.fill(color.into());
+    }
+}
+
+/// Colors for the LEDs of one zone in a [`Frame`].
+#[derive(Debug)]
+pub struct ZoneFrame<'a> {
+    zone: &'a ZoneData,
+    colors: &'a mut [Color],
+}
+
+impl ZoneFrame<'_> {
+    /// Returns the zone, such as its type and matrix.
+    pub fn zone(&self) -> &ZoneData {
+        self.zone
+    }
+
+    /// Returns the colors of the LEDs in the zone.
+    pub fn colors(&self) -> &[Color] {
+        self.colors
+    }
+
+    /// Returns the colors of the LEDs in the zone, to draw on them.
+    pub fn colors_mut(&mut self) -> &mut [Color] {
+        self.colors
+    }
+
+    /// Returns the number of LEDs in the zone.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Returns true if the zone has no LEDs.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Sets all LEDs in the zone to `color`.
+    pub fn fill(&mut self, color: impl Into<Color>) {
+        self.colors.fill(color.into());
+    }
+
+    /// Returns the position of each LED along the zone from 0.0 to 1.0, with its color.
+    ///
+    /// Useful for effects that run along strips, a zone with a single LED is at 0.0.
+    pub fn positions_mut(&mut self) -> impl Iterator<Item = (f32, &mut Color)> {
+        let last = self.colors.len().saturating_sub(1).max(1) as f32;
+        self.colors
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, color)| (i as f32 / last, color))
+    }
+}
@@ -0,0 +1,510 @@
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+use super::{Effect, Frame};
+use crate::{Color, ControllerGroup, OpenRgbError, OpenRgbResult, ZoneData};
+
+/// A position in the space shared by all controllers of a [`Layout`].
+///
+/// The unit is up to you, the default layout uses one LED (or key) pitch.
+/// In the default layout `x` grows to the right and `y` downwards, like the rows of a matrix zone.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    /// Horizontal coordinate, growing to the right.
+    pub x: f32,
+    /// Vertical coordinate, growing downwards.
+    pub y: f32,
+    /// Depth coordinate, such as the height above the desk.
+    pub z: f32,
+}
+
+impl Point {
+    /// The origin of the space.
+    pub const ORIGIN: Self = Self::new(0.0, 0.0, 0.0);
+
+    /// Creates a point at `x`, `y` and `z`.
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Returns the distance between this point and `other`.
+    pub fn distance(self, other: Self) -> f32 {
+        let d = other - self;
+        (d.x * d.x + d.y * d.y + d.z * d.z).sqrt()
+    }
+
+    /// Returns the point `t` of the way from this point to `other`.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+        )
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl From<(f32, f32)> for Point {
+    fn from((x, y): (f32, f32)) -> Self {
+        Self::new(x, y, 0.0)
+    }
+}
+
+impl From<(f32, f32, f32)> for Point {
+    fn from((x, y, z): (f32, f32, f32)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+/// A path the LEDs of a linear zone are spread along evenly, see [`ControllerLayout::set_zone_path`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Path {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl Path {
+    /// Creates a straight path from `from` to `to`, with the first LED at `from` and the last at `to`.
+    pub fn line(from: impl Into<Point>, to: impl Into<Point>) -> Self {
+        Self::polyline([from.into(), to.into()])
+    }
+
+    /// Creates a path through `points`, such as a strip running around the edges of a desk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` is empty.
+    pub fn polyline<P: Into<Point>>(points: impl IntoIterator<Item = P>) -> Self {
+        let points = points.into_iter().map(Into::into).collect::<Vec<_>>();
+        assert!(!points.is_empty(), "path needs at least one point");
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    /// Creates a circle around `center` in the `x`/`y` plane, such as the ring of a fan.
+    ///
+    /// The first LED is at the right of the center and the LEDs go towards growing `y`.
+    /// Since the path is closed, the last LED is one step before the first.
+    pub fn circle(center: impl Into<Point>, radius: f32) -> Self {
+        const STEPS: usize = 64;
+        let center = center.into();
+        let points = (0..STEPS)
+            .map(|i| {
+                let angle = i as f32 / STEPS as f32 * std::f32::consts::TAU;
+                center + Point::new(radius * angle.cos(), radius * angle.sin(), 0.0)
+            })
+            .collect();
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    /// Returns the points of the path.
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// Returns true if the path ends where it starts.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the length of the path.
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(from, to)| from.distance(to)).sum()
+    }
+
+    /// Returns the point `t` of the way along the path, from 0.0 to 1.0.
+    pub fn at(&self, t: f32) -> Point {
+        let mut rest = t.clamp(0.0, 1.0) * self.length();
+        let mut last = self.points[0];
+        for (from, to) in self.segments() {
+            let len = from.distance(to);
+            if rest <= len && len > 0.0 {
+                return from.lerp(to, rest / len);
+            }
+            rest -= len;
+            last = to;
+        }
+        last
+    }
+
+    /// Returns `count` points spread evenly along the path.
+    pub fn spread(&self, count: usize) -> Vec<Point> {
+        let steps = match self.closed {
+            true => count,
+            false => count.saturating_sub(1),
+        }
+        .max(1) as f32;
+        (0..count).map(|i| self.at(i as f32 / steps)).collect()
+    }
+
+    fn segments(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let closing = self
+            .closed
+            .then(|| (self.points[self.points.len() - 1], self.points[0]));
+        self.points
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .chain(closing)
+    }
+}
+
+/// Positions of all LEDs of a [`ControllerGroup`] in one shared space, such as a room or desk.
+///
+/// [`Layout::new`] derives a default: matrix zones, such as keyboards, are placed by their matrix,
+/// and the LEDs of other zones in a row. Every zone starts below the previous one,
+/// and every controller below the previous controller.
+/// Then controllers can be moved with [`ControllerLayout::translate`]
+/// and zones laid along a [`Path`] with [`ControllerLayout::set_zone_path`].
+///
+/// Effects can then color LEDs by their position with [`Layout::sample`] or [`Spatial`],
+/// so a wave runs across the keyboard, the fans and the strips together.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use openrgb2::effects::{EffectRunner, Layout, Path, Point, Spatial};
+/// use openrgb2::color::Hsv;
+/// use openrgb2::{OpenRgbClient, OpenRgbResult};
+///
+/// # #[tokio::main]
+/// # async fn main() -> OpenRgbResult<()> {
+/// let client = OpenRgbClient::connect().await?;
+/// let group = client.get_all_controllers().await?;
+///
+/// let mut layout = Layout::new(&group);
+/// // a strip running up the left side of the monitor, and a fan next to it
+/// layout.controllers_mut()[1].set_zone_path(0, &Path::line((0.0, 10.0), (0.0, -10.0)))?;
+/// layout.controllers_mut()[2].set_zone_path(0, &Path::circle((-10.0, 0.0), 3.0))?;
+///
+/// let mut runner = EffectRunner::new(group);
+/// runner.start(Spatial::new(layout, |point: Point, t: Duration| {
+///     let hue = point.x * 10.0 + t.as_secs_f32() * 90.0;
+///     Hsv::new(hue, 1.0, 1.0).into()
+/// }));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layout {
+    controllers: Vec<ControllerLayout>,
+}
+
+impl Layout {
+    /// Creates the default layout of the controllers in `group`.
+    pub fn new(group: &ControllerGroup) -> Self {
+        let mut top = 0.0;
+        let controllers = group
+            .iter()
+            .map(|controller| {
+                let mut positions = Vec::with_capacity(controller.num_leds());
+                for zone in controller.zones() {
+                    top += default_zone_positions(zone, top, &mut positions);
+                }
+                // keep the LED count in line with the frames, even for odd zone data
+                positions.resize(controller.num_leds(), Point::new(0.0, top, 0.0));
+                ControllerLayout {
+                    id: controller.id(),
+                    name: controller.name().to_owned(),
+                    zones: controller.zones().to_vec(),
+                    positions,
+                }
+            })
+            .collect();
+        Self { controllers }
+    }
+
+    /// Returns the layouts of the single controllers, in the order of the group.
+    pub fn controllers(&self) -> &[ControllerLayout] {
+        &self.controllers
+    }
+
+    /// Returns the layouts of the single controllers, to move them around.
+    pub fn controllers_mut(&mut self) -> &mut [ControllerLayout] {
+        &mut self.controllers
+    }
+
+    /// Returns the position of every LED of all controllers.
+    pub fn positions(&self) -> impl Iterator<Item = Point> + '_ {
+        self.controllers
+            .iter()
+            .flat_map(|controller| controller.positions.iter().copied())
+    }
+
+    /// Returns the smallest and largest coordinates of all LEDs, `None` if there are no LEDs.
+    ///
+    /// Useful to scale a function to the size of the layout.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.positions().fold(None, |bounds, p| {
+            let (min, max) = bounds.unwrap_or((p, p));
+            Some((
+                Point::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                Point::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+            ))
+        })
+    }
+
+    /// Sets every LED in `frame` to the color `f` returns for its position.
+    ///
+    /// `frame` should be created from the same group as the layout.
+    pub fn sample(&self, frame: &mut Frame, mut f: impl FnMut(Point) -> Color) {
+        for (layout, controller) in self.controllers.iter().zip(frame.controllers_mut()) {
+            for (position, color) in layout.positions.iter().zip(controller.colors_mut()) {
+                *color = f(*position);
+            }
+        }
+    }
+}
+
+/// Positions of the LEDs of one controller in a [`Layout`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControllerLayout {
+    id: usize,
+    name: String,
+    zones: Vec<ZoneData>,
+    positions: Vec<Point>,
+}
+
+impl ControllerLayout {
+    /// Returns the id of the controller, see [`crate::Controller::id()`].
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns the name of the controller.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the zones of the controller.
+    pub fn zones(&self) -> &[ZoneData] {
+        &self.zones
+    }
+
+    /// Returns the positions of all LEDs of the controller.
+    pub fn positions(&self) -> &[Point] {
+        &self.positions
+    }
+
+    /// Returns the positions of all LEDs of the controller, to place them freely.
+    pub fn positions_mut(&mut self) -> &mut [Point] {
+        &mut self.positions
+    }
+
+    /// Moves all LEDs of the controller by `offset`.
+    pub fn translate(&mut self, offset: impl Into<Point>) {
+        let offset = offset.into();
+        for position in &mut self.positions {
+            *position = *position + offset;
+        }
+    }
+
+    /// Spreads the LEDs of zone `zone_id` evenly along `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller has no zone `zone_id`.
+    pub fn set_zone_path(&mut self, zone_id: usize, path: &Path) -> OpenRgbResult<()> {
+        let range = self.zone_range(zone_id)?;
+        let points = path.spread(range.len());
+        self.positions[range].copy_from_slice(&points);
+        Ok(())
+    }
+
+    /// Returns the positions of the LEDs of zone `zone_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller has no zone `zone_id`.
+    pub fn zone_positions_mut(&mut self, zone_id: usize) -> OpenRgbResult<&mut [Point]> {
+        let range = self.zone_range(zone_id)?;
+        Ok(&mut self.positions[range])
+    }
+
+    fn zone_range(&self, zone_id: usize) -> OpenRgbResult<std::ops::Range<usize>> {
+        let mut offset = 0;
+        for zone in &self.zones {
+            let end = (offset + zone.leds_count()).min(self.positions.len());
+            if zone.id() == zone_id {
+                return Ok(offset..end);
+            }
+            offset = end;
+        }
+        Err(OpenRgbError::CommandError(format!(
+            "Zone with id {zone_id} not found in controller {}",
+            self.name
+        )))
+    }
+}
+
+/// Pushes the default positions of the LEDs of `zone`, starting at row `top`, and returns the rows it takes.
+fn default_zone_positions(zone: &ZoneData, top: f32, positions: &mut Vec<Point>) -> f32 {
+    let start = positions.len();
+    let count = zone.leds_count();
+    positions.extend((0..count).map(|i| Point::new(i as f32, top, 0.0)));
+    let Some(matrix) = zone.matrix() else {
+        return 1.0;
+    };
+    for (row, leds) in matrix.rows_iter().enumerate() {
+        for (column, &led) in leds.enumerate() {
+            if let Some(position) = positions[start..].get_mut(led as usize) {
+                *position = Point::new(column as f32, top + row as f32, 0.0);
+            }
+        }
+    }
+    matrix.num_rows().max(1) as f32
+}
+
+/// An effect coloring each LED by its position in a [`Layout`] and the time.
+///
+/// See [`Layout`] for an example.
+pub struct Spatial<F> {
+    layout: Layout,
+    f: F,
+}
+
+impl<F> std::fmt::Debug for Spatial<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Spatial")
+            .field("layout", &self.layout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: FnMut(Point, Duration) -> Color + Send> Spatial<F> {
+    /// Creates an effect setting each LED to the color `f` returns for its position and the time.
+    pub fn new(layout: Layout, f: F) -> Self {
+        Self { layout, f }
+    }
+
+    /// Returns the layout the LEDs are placed by.
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+}
+
+impl<F: FnMut(Point, Duration) -> Color + Send> Effect for Spatial<F> {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        let f = &mut self.f;
+        self.layout.sample(frame, |point| f(point, t));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use array2d::Array2D;
+
+    use crate::DeviceType;
+    use crate::mock::{MockController, MockServer, MockZone};
+
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let path = Path::polyline([(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)]);
+        assert_eq!(path.length(), 4.0);
+        assert_eq!(path.at(0.75), Point::new(2.0, 1.0, 0.0));
+        assert_eq!(
+            path.spread(3),
+            [(0.0, 0.0).into(), (2.0, 0.0).into(), (2.0, 2.0).into()]
+        );
+
+        let circle = Path::circle((1.0, 1.0), 1.0).spread(4);
+        assert!(circle[0].distance(Point::new(2.0, 1.0, 0.0)) < 1e-3);
+        assert!(circle[1].distance(Point::new(1.0, 2.0, 0.0)) < 1e-3);
+        assert!(circle[3].distance(Point::new(1.0, 0.0, 0.0)) < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_layout() -> OpenRgbResult<()> {
+        // 2x2 keyboard with the top right key missing, LEDs numbered out of order
+        let keys = Array2D::from_row_major(&[1, u32::MAX, 0, 2], 2, 2).unwrap();
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Keyboard", DeviceType::Keyboard)
+                    .with_zone(MockZone::matrix("Keys", 1, 1).with_matrix(keys)),
+            )
+            .with_controller(
+                MockController::new("Strip", DeviceType::LEDStrip)
+                    .with_zone(MockZone::linear("Top", 2))
+                    .with_zone(MockZone::linear("Side", 3)),
+            )
+            .start()
+            .await?;
+        let group = server.client().await?.get_all_controllers().await?;
+
+        let mut layout = Layout::new(&group);
+        let p = |x, y| Point::new(x, y, 0.0);
+        assert_eq!(
+            layout.controllers()[0].positions(),
+            [p(0.0, 1.0), p(0.0, 0.0), p(1.0, 1.0)]
+        );
+        assert_eq!(
+            layout.controllers()[1].positions(),
+            [
+                p(0.0, 2.0),
+                p(1.0, 2.0),
+                p(0.0, 3.0),
+                p(1.0, 3.0),
+                p(2.0, 3.0)
+            ]
+        );
+
+        let strip = &mut layout.controllers_mut()[1];
+        strip.set_zone_path(1, &Path::line((5.0, 0.0, 1.0), (5.0, 4.0, 1.0)))?;
+        strip.translate((1.0, 0.0));
+        assert_eq!(strip.positions()[4], Point::new(6.0, 4.0, 1.0));
+        assert!(
+            strip
+                .set_zone_path(2, &Path::line((0.0, 0.0), (1.0, 0.0)))
+                .is_err()
+        );
+        assert_eq!(
+            layout.bounds(),
+            Some((Point::ORIGIN, Point::new(6.0, 4.0, 1.0)))
+        );
+
+        let mut frame = Frame::new(&group);
+        let mut effect = Spatial::new(layout, |point: Point, t: Duration| {
+            let on = point.x >= t.as_secs_f32();
+            if on {
+                Color::new(255, 255, 255)
+            } else {
+                Color::default()
+            }
+        });
+        effect.render(Duration::from_secs(1), &mut frame);
+        let (white, black) = (Color::new(255, 255, 255), Color::default());
+        assert_eq!(frame.controllers()[0].colors(), [black, black, white]);
+        assert_eq!(
+            frame.controllers()[1].colors(),
+            [white, white, white, white, white]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+//! Effects that render frames for a [`ControllerGroup`] in a loop.
+//!
+//! An [`Effect`] draws into a [`Frame`], which holds a color buffer per controller split into its zones.
+//! The [`EffectRunner`] renders the effect at a target frame rate and sends each frame
+//! through a [`crate::CommandGroup`], so every frame is one update per controller.
+//!
+//! Built-in effects are [`Rainbow`], [`Breathing`], [`ColorWave`] and [`StaticGradient`].
+//! Closures taking the time and the frame are effects too.
+//! To color LEDs by where they are, place them in a [`Layout`] and use [`Spatial`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use openrgb2::effects::{Breathing, EffectRunner, Frame, Rainbow};
+//! use openrgb2::{Color, OpenRgbClient, OpenRgbResult};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let client = OpenRgbClient::connect().await?;
+//! let group = client.get_all_controllers().await?;
+//! group.init().await?;
+//!
+//! let mut runner = EffectRunner::new(group).with_fps(60.0);
+//! runner.start(Rainbow::new(Duration::from_secs(5)));
+//! tokio::time::sleep(Duration::from_secs(10)).await;
+//!
+//! // swap to another effect without stopping
+//! runner.start(Breathing::new(Color::new(255, 0, 0), Duration::from_secs(2)));
+//! tokio::time::sleep(Duration::from_secs(10)).await;
+//!
+//! // or write one inline
+//! runner.start(|t: Duration, frame: &mut Frame| {
+//!     let on = t.as_millis() / 500 % 2 == 0;
+//!     frame.fill(if on { Color::new(255, 255, 255) } else { Color::default() });
+//! });
+//! tokio::time::sleep(Duration::from_secs(10)).await;
+//!
+//! runner.stop().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ControllerGroup`]: crate::ControllerGroup
+
+mod builtin;
+mod frame;
+mod layout;
+mod runner;
+
+pub use builtin::*;
+pub use frame::*;
+pub use layout::*;
+pub use runner::*;
+
+use std::time::Duration;
+
+/// Something that draws frames, run by an [`EffectRunner`].
+pub trait Effect: Send {
+    /// Draws the frame at time `t`, counted from when the effect was started.
+    ///
+    /// `frame` still holds the previous frame, so effects can build on it or overwrite it.
+    fn render(&mut self, t: Duration, frame: &mut Frame);
+}
+
+impl<F: FnMut(Duration, &mut Frame) + Send> Effect for F {
+    fn render(&mut self, t: Duration, frame: &mut Frame) {
+        self(t, frame);
+    }
+}
+
+/// Returns how far `t` is into the current cycle of `period`, from 0.0 to 1.0.
+fn phase(t: Duration, period: Duration) -> f32 {
+    if period.is_zero() {
+        return 0.0;
+    }
+    (t.as_secs_f64() / period.as_secs_f64()).fract() as f32
+}
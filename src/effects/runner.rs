@@ -0,0 +1,238 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use super::{Effect, Frame};
+use crate::{ControllerGroup, OpenRgbResult};
+
+/// Frame rate of an [`EffectRunner`] unless set with [`EffectRunner::with_fps`].
+pub const DEFAULT_FPS: f32 = 30.0;
+
+/// Renders an [`Effect`] at a target frame rate and sends the frames to a [`ControllerGroup`].
+///
+/// The effect runs in a background task. Each frame is rendered into a [`Frame`]
+/// shaped by the zones of the controllers, and sent with one update per controller.
+/// When rendering and sending take longer than a frame, frames are skipped instead of queued.
+///
+/// See [the module documentation](super) for an example.
+pub struct EffectRunner {
+    group: Arc<ControllerGroup>,
+    period: Duration,
+    running: Option<Running>,
+}
+
+/// Background task rendering an effect.
+struct Running {
+    /// Sends effects to swap to, dropped to stop the task.
+    swap: mpsc::UnboundedSender<Box<dyn Effect>>,
+    task: JoinHandle<OpenRgbResult<()>>,
+}
+
+impl std::fmt::Debug for EffectRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EffectRunner")
+            .field("group", &self.group)
+            .field("period", &self.period)
+            .field("running", &self.is_running())
+            .finish()
+    }
+}
+
+impl EffectRunner {
+    /// Creates a runner for the controllers in `group`, running at [`DEFAULT_FPS`].
+    ///
+    /// Nothing is sent until [`Self::start`] is called.
+    /// Controllers should be in a mode that shows per-LED colors, see [`ControllerGroup::init`].
+    pub fn new(group: ControllerGroup) -> Self {
+        Self {
+            group: Arc::new(group),
+            period: tick_period(DEFAULT_FPS),
+            running: None,
+        }
+    }
+
+    /// Sets the target frame rate, frames per second.
+    ///
+    /// Applies the next time the runner is started.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fps` is not positive or not finite.
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        assert!(
+            fps.is_finite() && fps > 0.0,
+            "frame rate must be positive and finite, got {fps}"
+        );
+        self.period = tick_period(fps);
+        self
+    }
+
+    /// Returns the controllers the frames are sent to.
+    pub fn group(&self) -> &ControllerGroup {
+        &self.group
+    }
+
+    /// Returns true while an effect is running.
+    ///
+    /// Turns false when sending a frame failed, call [`Self::stop`] to get the error.
+    pub fn is_running(&self) -> bool {
+        self.running
+            .as_ref()
+            .is_some_and(|running| !running.task.is_finished())
+    }
+
+    /// Starts rendering `effect`, counting its time from now.
+    ///
+    /// If an effect is already running it is swapped for `effect` at the next frame,
+    /// keeping the colors of the last frame for the new effect to start from.
+    /// Must be called within a tokio runtime.
+    pub fn start(&mut self, effect: impl Effect + 'static) {
+        let effect = Box::new(effect);
+        let effect = match &self.running {
+            Some(running) if !running.task.is_finished() => match running.swap.send(effect) {
+                Ok(()) => return,
+                Err(mpsc::error::SendError(effect)) => effect,
+            },
+            _ => effect,
+        };
+        let (swap, swaps) = mpsc::unbounded_channel();
+        let task = tokio::spawn(run(self.group.clone(), effect, self.period, swaps));
+        self.running = Some(Running { swap, task });
+    }
+
+    /// Stops the running effect after the current frame. The LEDs keep the colors of the last frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that stopped the effect early, such as the connection being lost.
+    pub async fn stop(&mut self) -> OpenRgbResult<()> {
+        let Some(Running { swap, task }) = self.running.take() else {
+            return Ok(());
+        };
+        drop(swap);
+        match task.await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Returns the time between ticks at `rate` ticks per second, from 1 ns up to a day.
+///
+/// Tokio intervals panic on a zero period or on a deadline past the end of time,
+/// so the period is clamped instead of converted as is.
+pub(crate) fn tick_period(rate: f32) -> Duration {
+    const MAX_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+    Duration::try_from_secs_f32(1.0 / rate)
+        .map_or(MAX_PERIOD, |period| period.min(MAX_PERIOD))
+        .max(Duration::from_nanos(1))
+}
+
+/// Renders `effect` once every `period`, until `swaps` is closed.
+async fn run(
+    group: Arc<ControllerGroup>,
+    mut effect: Box<dyn Effect>,
+    period: Duration,
+    mut swaps: mpsc::UnboundedReceiver<Box<dyn Effect>>,
+) -> OpenRgbResult<()> {
+    let mut frame = Frame::new(&group);
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut started = Instant::now();
+    loop {
+        interval.tick().await;
+        loop {
+            match swaps.try_recv() {
+                Ok(next) => {
+                    effect = next;
+                    started = Instant::now();
+                }
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+        effect.render(started.elapsed(), &mut frame);
+        frame.send(&group).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockServer, MockZone};
+    use crate::{Color, DeviceType, OpenRgbError};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_runner() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Strip", DeviceType::LEDStrip)
+                    .with_zone(MockZone::linear("Strip", 2)),
+            )
+            .start()
+            .await?;
+        let mut client = server.client().await?;
+        let mut runner = EffectRunner::new(client.get_all_controllers().await?).with_fps(200.0);
+        assert!(!runner.is_running());
+
+        let red = Color::new(255, 0, 0);
+        let (frames, mut rendered) = mpsc::unbounded_channel();
+        runner.start(move |t: Duration, frame: &mut Frame| {
+            frame.fill(red);
+            let _ = frames.send(t);
+        });
+        assert!(runner.is_running());
+        let first = rendered.recv().await.expect("first frame");
+        let second = rendered.recv().await.expect("second frame");
+        assert!(second > first);
+
+        let blue = Color::new(0, 0, 255);
+        runner.start(move |_: Duration, frame: &mut Frame| {
+            assert_eq!(frame.controllers()[0].colors()[0], red);
+            frame.controllers_mut()[0].colors_mut()[1] = blue;
+        });
+        // the first effect is dropped once the second one took over
+        while rendered.recv().await.is_some() {}
+        runner.stop().await?;
+        assert!(!runner.is_running());
+
+        client.get_controller_count().await?;
+        assert_eq!(server.colors(0), Some(vec![red, blue]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_runner_error() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Strip", DeviceType::LEDStrip)
+                    .with_zone(MockZone::linear("Strip", 2)),
+            )
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let mut runner = EffectRunner::new(client.get_all_controllers().await?).with_fps(200.0);
+        runner.start(|_: Duration, frame: &mut Frame| frame.fill(Color::new(255, 0, 0)));
+        server.disconnect_clients();
+        while runner.is_running() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(matches!(
+            runner.stop().await,
+            Err(OpenRgbError::CommunicationError { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tick_period() {
+        assert_eq!(tick_period(4.0), Duration::from_millis(250));
+        assert_eq!(tick_period(f32::MAX), Duration::from_nanos(1));
+        assert_eq!(tick_period(1e-30), Duration::from_secs(24 * 60 * 60));
+    }
+}
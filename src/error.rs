@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::{ColorMode, Direction};
+
 /// Type alias for `Result<T, OpenRgbError>`
 pub type OpenRgbResult<T> = std::result::Result<T, OpenRgbError>;
 
@@ -45,7 +47,148 @@ pub enum OpenRgbError {
         min_protocol_version: u32,
     },
 
+    /// OpenRGB server did not respond in time, see [`crate::Timeouts`].
+    ///
+    /// The connection is closed afterwards, the next call reconnects if a reconnect policy is set.
+    #[error(
+        "{operation} timed out while communicating with OpenRGB server (packet id {packet_id:?})"
+    )]
+    Timeout {
+        /// What timed out, such as connecting or reading a reply.
+        operation: String,
+
+        /// Raw id of the packet that was written or waited for, `None` when connecting.
+        packet_id: Option<u32>,
+    },
+
     /// Command was given invalid parameters
     #[error("Invalid command: {0}")]
     CommandError(String),
+
+    /// Failed reading or writing a file, such as a [`crate::ProfileFile`].
+    #[error("Failed reading or writing file {path:?}")]
+    FileError {
+        /// Path of the file.
+        path: std::path::PathBuf,
+
+        /// Source error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Image data is not valid, see [`crate::image::Image`].
+    #[error("Invalid image: {0}")]
+    ImageError(String),
+
+    /// Audio data is not valid, such as a WAV file in an unsupported format.
+    #[error("Invalid audio: {0}")]
+    AudioError(String),
+
+    /// Calibration file is not valid, see [`crate::Calibrations`].
+    #[error(transparent)]
+    CalibrationError(#[from] ParseCalibrationError),
+
+    /// Mode setting is not supported by the mode, see [`crate::ModeEditor`].
+    #[error(transparent)]
+    ModeError(#[from] ModeError),
+}
+
+/// Setting of a mode, see [`ModeError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ModeSetting {
+    /// Speed of the effect.
+    Speed,
+    /// Brightness of the effect.
+    Brightness,
+    /// Direction of the effect.
+    Direction,
+    /// Mode specific colors.
+    Colors,
+}
+
+impl std::fmt::Display for ModeSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Speed => "speed",
+            Self::Brightness => "brightness",
+            Self::Direction => "direction",
+            Self::Colors => "colors",
+        })
+    }
+}
+
+/// Change that a mode does not support, returned by [`crate::ModeEditor`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ModeError {
+    /// Controller has no mode with this name.
+    #[error("Controller {controller:?} has no mode named {mode:?}")]
+    NotFound {
+        /// Controller name.
+        controller: String,
+
+        /// Requested mode name.
+        mode: String,
+    },
+
+    /// Mode does not have this setting.
+    #[error("Mode {mode:?} has no {setting} setting")]
+    Unsupported {
+        /// Mode name.
+        mode: String,
+
+        /// Setting that was changed.
+        setting: ModeSetting,
+    },
+
+    /// Value is outside of the range the mode allows, the number of colors for [`ModeSetting::Colors`].
+    #[error("Mode {mode:?} {setting} must be between {min} and {max}, got {value}")]
+    OutOfRange {
+        /// Mode name.
+        mode: String,
+
+        /// Setting that was changed.
+        setting: ModeSetting,
+
+        /// Requested value.
+        value: u32,
+
+        /// Lowest allowed value.
+        min: u32,
+
+        /// Highest allowed value.
+        max: u32,
+    },
+
+    /// Mode cannot run in this direction.
+    #[error("Mode {mode:?} does not support direction {direction:?}")]
+    UnsupportedDirection {
+        /// Mode name.
+        mode: String,
+
+        /// Requested direction.
+        direction: Direction,
+    },
+
+    /// Mode does not support this color mode.
+    #[error("Mode {mode:?} does not support color mode {color_mode:?}")]
+    UnsupportedColorMode {
+        /// Mode name.
+        mode: String,
+
+        /// Requested color mode.
+        color_mode: ColorMode,
+    },
+}
+
+/// Invalid line in a calibration file, see [`crate::Calibrations`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid calibration on line {line}: {message}")]
+pub struct ParseCalibrationError {
+    /// Line number, starting at 1.
+    pub line: usize,
+
+    /// What is wrong with the line.
+    pub message: String,
 }
@@ -0,0 +1,143 @@
+use std::path::Path;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use super::{DrawOptions, Image, ppm};
+use crate::{OpenRgbResult, Zone};
+
+/// A sequence of images with the time each one is shown, such as the frames of a GIF.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Animation {
+    frames: Vec<(Image, Duration)>,
+}
+
+impl Animation {
+    /// Creates an animation without frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `image` as the next frame, shown for `delay`.
+    pub fn with_frame(mut self, image: Image, delay: Duration) -> Self {
+        self.push(image, delay);
+        self
+    }
+
+    /// Adds `image` as the next frame, shown for `delay`.
+    pub fn push(&mut self, image: Image, delay: Duration) {
+        self.frames.push((image, delay));
+    }
+
+    /// Parses all images of a Netpbm file, see [`Image::from_ppm`], each shown for `delay`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::OpenRgbError::ImageError`] if an image is not valid.
+    pub fn from_ppm(mut bytes: &[u8], delay: Duration) -> OpenRgbResult<Self> {
+        let mut animation = Self::new();
+        while !bytes.trim_ascii_start().is_empty() {
+            let (image, rest) = ppm::parse(bytes)?;
+            animation.push(image, delay);
+            bytes = rest;
+        }
+        Ok(animation)
+    }
+
+    /// Reads all images of a Netpbm file, see [`Self::from_ppm`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::OpenRgbError::FileError`] if the file cannot be read,
+    /// or [`crate::OpenRgbError::ImageError`] if an image is not valid.
+    pub fn load(path: impl AsRef<Path>, delay: Duration) -> OpenRgbResult<Self> {
+        Self::from_ppm(&super::read(path.as_ref())?, delay)
+    }
+
+    /// Returns the frames with the time each one is shown.
+    pub fn frames(&self) -> &[(Image, Duration)] {
+        &self.frames
+    }
+
+    /// Returns the number of frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if the animation has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the time it takes to play all frames.
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|(_, delay)| *delay).sum()
+    }
+
+    /// Plays the animation once on the matrix of `zone`, sending one [`crate::Command`] per frame.
+    ///
+    /// Frames are timed from the start, so slow updates do not make the animation drift.
+    /// Call it again to loop.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the zone has no matrix or a frame cannot be sent.
+    pub async fn play(&self, zone: &Zone<'_>, options: &DrawOptions) -> OpenRgbResult<()> {
+        let mut view = zone.matrix_view()?;
+        let mut next = Instant::now();
+        for (image, delay) in &self.frames {
+            view.draw_image(image, options);
+            view.cmd()?.execute().await?;
+            next += *delay;
+            tokio::time::sleep_until(next).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::Fit;
+    use crate::mock::{MockController, MockServer, MockZone, RecordedPacket};
+    use crate::{Color, DeviceType};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_play() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Mousepad", DeviceType::MouseMat)
+                    .with_zone(MockZone::matrix("Pad", 1, 2)),
+            )
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let mousepad = client.get_controller(0).await?;
+
+        let mut ppm = b"P3 1 1 255 255 0 0\n".to_vec();
+        ppm.extend(b"P6 1 1 255\n\x00\x00\xff\n");
+        let animation = Animation::from_ppm(&ppm, Duration::from_millis(5))?;
+        assert_eq!(animation.len(), 2);
+        assert_eq!(animation.duration(), Duration::from_millis(10));
+
+        let options = DrawOptions::default().with_fit(Fit::Stretch);
+        animation.play(&mousepad.get_zone(0)?, &options).await?;
+        client.get_controller(0).await?;
+        let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+        assert_eq!(
+            server.received(),
+            [
+                RecordedPacket::UpdateLeds {
+                    controller_id: 0,
+                    colors: vec![red; 2],
+                },
+                RecordedPacket::UpdateLeds {
+                    controller_id: 0,
+                    colors: vec![blue; 2],
+                },
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+use super::Image;
+use crate::{Color, MatrixView};
+
+/// How an image is sampled when its size differs from the matrix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scaling {
+    /// Takes the image pixel under the center of each LED, keeping hard edges such as pixel art.
+    #[default]
+    Nearest,
+    /// Blends the four image pixels around the center of each LED, smoothing photos and gradients.
+    Bilinear,
+}
+
+/// How an image is fitted onto the matrix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fit {
+    /// Scales the image to cover the whole matrix, distorting it if the aspect ratios differ.
+    Stretch,
+    /// Scales the image to fit inside the matrix, keeping its aspect ratio.
+    /// The LEDs around it are set to the background, if there is one.
+    #[default]
+    Contain,
+    /// Scales the image to cover the whole matrix, keeping its aspect ratio.
+    /// The edges of the image that do not fit are cropped.
+    Cover,
+}
+
+/// Settings for [`MatrixView::draw_image`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawOptions {
+    scaling: Scaling,
+    fit: Fit,
+    background: Option<Color>,
+}
+
+impl DrawOptions {
+    /// Sets how the image is sampled, [`Scaling::Nearest`] by default.
+    pub fn with_scaling(mut self, scaling: Scaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    /// Sets how the image is fitted onto the matrix, [`Fit::Contain`] by default.
+    pub fn with_fit(mut self, fit: Fit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Sets the color of the LEDs the image does not cover.
+    ///
+    /// By default these LEDs keep their colors.
+    pub fn with_background(mut self, color: impl Into<Color>) -> Self {
+        self.background = Some(color.into());
+        self
+    }
+
+    /// Returns how the image is sampled.
+    pub fn scaling(&self) -> Scaling {
+        self.scaling
+    }
+
+    /// Returns how the image is fitted onto the matrix.
+    pub fn fit(&self) -> Fit {
+        self.fit
+    }
+
+    /// Returns the color of the LEDs the image does not cover.
+    pub fn background(&self) -> Option<Color> {
+        self.background
+    }
+}
+
+/// A rectangle with its top left corner at `x`, `y`.
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+impl MatrixView<'_> {
+    /// Draws `image` onto the matrix, scaled and fitted as set by `options`.
+    ///
+    /// Every matrix position is one pixel, so the image keeps its aspect ratio in positions
+    /// rather than physical size. Positions without an LED are skipped.
+    /// To show part of an image, [`Image::crop`] it first.
+    pub fn draw_image(&mut self, image: &Image, options: &DrawOptions) {
+        let (width, height) = (self.width() as f32, self.height() as f32);
+        if image.is_empty() || width == 0.0 || height == 0.0 {
+            return;
+        }
+        let whole = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: image.width() as f32,
+            height: image.height() as f32,
+        };
+        let matrix = Rect {
+            x: 0.0,
+            y: 0.0,
+            width,
+            height,
+        };
+        // the part of the matrix the image is drawn on, and the part of the image drawn there
+        let (target, source) = match options.fit {
+            Fit::Stretch => (matrix, whole),
+            Fit::Contain => {
+                let scale = (width / whole.width).min(height / whole.height);
+                let (w, h) = (whole.width * scale, whole.height * scale);
+                let target = Rect {
+                    x: (width - w) / 2.0,
+                    y: (height - h) / 2.0,
+                    width: w,
+                    height: h,
+                };
+                (target, whole)
+            }
+            Fit::Cover => {
+                let scale = (width / whole.width).max(height / whole.height);
+                let (w, h) = (width / scale, height / scale);
+                let source = Rect {
+                    x: (whole.width - w) / 2.0,
+                    y: (whole.height - h) / 2.0,
+                    width: w,
+                    height: h,
+                };
+                (matrix, source)
+            }
+        };
+
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let Some(led) = self.zone_led(x, y) else {
+                    continue;
+                };
+                let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+                let inside = cx >= target.x
+                    && cx < target.x + target.width
+                    && cy >= target.y
+                    && cy < target.y + target.height;
+                let color = if inside {
+                    let u = source.x + (cx - target.x) / target.width * source.width;
+                    let v = source.y + (cy - target.y) / target.height * source.height;
+                    sample(image, u, v, options.scaling)
+                } else if let Some(background) = options.background {
+                    background
+                } else {
+                    continue;
+                };
+                self.colors_mut()[led] = color;
+            }
+        }
+    }
+}
+
+/// Returns the color of `image` at `u`, `v`, in pixels from its top left corner.
+fn sample(image: &Image, u: f32, v: f32, scaling: Scaling) -> Color {
+    match scaling {
+        Scaling::Nearest => image.clamped(u.floor() as isize, v.floor() as isize),
+        Scaling::Bilinear => {
+            // pixel centers are at half pixels
+            let (u, v) = (u - 0.5, v - 0.5);
+            let (x, y) = (u.floor(), v.floor());
+            let (fx, fy) = (u - x, v - y);
+            let (x, y) = (x as isize, y as isize);
+            let mix = |a: Color, b: Color, t: f32| {
+                let channel = |a: u8, b: u8| f32::from(a) + (f32::from(b) - f32::from(a)) * t;
+                [channel(a.r, b.r), channel(a.g, b.g), channel(a.b, b.b)]
+            };
+            let top = mix(image.clamped(x, y), image.clamped(x + 1, y), fx);
+            let bottom = mix(image.clamped(x, y + 1), image.clamped(x + 1, y + 1), fx);
+            let [r, g, b] =
+                std::array::from_fn(|i| (top[i] + (bottom[i] - top[i]) * fy).round() as u8);
+            Color::new(r, g, b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use array2d::Array2D;
+
+    use crate::mock::{MockController, MockServer, MockZone};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_draw_image() -> OpenRgbResult<()> {
+        // 4x2 matrix with a hole at the bottom left
+        let keys = Array2D::from_row_major(&[0, 1, 2, 3, u32::MAX, 4, 5, 6], 2, 4).unwrap();
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Keyboard", DeviceType::Keyboard)
+                    .with_zone(MockZone::matrix("Keys", 1, 1).with_matrix(keys)),
+            )
+            .start()
+            .await?;
+        let keyboard = server.client().await?.get_controller(0).await?;
+        let zone = keyboard.get_zone(0)?;
+        let mut view = zone.matrix_view()?;
+        let (red, blue, green) = (
+            Color::new(255, 0, 0),
+            Color::new(0, 0, 255),
+            Color::new(0, 255, 0),
+        );
+        let rows = |view: &MatrixView| view.rows().map(Iterator::collect).collect::<Vec<Vec<_>>>();
+
+        // left half red, right half blue
+        let halves = Image::new(2, 1, vec![red, blue])?;
+        let stretch = DrawOptions::default().with_fit(Fit::Stretch);
+        view.draw_image(&halves, &stretch);
+        assert_eq!(
+            rows(&view),
+            [
+                vec![Some(red), Some(red), Some(blue), Some(blue)],
+                vec![None, Some(red), Some(blue), Some(blue)],
+            ]
+        );
+
+        // a square image fits in the middle two columns
+        let square = Image::from_fn(2, 2, |_, _| green);
+        view.draw_image(&square, &DrawOptions::default().with_background(blue));
+        assert_eq!(
+            rows(&view),
+            [
+                vec![Some(blue), Some(green), Some(green), Some(blue)],
+                vec![None, Some(green), Some(green), Some(blue)],
+            ]
+        );
+
+        // only the middle rows of a tall image are shown
+        let stripes = Image::from_fn(1, 4, |_, y| if y % 3 == 0 { red } else { blue });
+        view.draw_image(&stripes, &DrawOptions::default().with_fit(Fit::Cover));
+        assert_eq!(rows(&view)[0], [Some(blue); 4]);
+
+        let smooth = stretch.with_scaling(Scaling::Bilinear);
+        view.draw_image(&halves, &smooth);
+        assert_eq!(
+            rows(&view)[0],
+            [
+                Some(red),
+                Some(Color::new(191, 0, 64)),
+                Some(Color::new(64, 0, 191)),
+                Some(blue)
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Images and animations drawn onto matrix zones, such as logos on a keyboard.
+//!
+//! An [`Image`] is a buffer of colors, created from raw RGB bytes, a function,
+//! or a Netpbm file (PPM or PAM, which most image tools can export).
+//! [`crate::MatrixView::draw_image`] scales it onto the matrix of a zone as set by [`DrawOptions`],
+//! and an [`Animation`] plays a sequence of images with one LED update per frame.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use openrgb2::image::{Animation, DrawOptions, Fit, Image, Scaling};
+//! use openrgb2::{OpenRgbClient, OpenRgbResult};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let client = OpenRgbClient::connect().await?;
+//! let keyboard = client.get_controller(0).await?;
+//! let zone = keyboard.get_zone(0)?;
+//! let options = DrawOptions::default()
+//!     .with_scaling(Scaling::Bilinear)
+//!     .with_fit(Fit::Cover);
+//!
+//! let logo = Image::load("logo.ppm")?;
+//! let mut view = zone.matrix_view()?;
+//! view.draw_image(&logo, &options);
+//! view.execute().await?;
+//!
+//! // all images of a multi-image PPM file, 10 frames per second
+//! let frames = Animation::load("clip.ppm", Duration::from_millis(100))?;
+//! frames.play(&zone, &options).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod animation;
+mod draw;
+mod ppm;
+
+pub use animation::*;
+pub use draw::*;
+
+use std::path::Path;
+
+use crate::{Color, OpenRgbError, OpenRgbResult};
+
+/// An image, stored as colors row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    /// Creates an image from `pixels`, row by row starting at the top left.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::ImageError`] if there are not `width * height` pixels.
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> OpenRgbResult<Self> {
+        if Some(pixels.len()) != width.checked_mul(height) {
+            return Err(OpenRgbError::ImageError(format!(
+                "{width}x{height} image needs {} pixels, got {}",
+                width.saturating_mul(height),
+                pixels.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Creates an image with the color `f` returns for each `x`, `y`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> Color) -> Self {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Creates an image from raw RGB bytes, three per pixel, row by row starting at the top left.
+    ///
+    /// This is the layout of most decoded frames, such as the output of `ffmpeg -pix_fmt rgb24 -f rawvideo`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::ImageError`] if there are not `width * height * 3` bytes.
+    pub fn from_rgb(width: usize, height: usize, bytes: &[u8]) -> OpenRgbResult<Self> {
+        if bytes.len() % 3 != 0 {
+            return Err(OpenRgbError::ImageError(format!(
+                "RGB data length {} is not a multiple of 3",
+                bytes.len()
+            )));
+        }
+        let pixels = bytes
+            .chunks_exact(3)
+            .map(|rgb| Color::new(rgb[0], rgb[1], rgb[2]))
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    /// Parses a Netpbm image: a binary (`P6`) or plain (`P3`) PPM, or a PAM (`P7`).
+    ///
+    /// PAM images may be grayscale or RGB, with or without alpha.
+    /// Transparent pixels are blended onto black.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::ImageError`] if `bytes` is not a supported image.
+    pub fn from_ppm(bytes: &[u8]) -> OpenRgbResult<Self> {
+        let (image, _) = ppm::parse(bytes)?;
+        Ok(image)
+    }
+
+    /// Reads a Netpbm image from the file at `path`, see [`Self::from_ppm`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpenRgbError::FileError`] if the file cannot be read,
+    /// or [`OpenRgbError::ImageError`] if it is not a supported image.
+    pub fn load(path: impl AsRef<Path>) -> OpenRgbResult<Self> {
+        Self::from_ppm(&read(path.as_ref())?)
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns true if the image has no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// Returns all pixels, row by row starting at the top left.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Returns the pixel at `x`, `y`, `None` if it is outside the image.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.pixels[y * self.width + x])
+    }
+
+    /// Returns the part of the image `width` by `height` pixels with its top left corner at `x`, `y`.
+    ///
+    /// The part outside of the image is cut off, so the result may be smaller.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        Self::from_fn(width, height, |cx, cy| {
+            self.pixels[(y + cy) * self.width + x + cx]
+        })
+    }
+
+    /// Returns the pixel at `x`, `y`, using the nearest edge pixel outside the image.
+    fn clamped(&self, x: isize, y: isize) -> Color {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        self.pixels[y * self.width + x]
+    }
+}
+
+fn read(path: &Path) -> OpenRgbResult<Vec<u8>> {
+    std::fs::read(path).map_err(|source| OpenRgbError::FileError {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image() -> OpenRgbResult<()> {
+        let image = Image::from_rgb(2, 2, &[255, 0, 0, 0, 255, 0, 0, 0, 255, 1, 2, 3])?;
+        assert_eq!(image.pixel(1, 0), Some(Color::new(0, 255, 0)));
+        assert_eq!(image.pixel(1, 1), Some(Color::new(1, 2, 3)));
+        assert_eq!(image.pixel(2, 0), None);
+        assert!(Image::from_rgb(2, 2, &[0; 9]).is_err());
+        assert!(Image::new(3, 1, vec![Color::default(); 2]).is_err());
+
+        let image = Image::from_fn(4, 3, |x, y| Color::new(x as u8, y as u8, 0));
+        let cropped = image.crop(2, 1, 5, 1);
+        assert_eq!((cropped.width(), cropped.height()), (2, 1));
+        assert_eq!(cropped.pixels(), [Color::new(2, 1, 0), Color::new(3, 1, 0)]);
+        assert!(image.crop(4, 0, 1, 1).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+//! Netpbm images: PPM (`P3` and `P6`) and PAM (`P7`).
+//!
+//! See <https://netpbm.sourceforge.net/doc/ppm.html> and <https://netpbm.sourceforge.net/doc/pam.html>.
+
+use super::Image;
+use crate::{Color, OpenRgbError, OpenRgbResult};
+
+/// Parses the image at the start of `bytes`, and returns it with the bytes after it.
+///
+/// Netpbm files may hold several images one after another.
+pub(super) fn parse(bytes: &[u8]) -> OpenRgbResult<(Image, &[u8])> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let header = match reader.token() {
+        Some(b"P3") => Header {
+            plain: true,
+            ..reader.ppm_header()?
+        },
+        Some(b"P6") => reader.ppm_header()?,
+        Some(b"P7") => reader.pam_header()?,
+        _ => return Err(invalid("expected a P3, P6 or P7 Netpbm image")),
+    };
+    if header.maxval == 0 || header.maxval > usize::from(u16::MAX) {
+        return Err(invalid(format!(
+            "maximum value {} is not between 1 and 65535",
+            header.maxval
+        )));
+    }
+    if !(1..=4).contains(&header.depth) {
+        return Err(invalid(format!(
+            "depth {} is not 1 to 4 channels",
+            header.depth
+        )));
+    }
+    let count = header
+        .width
+        .checked_mul(header.height)
+        .and_then(|pixels| pixels.checked_mul(header.depth))
+        .ok_or_else(|| invalid("image is too large"))?;
+
+    let samples = if header.plain {
+        (0..count)
+            .map(|_| reader.number("sample"))
+            .collect::<OpenRgbResult<Vec<_>>>()?
+    } else {
+        // binary samples follow a single whitespace character
+        reader.pos += 1;
+        let size = if header.maxval < 256 { 1 } else { 2 };
+        let len = count
+            .checked_mul(size)
+            .ok_or_else(|| invalid("image is too large"))?;
+        let data = reader
+            .bytes
+            .get(reader.pos..)
+            .and_then(|rest| rest.get(..len))
+            .ok_or_else(|| invalid("image data is truncated"))?;
+        reader.pos += data.len();
+        data.chunks_exact(size)
+            .map(|sample| match sample {
+                [value] => usize::from(*value),
+                [high, low] => usize::from(u16::from_be_bytes([*high, *low])),
+                _ => unreachable!(),
+            })
+            .collect()
+    };
+
+    let maxval = header.maxval;
+    // plain samples can be any number, so they are clamped before any arithmetic
+    let samples = samples
+        .into_iter()
+        .map(|value: usize| value.min(maxval))
+        .collect::<Vec<_>>();
+    let scale = |value: usize| ((value * 255 + maxval / 2) / maxval) as u8;
+    let pixels = samples
+        .chunks_exact(header.depth)
+        .map(|pixel| {
+            let (channels, alpha) = match pixel {
+                [gray] => ([*gray; 3], maxval),
+                [gray, alpha] => ([*gray; 3], *alpha),
+                [r, g, b] => ([*r, *g, *b], maxval),
+                [r, g, b, alpha] => ([*r, *g, *b], *alpha),
+                _ => unreachable!(),
+            };
+            let [r, g, b] = channels.map(|value| scale(value * alpha / maxval));
+            Color::new(r, g, b)
+        })
+        .collect();
+    let image = Image::new(header.width, header.height, pixels)?;
+    Ok((image, &bytes[reader.pos..]))
+}
+
+fn invalid(reason: impl std::fmt::Display) -> OpenRgbError {
+    OpenRgbError::ImageError(format!("Invalid Netpbm image: {reason}"))
+}
+
+/// Size and sample format of an image.
+struct Header {
+    width: usize,
+    height: usize,
+    depth: usize,
+    maxval: usize,
+    /// Samples are written as text, rather than binary.
+    plain: bool,
+}
+
+/// Reads the whitespace separated tokens of a header.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Returns the next token, skipping whitespace and comments.
+    fn token(&mut self) -> Option<&'a [u8]> {
+        loop {
+            match self.bytes.get(self.pos)? {
+                b'#' => {
+                    while self.bytes.get(self.pos).is_some_and(|&b| b != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+        let start = self.pos;
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#')
+        {
+            self.pos += 1;
+        }
+        Some(&self.bytes[start..self.pos])
+    }
+
+    fn number(&mut self, what: &str) -> OpenRgbResult<usize> {
+        let token = self.token().unwrap_or_default();
+        std::str::from_utf8(token)
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .ok_or_else(|| {
+                invalid(format!(
+                    "expected {what}, got {:?}",
+                    String::from_utf8_lossy(token)
+                ))
+            })
+    }
+
+    /// Reads the header of a PPM after the magic number.
+    fn ppm_header(&mut self) -> OpenRgbResult<Header> {
+        Ok(Header {
+            width: self.number("width")?,
+            height: self.number("height")?,
+            depth: 3,
+            maxval: self.number("maximum value")?,
+            plain: false,
+        })
+    }
+
+    /// Reads the header lines of a PAM after the magic number, up to `ENDHDR`.
+    fn pam_header(&mut self) -> OpenRgbResult<Header> {
+        let (mut width, mut height, mut depth, mut maxval) = (None, None, None, None);
+        loop {
+            match self.token() {
+                Some(b"WIDTH") => width = Some(self.number("width")?),
+                Some(b"HEIGHT") => height = Some(self.number("height")?),
+                Some(b"DEPTH") => depth = Some(self.number("depth")?),
+                Some(b"MAXVAL") => maxval = Some(self.number("maximum value")?),
+                // the depth tells the channels apart
+                Some(b"TUPLTYPE") => {
+                    self.token();
+                }
+                Some(b"ENDHDR") => break,
+                Some(token) => {
+                    return Err(invalid(format!(
+                        "unknown header field {:?}",
+                        String::from_utf8_lossy(token)
+                    )));
+                }
+                None => return Err(invalid("missing ENDHDR")),
+            }
+        }
+        let missing = |field| invalid(format!("missing {field}"));
+        Ok(Header {
+            width: width.ok_or_else(|| missing("WIDTH"))?,
+            height: height.ok_or_else(|| missing("HEIGHT"))?,
+            depth: depth.ok_or_else(|| missing("DEPTH"))?,
+            maxval: maxval.ok_or_else(|| missing("MAXVAL"))?,
+            plain: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ppm() -> OpenRgbResult<()> {
+        let plain = b"P3\n# a comment\n2 1\n15\n15 0 0  0 0 15\n";
+        let image = Image::from_ppm(plain)?;
+        assert_eq!(
+            image.pixels(),
+            [Color::new(255, 0, 0), Color::new(0, 0, 255)]
+        );
+
+        let mut binary = b"P6 1 1 255\n".to_vec();
+        binary.extend([10, 20, 30]);
+        binary.extend(b"P6 1 1 65535\n");
+        binary.extend([0xff, 0xff, 0x80, 0x00, 0x00, 0x00]);
+        let (first, rest) = parse(&binary)?;
+        assert_eq!(first.pixels(), [Color::new(10, 20, 30)]);
+        let (second, rest) = parse(rest)?;
+        assert_eq!(second.pixels(), [Color::new(255, 128, 0)]);
+        assert!(rest.is_empty());
+
+        let mut pam =
+            b"P7\nWIDTH 2\nHEIGHT 1\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n".to_vec();
+        pam.extend([255, 255, 255, 255, 200, 100, 0, 0]);
+        let image = Image::from_ppm(&pam)?;
+        assert_eq!(
+            image.pixels(),
+            [Color::new(255, 255, 255), Color::default()]
+        );
+
+        assert!(Image::from_ppm(b"P6 2 2 255\n\x00\x00\x00").is_err());
+        assert!(Image::from_ppm(b"P5 1 1 255\n\x00").is_err());
+        assert!(Image::from_ppm(b"P6 4611686018427387904 1 65535\n\x00\x00").is_err());
+        // samples above the maximum value are clamped
+        let image = Image::from_ppm(b"P3 1 1 255\n18446744073709551615 0 0\n")?;
+        assert_eq!(image.pixels(), [Color::new(255, 0, 0)]);
+        Ok(())
+    }
+}
@@ -30,11 +30,22 @@
 pub use {
     client::*,
     data::*,
-    error::{OpenRgbError, OpenRgbResult},
+    error::{ModeError, ModeSetting, OpenRgbError, OpenRgbResult, ParseCalibrationError},
 };
 
 pub(crate) use protocol::*;
 
+pub mod ambilight;
+#[cfg(feature = "audio")]
+pub mod audio;
 mod client;
+pub mod color;
+pub mod effects;
 mod error;
+pub mod image;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub(crate) mod protocol;
+#[cfg(any(test, feature = "server"))]
+pub mod server;
+pub mod text;
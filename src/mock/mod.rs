@@ -0,0 +1,655 @@
+//! In-process fake `OpenRGB` SDK server, to test code built on this crate without any hardware.
+//!
+//! The [`MockServer`] listens on localhost, serves [`MockController`] fixtures and answers every packet
+//! a client can send. Packets that change state are applied to the fixtures, so reading a controller
+//! returns the colors and mode that were set, and are recorded as [`RecordedPacket`]s.
+//!
+//! Packets from a single client are handled in order. Most packets have no reply,
+//! but once any request of the same client returns, all packets it sent before have been handled.
+//!
+//! Only available with the `mock` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use openrgb2::mock::{MockController, MockServer, MockZone, RecordedPacket};
+//! use openrgb2::{Color, DeviceType, OpenRgbResult};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let server = MockServer::builder()
+//!     .with_controller(
+//!         MockController::new("Strip", DeviceType::LEDStrip).with_zone(MockZone::linear("Strip", 3)),
+//!     )
+//!     .start()
+//!     .await?;
+//!
+//! let client = server.client().await?;
+//! let mut controller = client.get_controller(0).await?;
+//! controller.set_all_leds(Color::new(255, 0, 0)).await?;
+//! controller.sync_controller_data().await?;
+//!
+//! assert_eq!(controller.colors(), [Color::new(255, 0, 0); 3]);
+//! assert!(server.received().contains(&RecordedPacket::UpdateLeds {
+//!     controller_id: 0,
+//!     colors: vec![Color::new(255, 0, 0); 3],
+//! }));
+//! # Ok(())
+//! # }
+//! ```
+
+mod record;
+
+pub use record::*;
+
+/// Controller served by a [`MockServer`].
+pub use crate::server::VirtualController as MockController;
+/// Mode of a [`MockController`].
+pub use crate::server::VirtualMode as MockMode;
+/// Zone of a [`MockController`].
+pub use crate::server::VirtualZone as MockZone;
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex as StdMutex};
+
+use tokio::{net::TcpListener, sync::broadcast, task::JoinHandle};
+
+use crate::server::{
+    PacketHandler, SIGNAL_CAPACITY, Signal, accept_loop, controller_not_found, raw_string,
+};
+use crate::{
+    Color, ControllerData, ControllerUpdate, DEFAULT_PROTOCOL, ModeData, OpenRgbClient,
+    OpenRgbPacket, OpenRgbResult, PacketId, PluginData, ReceivedMessage, WriteMessage,
+};
+
+/// Builder for a [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct MockServerBuilder {
+    protocol_version: u32,
+    controllers: Vec<MockController>,
+    profiles: Vec<String>,
+    plugins: Vec<PluginData>,
+}
+
+impl Default for MockServerBuilder {
+    fn default() -> Self {
+        Self {
+            protocol_version: DEFAULT_PROTOCOL,
+            controllers: Vec::new(),
+            profiles: Vec::new(),
+            plugins: Vec::new(),
+        }
+    }
+}
+
+impl MockServerBuilder {
+    /// Sets the highest protocol version the server supports, [`DEFAULT_PROTOCOL`] by default.
+    pub fn with_protocol_version(mut self, protocol_version: u32) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// Adds a controller, controller ids are given in the order controllers are added.
+    pub fn with_controller(mut self, controller: MockController) -> Self {
+        self.controllers.push(controller);
+        self
+    }
+
+    /// Adds a saved profile.
+    pub fn with_profile(mut self, name: impl Into<String>) -> Self {
+        self.profiles.push(name.into());
+        self
+    }
+
+    /// Adds an installed plugin.
+    pub fn with_plugin(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        version: impl Into<String>,
+        plugin_protocol_version: u32,
+    ) -> Self {
+        self.plugins.push(PluginData {
+            name: name.into(),
+            description: description.into(),
+            version: version.into(),
+            index: self.plugins.len() as u32,
+            plugin_protocol_version,
+        });
+        self
+    }
+
+    /// Starts listening on a free port on localhost.
+    pub async fn start(self) -> OpenRgbResult<MockServer> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let addr = listener.local_addr()?;
+        let (signals, _) = broadcast::channel(SIGNAL_CAPACITY);
+        let state = Arc::new(StdMutex::new(MockState {
+            protocol_version: self.protocol_version,
+            controllers: self.controllers.iter().map(MockController::build).collect(),
+            profiles: self.profiles,
+            plugins: self.plugins,
+            received: Vec::new(),
+            signals: signals.clone(),
+        }));
+        let task = tokio::spawn(accept_loop(listener, state.clone(), signals.clone()));
+        tracing::debug!("Mock OpenRGB server listening on {addr}");
+        Ok(MockServer {
+            addr,
+            state,
+            signals,
+            task,
+        })
+    }
+}
+
+/// Fake `OpenRGB` SDK server listening on localhost. See the [module documentation](self).
+///
+/// The server stops when this handle is dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<StdMutex<MockState>>,
+    signals: broadcast::Sender<Signal>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl std::fmt::Debug for MockServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockServer")
+            .field("addr", &self.addr)
+            .finish()
+    }
+}
+
+impl MockServer {
+    /// Returns a builder to configure the server before starting it.
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::default()
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Connects a new client to this server.
+    pub async fn client(&self) -> OpenRgbResult<OpenRgbClient> {
+        OpenRgbClient::connect_to(self.addr, DEFAULT_PROTOCOL).await
+    }
+
+    /// Returns all recorded packets, in the order they were received.
+    pub fn received(&self) -> Vec<RecordedPacket> {
+        self.state().received.clone()
+    }
+
+    /// Returns all recorded packets, and clears them.
+    pub fn take_received(&self) -> Vec<RecordedPacket> {
+        std::mem::take(&mut self.state().received)
+    }
+
+    /// Returns the number of controllers.
+    pub fn controller_count(&self) -> usize {
+        self.state().controllers.len()
+    }
+
+    /// Returns the current colors of a controller.
+    pub fn colors(&self, controller_id: usize) -> Option<Vec<Color>> {
+        let state = self.state();
+        state
+            .controllers
+            .get(controller_id)
+            .map(|c| c.colors.clone())
+    }
+
+    /// Returns the active mode of a controller.
+    pub fn active_mode(&self, controller_id: usize) -> Option<ModeData> {
+        let state = self.state();
+        state
+            .controllers
+            .get(controller_id)
+            .and_then(|c| c.active_mode())
+            .cloned()
+    }
+
+    /// Returns the names of the saved profiles.
+    pub fn profiles(&self) -> Vec<String> {
+        self.state().profiles.clone()
+    }
+
+    /// Adds a controller and tells clients the device list was updated.
+    pub fn add_controller(&self, controller: MockController) {
+        self.state().controllers.push(controller.build());
+        self.notify_device_list_updated();
+    }
+
+    /// Removes a controller and tells clients the device list was updated.
+    ///
+    /// Controllers after it shift down by one id, like they do in `OpenRGB`.
+    pub fn remove_controller(&self, controller_id: usize) -> bool {
+        let removed = {
+            let mut state = self.state();
+            (controller_id < state.controllers.len())
+                .then(|| state.controllers.remove(controller_id))
+                .is_some()
+        };
+        if removed {
+            self.notify_device_list_updated();
+        }
+        removed
+    }
+
+    /// Sends a `DeviceListUpdated` packet to every connected client.
+    pub fn notify_device_list_updated(&self) {
+        // only fails when no client is connected
+        let _ = self.signals.send(Signal::DeviceListUpdated);
+    }
+
+    /// Closes the connection of every connected client, the server keeps accepting new clients.
+    pub fn disconnect_clients(&self) {
+        let _ = self.signals.send(Signal::Disconnect);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().expect("mock state lock poisoned")
+    }
+}
+
+/// State of the server, shared by all clients.
+struct MockState {
+    protocol_version: u32,
+    controllers: Vec<ControllerData>,
+    profiles: Vec<String>,
+    plugins: Vec<PluginData>,
+    received: Vec<RecordedPacket>,
+    signals: broadcast::Sender<Signal>,
+}
+
+impl PacketHandler for StdMutex<MockState> {
+    fn protocol_version(&self) -> u32 {
+        self.lock()
+            .expect("mock state lock poisoned")
+            .protocol_version
+    }
+
+    fn handle(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        body: &[u8],
+        protocol_version: u32,
+    ) -> OpenRgbResult<Option<WriteMessage>> {
+        self.lock().expect("mock state lock poisoned").handle(
+            device_id,
+            packet_id,
+            body,
+            protocol_version,
+        )
+    }
+}
+
+impl MockState {
+    /// Applies a packet and returns the reply to send, if any.
+    ///
+    /// `protocol_version` is the version negotiated with the client that sent the packet.
+    fn handle(
+        &mut self,
+        device_id: u32,
+        packet_id: PacketId,
+        body: &[u8],
+        protocol_version: u32,
+    ) -> OpenRgbResult<Option<WriteMessage>> {
+        let mut msg = ReceivedMessage::new(body, protocol_version);
+        if let Some(mut update) = ControllerUpdate::read(packet_id, &mut msg)? {
+            update.apply(self.controller_mut(device_id)?)?;
+            self.record(RecordedPacket::from_update(device_id, update));
+            return Ok(None);
+        }
+
+        let mut reply = WriteMessage::new(protocol_version);
+        match packet_id {
+            PacketId::RequestControllerCount => {
+                reply.write_u32(self.controllers.len() as u32);
+            }
+            PacketId::RequestControllerData => {
+                // clients older than protocol 1 do not send their version
+                let requested = msg.read_u32().unwrap_or(protocol_version);
+                let mut reply = WriteMessage::new(requested.min(self.protocol_version));
+                reply.write_value(self.controller(device_id)?)?;
+                return Ok(Some(reply));
+            }
+            PacketId::SetClientName => {
+                let name = raw_string(body);
+                self.record(RecordedPacket::SetClientName { name });
+                return Ok(None);
+            }
+            PacketId::RequestDeviceRescan => {
+                self.record(RecordedPacket::RescanDevices);
+                let _ = self.signals.send(Signal::DeviceListUpdated);
+                return Ok(None);
+            }
+            PacketId::RequestProfileList => {
+                reply.write_value(&OpenRgbPacket::new(&self.profiles))?;
+            }
+            PacketId::RequestSaveProfile => {
+                let name = raw_string(body);
+                if !self.profiles.contains(&name) {
+                    self.profiles.push(name.clone());
+                }
+                self.record(RecordedPacket::SaveProfile { name });
+                return Ok(None);
+            }
+            PacketId::RequestLoadProfile => {
+                let name = raw_string(body);
+                self.record(RecordedPacket::LoadProfile { name });
+                return Ok(None);
+            }
+            PacketId::RequestDeleteProfile => {
+                let name = raw_string(body);
+                self.profiles.retain(|p| *p != name);
+                self.record(RecordedPacket::DeleteProfile { name });
+                return Ok(None);
+            }
+            PacketId::RequestPluginList => {
+                reply.write_value(&OpenRgbPacket::new(&self.plugins))?;
+            }
+            PacketId::PluginSpecific => {
+                // there is no plugin to answer, so echo the request back
+                self.record(RecordedPacket::PluginSpecific {
+                    plugin_id: device_id,
+                    data: body.to_vec(),
+                });
+                reply.write_slice(body);
+            }
+            _ => return Ok(None),
+        }
+        Ok(Some(reply))
+    }
+
+    fn record(&mut self, packet: RecordedPacket) {
+        tracing::trace!("Mock server recorded {packet:?}");
+        self.received.push(packet);
+    }
+
+    fn controller(&self, controller_id: u32) -> OpenRgbResult<&ControllerData> {
+        self.controllers
+            .get(controller_id as usize)
+            .ok_or_else(|| controller_not_found(controller_id))
+    }
+
+    fn controller_mut(&mut self, controller_id: u32) -> OpenRgbResult<&mut ControllerData> {
+        self.controllers
+            .get_mut(controller_id as usize)
+            .ok_or_else(|| controller_not_found(controller_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use array2d::Array2D;
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{DeviceType, ReconnectPolicy, ServerEvent, ZoneType};
+
+    fn strip(leds: u32) -> MockController {
+        MockController::new("Strip", DeviceType::LEDStrip)
+            .with_zone(MockZone::linear("Strip", leds).with_size_range(1, 60))
+            .with_mode(MockMode::direct())
+            .with_mode(
+                MockMode::new("Breathing")
+                    .with_speed(1, 10, 5)
+                    .with_brightness(0, 100, 100),
+            )
+    }
+
+    fn keyboard() -> MockController {
+        let mut layout = Array2D::filled_with(u32::MAX, 2, 3);
+        for (i, pos) in [(0, 0), (0, 1), (0, 2), (1, 1)].into_iter().enumerate() {
+            layout[pos] = i as u32;
+        }
+        MockController::new("Keyboard", DeviceType::Keyboard)
+            .with_vendor("Acme")
+            .with_zone(
+                MockZone::linear("Keys", 0)
+                    .with_matrix(layout)
+                    .with_led_names(["Key: Q", "Key: W", "Key: E"]),
+            )
+            .with_zone(MockZone::single("Logo"))
+    }
+
+    #[test]
+    fn test_controller_data_roundtrip() -> OpenRgbResult<()> {
+        let data = keyboard()
+            .with_zone(MockZone::linear("Edge", 8).with_segment("Left", 0, 4))
+            .with_mode(MockMode::new("Static").with_colors(1, 1, [Color::new(1, 2, 3)]))
+            .build();
+        let mut buf = WriteMessage::new(DEFAULT_PROTOCOL);
+        buf.write_value(&data)?;
+        let read = buf.to_received_msg().read_value::<ControllerData>()?;
+        assert_eq!(read, data);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_get_controllers() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(keyboard())
+            .with_controller(strip(10))
+            .start()
+            .await?;
+        let client = server.client().await?;
+
+        let controllers = client.get_all_controllers().await?;
+        let keyboard = &controllers.controllers()[0];
+        assert_eq!(keyboard.name(), "Keyboard");
+        assert_eq!(keyboard.vendor(), "Acme");
+        assert_eq!(keyboard.num_leds(), 5);
+        assert_eq!(keyboard.zones()[0].zone_type(), ZoneType::Matrix);
+        assert_eq!(keyboard.zones()[0].matrix().map(|m| m[(1, 1)]), Some(3));
+        assert_eq!(keyboard.leds()[1].name, "Key: W");
+        assert_eq!(keyboard.leds()[3].name, "Keys LED 4");
+        assert_eq!(keyboard.active_mode().map(|m| m.name()), Some("Direct"));
+
+        let strip = &controllers.controllers()[1];
+        assert_eq!(strip.device_type(), DeviceType::LEDStrip);
+        assert_eq!(strip.modes()[1].speed(), Some(5));
+        assert_eq!(strip.colors(), [Color::default(); 10]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_records_updates() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip(4))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let mut controller = client.get_controller(0).await?;
+
+        let breathing = controller.modes()[1].clone();
+        controller.set_mode(&breathing).await?;
+        controller.set_leds([Color::new(255, 0, 0); 4]).await?;
+        controller.set_led(2, Color::new(0, 0, 255)).await?;
+        controller.get_zone(0)?.add_segment("Half", 0, 2).await?;
+        controller.sync_controller_data().await?;
+
+        assert_eq!(
+            controller.active_mode().map(|m| m.name()),
+            Some("Breathing")
+        );
+        assert_eq!(
+            controller.colors()[1..3],
+            [Color::new(255, 0, 0), Color::new(0, 0, 255)]
+        );
+        assert_eq!(controller.zones()[0].segments().map(|s| s.len()), Some(1));
+        assert_eq!(
+            server.take_received(),
+            [
+                RecordedPacket::UpdateMode {
+                    controller_id: 0,
+                    mode: breathing,
+                },
+                RecordedPacket::UpdateLeds {
+                    controller_id: 0,
+                    colors: vec![Color::new(255, 0, 0); 4],
+                },
+                RecordedPacket::UpdateSingleLed {
+                    controller_id: 0,
+                    led_id: 2,
+                    color: Color::new(0, 0, 255),
+                },
+                RecordedPacket::AddSegment {
+                    controller_id: 0,
+                    zone_id: 0,
+                    segment: controller.zones()[0].segments().unwrap()[0].clone(),
+                },
+            ]
+        );
+        assert!(server.received().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_resize_zone() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip(4))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let mut controller = client.get_controller(0).await?;
+
+        controller.get_zone(0)?.resize(6).await?;
+        controller.sync_controller_data().await?;
+        assert_eq!(controller.num_leds(), 6);
+        assert_eq!(controller.colors().len(), 6);
+        assert_eq!(controller.leds()[5].name, "Strip LED 6");
+
+        controller.get_zone(0)?.resize(2).await?;
+        controller.sync_controller_data().await?;
+        assert_eq!(controller.num_leds(), 2);
+        assert_eq!(server.colors(0).map(|c| c.len()), Some(2));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_profiles() -> OpenRgbResult<()> {
+        let server = MockServer::builder().with_profile("Work").start().await?;
+        let client = server.client().await?;
+
+        client.save_profile("Gaming").await?;
+        assert_eq!(client.get_profiles().await?, ["Work", "Gaming"]);
+        client.delete_profile("Work").await?;
+        client.load_profile("Gaming").await?;
+        assert_eq!(client.get_profiles().await?, ["Gaming"]);
+        assert_eq!(
+            server.received(),
+            [
+                RecordedPacket::SaveProfile {
+                    name: "Gaming".to_owned()
+                },
+                RecordedPacket::DeleteProfile {
+                    name: "Work".to_owned()
+                },
+                RecordedPacket::LoadProfile {
+                    name: "Gaming".to_owned()
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_plugins() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_plugin("Effects", "Effects engine", "1.0", 2)
+            .start()
+            .await?;
+        let client = server.client().await?;
+
+        let plugins = client.get_plugins().await?;
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].name(), "Effects");
+        assert_eq!(plugins[0].plugin_protocol_version(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_protocol_version() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_protocol_version(3)
+            .with_controller(keyboard())
+            .start()
+            .await?;
+        let mut client = server.client().await?;
+
+        assert_eq!(client.get_protocol_version(), 3);
+        let controller = client.get_controller(0).await?;
+        assert_eq!(controller.zones()[0].segments(), None);
+        assert!(client.get_plugins().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_device_list_updated() -> OpenRgbResult<()> {
+        let server = MockServer::builder().start().await?;
+        let client = server.client().await?;
+        let mut events = client.events();
+
+        server.add_controller(strip(3));
+        assert_eq!(
+            events.recv().await.ok(),
+            Some(ServerEvent::DeviceListUpdated)
+        );
+        assert_eq!(client.get_all_controllers().await?.controllers().len(), 1);
+
+        assert!(server.remove_controller(0));
+        assert_eq!(
+            events.recv().await.ok(),
+            Some(ServerEvent::DeviceListUpdated)
+        );
+        assert_eq!(server.controller_count(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_disconnect_clients() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(strip(3))
+            .start()
+            .await?;
+        let client = server.client().await?;
+        client.set_reconnect_policy(Some(
+            ReconnectPolicy::default()
+                .with_initial_delay(Duration::from_millis(10))
+                .with_restore_state(true),
+        ));
+        let mut events = client.events();
+        let controller = client.get_controller(0).await?;
+        controller.set_all_leds(Color::new(0, 255, 0)).await?;
+
+        server.disconnect_clients();
+        assert_eq!(events.recv().await.ok(), Some(ServerEvent::Disconnected));
+        assert_eq!(events.recv().await.ok(), Some(ServerEvent::Reconnected));
+        assert_eq!(
+            client.get_controller(0).await?.colors(),
+            [Color::new(0, 255, 0); 3]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use crate::ControllerUpdate;
+use crate::{Color, ModeData, SegmentData};
+
+/// A packet received by a [`super::MockServer`] that changes its state.
+///
+/// Requests that only read data, such as the controller count, are not recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RecordedPacket {
+    /// `SetClientName`.
+    SetClientName {
+        /// Name of the client.
+        name: String,
+    },
+
+    /// `RGBController::ResizeZone()`.
+    ResizeZone {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+        /// Zone to resize.
+        zone_id: u32,
+        /// New number of LEDs in the zone.
+        size: u32,
+    },
+
+    /// `RGBController::ClearSegments()`.
+    ClearSegments {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+    },
+
+    /// `RGBController::AddSegment()`.
+    AddSegment {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+        /// Zone the segment was added to.
+        zone_id: u32,
+        /// The added segment.
+        segment: SegmentData,
+    },
+
+    /// `RGBController::UpdateLEDs()`.
+    UpdateLeds {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+        /// Colors of all LEDs.
+        colors: Vec<Color>,
+    },
+
+    /// `RGBController::UpdateZoneLEDs()`.
+    UpdateZoneLeds {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+        /// Zone that was updated.
+        zone_id: u32,
+        /// Colors of the LEDs in the zone.
+        colors: Vec<Color>,
+    },
+
+    /// `RGBController::UpdateSingleLED()`.
+    UpdateSingleLed {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+        /// LED that was updated.
+        led_id: i32,
+        /// New color of the LED.
+        color: Color,
+    },
+
+    /// `RGBController::SetCustomMode()`.
+    SetCustomMode {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+    },
+
+    /// `RGBController::UpdateMode()`.
+    UpdateMode {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+        /// The mode, with its id set to the mode index in the packet.
+        mode: ModeData,
+    },
+
+    /// `RGBController::SaveMode()`.
+    SaveMode {
+        /// Controller the packet was sent to.
+        controller_id: u32,
+        /// The mode, with its id set to the mode index in the packet.
+        mode: ModeData,
+    },
+
+    /// Request to load a profile.
+    LoadProfile {
+        /// Name of the profile.
+        name: String,
+    },
+
+    /// Request to save a profile.
+    SaveProfile {
+        /// Name of the profile.
+        name: String,
+    },
+
+    /// Request to delete a profile.
+    DeleteProfile {
+        /// Name of the profile.
+        name: String,
+    },
+
+    /// Plugin specific request.
+    PluginSpecific {
+        /// Plugin the request was sent to.
+        plugin_id: u32,
+        /// Raw packet data.
+        data: Vec<u8>,
+    },
+
+    /// Request to rescan devices.
+    RescanDevices,
+}
+
+impl RecordedPacket {
+    pub(crate) fn from_update(controller_id: u32, update: ControllerUpdate) -> Self {
+        match update {
+            ControllerUpdate::ResizeZone { zone_id, size } => Self::ResizeZone {
+                controller_id,
+                zone_id,
+                size,
+            },
+            ControllerUpdate::ClearSegments => Self::ClearSegments { controller_id },
+            ControllerUpdate::AddSegment { zone_id, segment } => Self::AddSegment {
+                controller_id,
+                zone_id,
+                segment,
+            },
+            ControllerUpdate::UpdateLeds { colors } => Self::UpdateLeds {
+                controller_id,
+                colors,
+            },
+            ControllerUpdate::UpdateZoneLeds { zone_id, colors } => Self::UpdateZoneLeds {
+                controller_id,
+                zone_id,
+                colors,
+            },
+            ControllerUpdate::UpdateSingleLed { led_id, color } => Self::UpdateSingleLed {
+                controller_id,
+                led_id,
+                color,
+            },
+            ControllerUpdate::SetCustomMode => Self::SetCustomMode { controller_id },
+            ControllerUpdate::UpdateMode { mode } => Self::UpdateMode {
+                controller_id,
+                mode,
+            },
+            ControllerUpdate::SaveMode { mode } => Self::SaveMode {
+                controller_id,
+                mode,
+            },
+        }
+    }
+}
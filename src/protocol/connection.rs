@@ -0,0 +1,387 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use tokio::{
+    io::{AsyncWriteExt, ReadHalf, WriteHalf},
+    sync::{Mutex, MutexGuard, broadcast, oneshot, watch},
+    task::JoinHandle,
+};
+
+use crate::{
+    BoxedTransport, DeserFromBuf, OpenRgbError, OpenRgbResult, PacketId, ProtocolStream,
+    ReceivedMessage, SerToBuf, ServerEvent, Timeouts, WriteMessage, encode_packet, read_raw_packet,
+};
+
+/// Raw body of a reply packet, or the error that prevented it from arriving.
+type Reply = OpenRgbResult<Vec<u8>>;
+
+/// A request that was sent to the server and is waiting for its reply.
+struct PendingRequest {
+    device_id: u32,
+    packet_id: PacketId,
+    tx: oneshot::Sender<Reply>,
+}
+
+/// State shared between the connection and its reader task.
+#[derive(Default)]
+struct PendingState {
+    /// Set once the stream can no longer be read from, or the connection was poisoned.
+    closed: bool,
+    /// Requests in the order they were written.
+    ///
+    /// The server answers requests for the same device and packet id in order,
+    /// so each reply belongs to the oldest request with the same ids.
+    requests: VecDeque<PendingRequest>,
+}
+
+/// A live connection to an OpenRGB server.
+///
+/// Packets are written directly to the stream, while a background task reads all incoming packets.
+/// Requests do not wait for earlier replies before they are written, so many can be in flight at once.
+/// Replies are handed to the request waiting for them, and unsolicited packets such as
+/// [`PacketId::DeviceListUpdated`] are published as [`ServerEvent`]s.
+///
+/// A request or write that times out poisons the connection, which closes it for good.
+/// Waiting for earlier writes to finish does not count towards the write timeout.
+///
+/// The reader task is stopped when the connection is dropped.
+pub(crate) struct Connection {
+    protocol_version: u32,
+    writer: Mutex<WriteHalf<BoxedTransport>>,
+    pending: Arc<StdMutex<PendingState>>,
+    closed: Arc<watch::Sender<bool>>,
+    timeouts: watch::Receiver<Timeouts>,
+    reader: JoinHandle<()>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl Connection {
+    /// Starts reading from `stream`. The stream must already have negotiated its protocol version.
+    ///
+    /// Reads and writes use the latest value of `timeouts`.
+    pub fn new(
+        stream: ProtocolStream,
+        events: broadcast::Sender<ServerEvent>,
+        timeouts: watch::Receiver<Timeouts>,
+    ) -> Self {
+        let protocol_version = stream.protocol_version();
+        let (read_half, write_half) = tokio::io::split(stream.into_inner());
+        let pending = Arc::new(StdMutex::new(PendingState::default()));
+        let pending_reader = pending.clone();
+        let closed = Arc::new(watch::Sender::new(false));
+        let closed_reader = closed.clone();
+        let reader = tokio::spawn(async move {
+            let err = read_loop(read_half, &pending_reader, events).await;
+            tracing::debug!("Stopped reading from OpenRGB server: {err}");
+            close(&pending_reader, &err);
+            closed_reader.send_replace(true);
+        });
+        Self {
+            protocol_version,
+            writer: Mutex::new(write_half),
+            pending,
+            closed,
+            timeouts,
+            reader,
+        }
+    }
+
+    /// Returns the protocol version negotiated for this connection.
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    /// Returns true if the server closed this connection, or reading from it failed.
+    pub fn is_closed(&self) -> bool {
+        *self.closed.borrow()
+    }
+
+    /// Returns a receiver that changes to `true` once this connection is closed.
+    ///
+    /// The sender is dropped without changing the value when the connection itself is dropped.
+    pub fn closed(&self) -> watch::Receiver<bool> {
+        self.closed.subscribe()
+    }
+
+    fn timeouts(&self) -> Timeouts {
+        *self.timeouts.borrow()
+    }
+
+    /// Writes a packet that does not expect a reply.
+    pub async fn write_packet<T: SerToBuf>(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: &T,
+    ) -> OpenRgbResult<()> {
+        let mut buf = WriteMessage::new(self.protocol_version);
+        encode_packet(&mut buf, device_id, packet_id, data)?;
+        self.write_all(packet_id, &buf).await
+    }
+
+    /// Writes several packets with the same packet id at once, none of which expect a reply.
+    pub async fn write_packets<T: SerToBuf>(
+        &self,
+        packet_id: PacketId,
+        packets: &[(u32, T)],
+    ) -> OpenRgbResult<()> {
+        let mut buf = WriteMessage::new(self.protocol_version);
+        for (device_id, data) in packets {
+            encode_packet(&mut buf, *device_id, packet_id, data)?;
+        }
+        self.write_all(packet_id, &buf).await
+    }
+
+    /// Writes encoded packets that do not expect a reply, poisoning the connection if the write fails or is cancelled.
+    async fn write_all(&self, packet_id: PacketId, buf: &WriteMessage) -> OpenRgbResult<()> {
+        let mut writer = self.lock_writer().await?;
+        self.write_locked(&mut writer, packet_id, buf).await
+    }
+
+    /// Waits for the writes before this one to finish.
+    ///
+    /// Not covered by the write timeout, since a slow write ahead of this one is bounded by its own timeout,
+    /// and poisons the connection when it passes.
+    async fn lock_writer(&self) -> OpenRgbResult<MutexGuard<'_, WriteHalf<BoxedTransport>>> {
+        let writer = self.writer.lock().await;
+        if self.is_closed() {
+            return Err(connection_closed());
+        }
+        Ok(writer)
+    }
+
+    /// Writes `buf` within the write timeout, poisoning the connection if the write fails, stalls or is cancelled.
+    async fn write_locked(
+        &self,
+        writer: &mut WriteHalf<BoxedTransport>,
+        packet_id: PacketId,
+        buf: &WriteMessage,
+    ) -> OpenRgbResult<()> {
+        let write = async {
+            let guard = PoisonGuard(Some(self));
+            writer.write_all(buf.bytes()).await?;
+            guard.disarm();
+            Ok(())
+        };
+        let timeout = self.timeouts().write();
+        self.deadline(timeout, "Writing", packet_id, write).await
+    }
+
+    /// Writes a packet and waits for the reader task to hand over the reply.
+    pub async fn request<I: SerToBuf, O: DeserFromBuf>(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: &I,
+    ) -> OpenRgbResult<O> {
+        let reply = self.send_request(device_id, packet_id, data).await?;
+        self.recv(reply).await
+    }
+
+    /// Writes a request for each of `requests`, and then waits for all replies.
+    ///
+    /// All requests are in flight at once, so this takes about as long as a single request.
+    pub async fn request_all<I: SerToBuf, O: DeserFromBuf>(
+        &self,
+        packet_id: PacketId,
+        requests: &[(u32, I)],
+    ) -> OpenRgbResult<Vec<O>> {
+        let mut pending = Vec::with_capacity(requests.len());
+        for (device_id, data) in requests {
+            pending.push(self.send_request(*device_id, packet_id, data).await?);
+        }
+        let mut replies = Vec::with_capacity(pending.len());
+        for reply in pending {
+            replies.push(self.recv(reply).await?);
+        }
+        Ok(replies)
+    }
+
+    /// Writes a request without waiting for its reply.
+    ///
+    /// The write lock is only held while writing, so other requests can be written before the reply arrives.
+    async fn send_request<I: SerToBuf>(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: &I,
+    ) -> OpenRgbResult<PendingReply> {
+        // encoded up front, so a request that cannot be serialized is never registered
+        let mut buf = WriteMessage::new(self.protocol_version);
+        encode_packet(&mut buf, device_id, packet_id, data)?;
+        let mut writer = self.lock_writer().await?;
+        // registered while holding the write lock, so requests are queued in the order they are written
+        let rx = self.register(device_id, packet_id)?;
+        self.write_locked(&mut writer, packet_id, &buf).await?;
+        Ok(PendingReply { packet_id, rx })
+    }
+
+    /// Waits for the reply to a request written by [`Self::send_request`], and parses it.
+    async fn recv<O: DeserFromBuf>(&self, reply: PendingReply) -> OpenRgbResult<O> {
+        let read = async { reply.rx.await.map_err(|_| connection_closed())? };
+        let timeout = self.timeouts().read();
+        let body = self
+            .deadline(timeout, "Reading reply", reply.packet_id, read)
+            .await?;
+        let mut recv = ReceivedMessage::new(&body, self.protocol_version);
+        tracing::trace!("Read packet: {}", recv);
+        O::deserialize(&mut recv)
+    }
+
+    /// Runs `operation`, poisoning the connection if it takes longer than `timeout`.
+    async fn deadline<T>(
+        &self,
+        timeout: Option<Duration>,
+        operation: &str,
+        packet_id: PacketId,
+        future: impl Future<Output = OpenRgbResult<T>>,
+    ) -> OpenRgbResult<T> {
+        let Some(timeout) = timeout else {
+            return future.await;
+        };
+        match tokio::time::timeout(timeout, future).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!("{operation} {packet_id:?} packet timed out after {timeout:?}");
+                self.poison();
+                Err(OpenRgbError::Timeout {
+                    operation: operation.to_owned(),
+                    packet_id: Some(packet_id as u32),
+                })
+            }
+        }
+    }
+
+    /// Closes this connection for good.
+    ///
+    /// Used after a timeout, since the reply that did not arrive in time
+    /// could otherwise be taken for the reply to a later request.
+    fn poison(&self) {
+        self.reader.abort();
+        close(&self.pending, &connection_closed());
+        self.closed.send_replace(true);
+    }
+
+    fn register(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+    ) -> OpenRgbResult<oneshot::Receiver<Reply>> {
+        let mut state = self.pending.lock().expect("pending state lock poisoned");
+        if state.closed {
+            return Err(connection_closed());
+        }
+        let (tx, rx) = oneshot::channel();
+        state.requests.push_back(PendingRequest {
+            device_id,
+            packet_id,
+            tx,
+        });
+        Ok(rx)
+    }
+}
+
+/// Poisons the connection when dropped before [`Self::disarm`], such as when a write fails or is cancelled.
+///
+/// The server would read the rest of the stream after a partly written packet as garbage,
+/// and a partly written request has been registered, so the connection cannot be used anymore.
+struct PoisonGuard<'a>(Option<&'a Connection>);
+
+impl PoisonGuard<'_> {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for PoisonGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.0 {
+            connection.poison();
+        }
+    }
+}
+
+/// Reply to a request that was written, but may not have arrived yet.
+struct PendingReply {
+    packet_id: PacketId,
+    rx: oneshot::Receiver<Reply>,
+}
+
+/// Reads packets until the stream fails, routing each one to where it belongs.
+///
+/// Returns the error that stopped it.
+async fn read_loop(
+    mut stream: ReadHalf<BoxedTransport>,
+    pending: &StdMutex<PendingState>,
+    events: broadcast::Sender<ServerEvent>,
+) -> OpenRgbError {
+    loop {
+        let (header, body) = match read_raw_packet(&mut stream).await {
+            Ok(packet) => packet,
+            Err(e) => return e,
+        };
+
+        if header.packet_id == PacketId::DeviceListUpdated {
+            tracing::debug!("Server reported that its device list was updated");
+            // only fails when nobody is subscribed, which is fine
+            let _ = events.send(ServerEvent::DeviceListUpdated);
+            continue;
+        }
+
+        let request = {
+            let mut state = pending.lock().expect("pending state lock poisoned");
+            state
+                .requests
+                .iter()
+                .position(|r| r.packet_id == header.packet_id && r.device_id == header.device_id)
+                .and_then(|i| state.requests.remove(i))
+        };
+        match request {
+            Some(req) => {
+                // fails if the request was cancelled, in which case nobody needs the reply
+                let _ = req.tx.send(Ok(body));
+            }
+            None => tracing::warn!(
+                "Discarding unsolicited {:?} packet for device {}",
+                header.packet_id,
+                header.device_id
+            ),
+        }
+    }
+}
+
+/// Marks the connection as closed, and fails every request still waiting with `err`.
+fn close(pending: &StdMutex<PendingState>, err: &OpenRgbError) {
+    let mut state = pending.lock().expect("pending state lock poisoned");
+    state.closed = true;
+    for req in state.requests.drain(..) {
+        let _ = req.tx.send(Err(clone_error(err)));
+    }
+}
+
+/// Copies an error that ended the connection, to hand it to every request that was waiting.
+fn clone_error(err: &OpenRgbError) -> OpenRgbError {
+    match err {
+        OpenRgbError::CommunicationError { source } => OpenRgbError::CommunicationError {
+            source: std::io::Error::new(source.kind(), source.to_string()),
+        },
+        OpenRgbError::ProtocolError(msg) => OpenRgbError::ProtocolError(msg.clone()),
+        _ => connection_closed(),
+    }
+}
+
+pub(crate) fn connection_closed() -> OpenRgbError {
+    OpenRgbError::CommunicationError {
+        source: std::io::Error::new(
+            std::io::ErrorKind::ConnectionAborted,
+            "connection to OpenRGB server was closed",
+        ),
+    }
+}
@@ -6,6 +6,8 @@ mod color;
 mod implement;
 mod openrgb;
 mod protocol_option;
+#[cfg(feature = "serde")]
+pub(crate) mod serde_flags;
 
 pub use color::*;
 pub(crate) use implement::*;
@@ -1,14 +1,15 @@
 use flagset::{FlagSet, flags};
 
-use crate::OpenRgbResult;
 use crate::data::ProtocolOption;
 use crate::protocol::data::{Color, DeviceType, Led, ModeData, ZoneData};
-use crate::protocol::{DeserFromBuf, ReceivedMessage};
+use crate::protocol::{DeserFromBuf, ReceivedMessage, SerToBuf, WriteMessage};
+use crate::{OpenRgbError, OpenRgbResult};
 
 flags! {
     /// RGB Controller flags.
     ///
     /// The definition was taken from OpenRGB/RGBController.h:223-231 (11/07/2025)
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ControllerFlags: u32 {
         /// Controller is local to this instance.
         IsLocal = 1 << 0,
@@ -27,61 +28,61 @@ flags! {
 /// RGB controller.
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_controller_data) for more information.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct ControllerData {
     /// Controller type.
-    device_type: DeviceType,
+    pub(crate) device_type: DeviceType,
 
     /// Controller name.
-    name: String,
+    pub(crate) name: String,
 
     /// Controller vendor.
-    vendor: String,
+    pub(crate) vendor: String,
 
     /// Controller description.
-    description: String,
+    pub(crate) description: String,
 
     /// Controller version.
-    version: String,
+    pub(crate) version: String,
 
     /// Controller serial.
-    serial: String,
+    pub(crate) serial: String,
 
     /// Controller location.
-    location: String,
+    pub(crate) location: String,
 
     /// Controller active mode index.
-    active_mode: i32,
+    pub(crate) active_mode: i32,
 
     /// Controller modes.
-    modes: Vec<ModeData>,
+    pub(crate) modes: Vec<ModeData>,
 
     /// Controller zones.
-    zones: Vec<ZoneData>,
+    pub(crate) zones: Vec<ZoneData>,
 
     /// Controller LEDs.
-    leds: Vec<Led>,
+    pub(crate) leds: Vec<Led>,
 
     /// Controller colors.
-    colors: Vec<Color>,
+    pub(crate) colors: Vec<Color>,
 
     /// Alternate names for LEDs (?)
     ///
     /// Minimum protocol version: 5
-    led_alt_names: ProtocolOption<5, Vec<String>>,
+    pub(crate) led_alt_names: ProtocolOption<5, Vec<String>>,
 
     /// flags
     ///
     /// Minimum protocol version: 5
-    flags: ProtocolOption<5, FlagSet<ControllerFlags>>,
+    pub(crate) flags: ProtocolOption<5, FlagSet<ControllerFlags>>,
 
     /* NOT IN PROTOCOL, BUT USEFUL */
     /// Id of this controller, which is the id used to make the request.
-    id: u32,
+    pub(crate) id: u32,
     /// Number of LEDs in this controller.
     ///
     /// Computed by adding up the zone's lengths.
-    num_leds: usize,
+    pub(crate) num_leds: usize,
 }
 
 impl ControllerData {
@@ -157,13 +158,11 @@ impl ControllerData {
     }
 
     /// Returns the alternate names for LEDs, only supported in protocol version 5 and above.
-    #[allow(unused)]
     pub fn led_alt_names(&self) -> Option<&[String]> {
         self.led_alt_names.value().map(|v| v.as_slice())
     }
 
     /// Returns the flags for this controller. Only supported in protocol version 5 and above.
-    #[allow(unused)]
     pub fn flags(&self) -> Option<FlagSet<ControllerFlags>> {
         self.flags.value().copied()
     }
@@ -220,6 +219,37 @@ impl DeserFromBuf for ControllerData {
     }
 }
 
+impl SerToBuf for ControllerData {
+    fn serialize(&self, buf: &mut WriteMessage) -> OpenRgbResult<()> {
+        let num_modes = u16::try_from(self.modes.len())
+            .map_err(|e| OpenRgbError::ProtocolError(format!("Too many modes to encode: {e}")))?;
+        let mut inner = WriteMessage::new(buf.protocol_version());
+        inner
+            .push_value(&self.device_type)?
+            .push_value(&self.name)?
+            .push_value(&self.vendor)?
+            .push_value(&self.description)?
+            .push_value(&self.version)?
+            .push_value(&self.serial)?
+            .push_value(&self.location)?
+            .push_value(&num_modes)?
+            .push_value(&self.active_mode)?;
+        for mode in &self.modes {
+            inner.write_value(mode)?;
+        }
+        inner
+            .push_value(&self.zones)?
+            .push_value(&self.leds)?
+            .push_value(&self.colors)?
+            .push_value(&self.led_alt_names)?
+            .push_value(&self.flags)?;
+        let data_size = inner.len() + size_of::<u32>(); // + u32 to account for the size field itself
+        buf.write_u32(data_size as u32);
+        buf.write_slice(inner.bytes());
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error;
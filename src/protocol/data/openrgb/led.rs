@@ -1,8 +1,9 @@
 use crate::OpenRgbResult;
-use crate::protocol::{DeserFromBuf, ReceivedMessage};
+use crate::protocol::{DeserFromBuf, ReceivedMessage, SerToBuf, WriteMessage};
 
 /// A single LED.
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Led {
     /// LED name.
     pub name: String,
@@ -10,7 +11,7 @@ pub struct Led {
     /// LED value.
     ///
     /// This is some internal flag, basically of no use to us
-    value: u32,
+    pub(crate) value: u32,
 }
 
 impl DeserFromBuf for Led {
@@ -25,6 +26,13 @@ impl DeserFromBuf for Led {
     }
 }
 
+impl SerToBuf for Led {
+    fn serialize(&self, buf: &mut WriteMessage) -> OpenRgbResult<()> {
+        buf.push_value(&self.name)?.push_value(&self.value)?;
+        Ok(())
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use std::error::Error;
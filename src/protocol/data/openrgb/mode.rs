@@ -9,6 +9,7 @@ flags! {
     /// RGB controller mode flags.
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation) for more information.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ModeFlag: u32 {
         /// Mode has speed parameter.
         HasSpeed = 1 << 0,
@@ -47,6 +48,7 @@ flags! {
 
 /// Direction for [ModeData].
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     /// Left direction.
     #[default]
@@ -82,6 +84,7 @@ impl_enum_discriminant!(
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation) for more information.
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ColorMode {
     /// No color mode.
     #[default]
@@ -102,58 +105,62 @@ impl_enum_discriminant!(ColorMode, None: 0, PerLED: 1, ModeSpecific: 2, Random:
 /// RGB controller mode.
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#mode-data) for more information.
+///
+/// With the `serde` feature, flags are serialized as a list of names.
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ModeData {
     /// Mode name.
-    name: String,
+    pub(crate) name: String,
 
     /// Device specific mode value
-    value: i32,
+    pub(crate) value: i32,
 
     /// Mode flags set.
-    flags: FlagSet<ModeFlag>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::data::serde_flags"))]
+    pub(crate) flags: FlagSet<ModeFlag>,
 
     /// Mode minimum speed (if mode has [ModeFlag::HasSpeed] flag).
-    speed_min: u32,
+    pub(crate) speed_min: u32,
 
     /// Mode maximum speed (if mode has [ModeFlag::HasSpeed] flag).
-    speed_max: u32,
+    pub(crate) speed_max: u32,
 
     /// Mode maximum speed (if mode has [ModeFlag::HasSpeed] flag).
-    speed: u32,
+    pub(crate) speed: u32,
 
     /// Mode minimum brightness (if mode has [ModeFlag::HasBrightness] flag).
     ///
     /// Minimum protocol version: 3
-    brightness_min: ProtocolOption<3, u32>,
+    pub(crate) brightness_min: ProtocolOption<3, u32>,
 
     /// Mode maximum brightness (if mode has [ModeFlag::HasBrightness] flag).
     ///
     /// Minimum protocol version: 3
-    brightness_max: ProtocolOption<3, u32>,
+    pub(crate) brightness_max: ProtocolOption<3, u32>,
 
     /// Mode brightness (if mode has [ModeFlag::HasBrightness] flag).
     ///
     /// Minimum protocol version: 3
-    brightness: ProtocolOption<3, u32>,
+    pub(crate) brightness: ProtocolOption<3, u32>,
 
     /// Mode color mode.
-    color_mode: ColorMode,
+    pub(crate) color_mode: ColorMode,
 
     /// Mode colors.
-    colors: Vec<Color>,
+    pub(crate) colors: Vec<Color>,
 
     /// Mode minimum colors (if mode has non empty [ModeData::colors] list).
-    colors_min: u32,
+    pub(crate) colors_min: u32,
 
     /// Mode minimum colors (if mode has non empty [ModeData::colors] list).
-    colors_max: u32,
+    pub(crate) colors_max: u32,
 
     /// Mode direction.
-    direction: Direction,
+    pub(crate) direction: Direction,
 
     /// Index of this mode, not part of received packet but set right after reading
-    id: u32,
+    pub(crate) id: u32,
 }
 
 impl ModeData {
@@ -187,12 +194,11 @@ impl ModeData {
     }
 
     /// Set the brightness setting of this mode, minimum protocol version: 3.
+    ///
+    /// Use [`crate::Controller::mode()`] to check the value against the range of the mode.
     pub fn set_brightness(&mut self, b: u32) {
         if self.flags.contains(ModeFlag::HasBrightness) {
             self.brightness.replace(b);
-            // Dirty hack, settings brightness is not working for some reason
-            self.brightness_min.replace(b);
-            self.brightness_max.replace(b);
         }
     }
 
@@ -226,6 +232,8 @@ impl ModeData {
     }
 
     /// Set the speed setting of this mode.
+    ///
+    /// Use [`crate::Controller::mode()`] to check the value against the range of the mode.
     pub fn set_speed(&mut self, sp: u32) {
         if self.flags.contains(ModeFlag::HasSpeed) {
             self.speed = sp;
@@ -252,11 +260,9 @@ impl ModeData {
 
     /// Returns the direction of this mode.
     ///
-    /// If `ModeFlag::HasDirection` is not set, returns `None`.
+    /// If none of the `ModeFlag::HasDirection*` flags are set, returns `None`.
     pub fn direction(&self) -> Option<Direction> {
-        self.flags
-            .contains(ModeFlag::HasDirection)
-            .then_some(self.direction)
+        (!(self.flags & ModeFlag::HasDirection).is_empty()).then_some(self.direction)
     }
 
     /// Returns the color mode of this mode.
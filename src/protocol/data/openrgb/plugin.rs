@@ -1,17 +1,19 @@
-use crate::{DeserFromBuf, ReceivedMessage};
+use crate::{DeserFromBuf, ReceivedMessage, SerToBuf, WriteMessage};
 
 /// Data for OpenRGB plugins.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PluginData {
     /// Plugin name
-    name: String,
+    pub(crate) name: String,
     /// Description of plugin
-    description: String,
+    pub(crate) description: String,
     /// Plugin version
-    version: String,
+    pub(crate) version: String,
     /// Index of this plugin. This is its id in `plugin_specific` commands.
-    index: u32,
+    pub(crate) index: u32,
     /// Plugin's protocol version.
-    plugin_protocol_version: u32,
+    pub(crate) plugin_protocol_version: u32,
 }
 
 impl PluginData {
@@ -57,3 +59,14 @@ impl DeserFromBuf for PluginData {
         })
     }
 }
+
+impl SerToBuf for PluginData {
+    fn serialize(&self, buf: &mut WriteMessage) -> crate::OpenRgbResult<()> {
+        buf.push_value(&self.name)?
+            .push_value(&self.description)?
+            .push_value(&self.version)?
+            .push_value(&self.index)?
+            .push_value(&self.plugin_protocol_version)?;
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
 
 /// Data for OpenRGB segments
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SegmentData {
     name: String,
     /// Segment type should be the same as its parent's zone type.
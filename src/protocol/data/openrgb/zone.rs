@@ -2,8 +2,8 @@ use array2d::Array2D;
 use flagset::{FlagSet, flags};
 
 use crate::protocol::data::ProtocolOption;
-use crate::protocol::{DeserFromBuf, ReceivedMessage};
-use crate::{OpenRgbResult, impl_enum_discriminant};
+use crate::protocol::{DeserFromBuf, ReceivedMessage, SerToBuf, WriteMessage};
+use crate::{OpenRgbError, OpenRgbResult, impl_enum_discriminant};
 
 use super::SegmentData;
 
@@ -11,6 +11,7 @@ use super::SegmentData;
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#zone-data) for more information.
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ZoneType {
     /// Single zone.
     Single = 0,
@@ -28,6 +29,7 @@ flags! {
     /// Flags for RGB controller zones
     ///
     /// Taken from OpenRGB/RGBController.h:122-126 (11/07/2025)
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ZoneFlags: u32 {
         /// Zone is resizable, but only for effects. Treat as single LED
         ResizableForEffectsOnly = 1 << 0,
@@ -37,7 +39,10 @@ flags! {
 /// RGB controller zone.
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#zone-data) for more information.
+///
+/// With the `serde` feature, flags are serialized as a list of names.
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZoneData {
     /// Id of this zone.
     ///
@@ -71,6 +76,7 @@ pub struct ZoneData {
     /// Flags for this zone.
     ///
     /// Minimum version: 5
+    #[cfg_attr(feature = "serde", serde(with = "crate::data::serde_flags::option"))]
     pub flags: ProtocolOption<5, FlagSet<ZoneFlags>>,
 
     /// Zone LED matrix (if [ZoneData::zone_type] is [ZoneType::Matrix]).
@@ -169,6 +175,36 @@ impl DeserFromBuf for ZoneData {
     }
 }
 
+impl SerToBuf for ZoneData {
+    fn serialize(&self, buf: &mut WriteMessage) -> OpenRgbResult<()> {
+        buf.push_value(&self.name)?
+            .push_value(&self.zone_type)?
+            .push_value(&self.leds_min)?
+            .push_value(&self.leds_max)?
+            .push_value(&self.leds_count)?;
+        match &self.matrix {
+            None => buf.write_u16(0),
+            Some(matrix) => {
+                // height and width come before the data, and are included in the length
+                let matrix_len = u16::try_from((matrix.num_elements() + 2) * size_of::<u32>())
+                    .map_err(|e| {
+                        OpenRgbError::ProtocolError(format!(
+                            "Zone matrix is too large to encode: {e}"
+                        ))
+                    })?;
+                buf.write_u16(matrix_len);
+                buf.write_u32(matrix.num_rows() as u32);
+                buf.write_u32(matrix.num_columns() as u32);
+                for led in matrix.elements_row_major_iter() {
+                    buf.write_u32(*led);
+                }
+            }
+        }
+        buf.push_value(&self.segments)?.push_value(&self.flags)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error;
@@ -9,7 +9,18 @@ use crate::{
 /// If not, then this is always `ProtocolOption::UnsupportedVersion`.
 ///
 /// Useful when determining sizes of data structures that contains fields that may not be supported by the current protocol version.
+///
+/// With the `serde` feature, this is serialized like an [`Option`].
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "Option<T>",
+        into = "Option<T>",
+        bound(serialize = "T: Clone + serde::Serialize")
+    )
+)]
 pub enum ProtocolOption<const VER: usize, T> {
     /// Value is supported by the current protocol version.
     Some(T),
@@ -32,6 +43,15 @@ impl<const VER: usize, T> From<ProtocolOption<VER, T>> for Option<T> {
     }
 }
 
+impl<const VER: usize, T> From<Option<T>> for ProtocolOption<VER, T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => ProtocolOption::Some(v),
+            None => ProtocolOption::UnsupportedVersion,
+        }
+    }
+}
+
 impl<const VER: usize, T> ProtocolOption<VER, T> {
     /// Creates a new `ProtocolOption` with the given value if the protocol version is supported.
     pub fn new(val: T, version: usize) -> Self {
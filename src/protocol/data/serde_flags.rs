@@ -0,0 +1,80 @@
+//! Serializes flag sets as lists of flag names, such as `["HasSpeed", "HasBrightness"]`.
+//!
+//! Use with `#[serde(with = "serde_flags")]`.
+
+use flagset::{FlagSet, Flags};
+use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+pub(crate) fn serialize<F: Flags, S: Serializer>(
+    flags: &FlagSet<F>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let names = flags
+        .into_iter()
+        .filter(|flag| !is_composite(*flag))
+        .map(|flag| format!("{flag:?}"));
+    serializer.collect_seq(names)
+}
+
+pub(crate) fn deserialize<'de, F: Flags, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<FlagSet<F>, D::Error> {
+    let names = Vec::<String>::deserialize(deserializer)?;
+    names.iter().try_fold(FlagSet::default(), |flags, name| {
+        let flag = F::LIST
+            .iter()
+            .find(|flag| format!("{flag:?}") == *name)
+            .ok_or_else(|| D::Error::custom(format!("unknown flag {name:?}")))?;
+        Ok(flags | *flag)
+    })
+}
+
+/// Returns true if `flag` is a combination of other flags, such as `ModeFlag::HasDirection`.
+fn is_composite<F: Flags>(flag: F) -> bool {
+    let set: FlagSet<F> = flag.into();
+    F::LIST
+        .iter()
+        .any(|other| *other != flag && set.contains(*other))
+}
+
+/// Serializes optional flag sets, such as `ProtocolOption<5, FlagSet<ZoneFlags>>`, as a list of names or `null`.
+///
+/// Use with `#[serde(with = "serde_flags::option")]`.
+pub(crate) mod option {
+    use flagset::{FlagSet, Flags};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Flag set that is serialized as a list of names.
+    struct Names<F: Flags>(FlagSet<F>);
+
+    impl<F: Flags> Serialize for Names<F> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize(&self.0, serializer)
+        }
+    }
+
+    impl<'de, F: Flags> Deserialize<'de> for Names<F> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            super::deserialize(deserializer).map(Self)
+        }
+    }
+
+    pub(crate) fn serialize<F, T, S>(flags: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: Flags,
+        T: Clone + Into<Option<FlagSet<F>>>,
+        S: Serializer,
+    {
+        flags.clone().into().map(Names).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, F, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        F: Flags,
+        T: From<Option<FlagSet<F>>>,
+        D: Deserializer<'de>,
+    {
+        let names = Option::<Names<F>>::deserialize(deserializer)?;
+        Ok(names.map(|names| names.0).into())
+    }
+}
@@ -87,6 +87,14 @@ impl WriteMessage {
     }
 }
 
+/// Writes an already serialized message as is.
+impl SerToBuf for WriteMessage {
+    fn serialize(&self, buf: &mut WriteMessage) -> OpenRgbResult<()> {
+        buf.write_slice(self.bytes());
+        Ok(())
+    }
+}
+
 impl std::io::Write for WriteMessage {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         self.buf.extend_from_slice(buf);
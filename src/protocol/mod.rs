@@ -1,12 +1,20 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
-use std::net::Ipv4Addr;
-use std::sync::Arc;
+use std::future::Future;
+use std::net::{Ipv4Addr, SocketAddr};
+#[cfg(unix)]
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex};
 
 use tokio::net::ToSocketAddrs;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast, watch};
 
 use super::data::{Color, ControllerData, ModeData, RawString, SegmentData};
-use crate::{OpenRgbError, OpenRgbResult, PluginData};
+use crate::{
+    Brightness, Calibrations, ControllerKey, OpenRgbError, OpenRgbResult, PluginData,
+    ReconnectPolicy, ServerEvent, Timeouts,
+};
 
 /// Default protocol version used by the [`crate::OpenRgbClient::connect`].
 pub const DEFAULT_PROTOCOL: u32 = 5;
@@ -17,115 +25,534 @@ pub const DEFAULT_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::LOCALHOST, 6742);
 /// Device ID to use when no specific device is targeted.
 const NO_DEVICE_ID: u32 = 0;
 
+/// Number of [`ServerEvent`]s that are buffered for subscribers that are lagging behind.
+const EVENT_CAPACITY: usize = 16;
+
+mod connection;
 pub mod data;
 mod deserialize;
 mod packet;
+mod reconnect;
 mod serialize;
 mod stream;
+mod tcp;
+mod update;
+
+pub(crate) use {
+    connection::*, deserialize::*, packet::*, serialize::*, stream::*, tcp::*, update::*,
+};
+
+use reconnect::ReplayState;
+
+/// Opens a new stream to the server. Used for the initial connection and when reconnecting.
+pub(crate) type Connector = Box<
+    dyn Fn() -> Pin<Box<dyn Future<Output = std::io::Result<BoxedTransport>> + Send>> + Send + Sync,
+>;
+
+/// Boxes a function that opens streams into a [`Connector`].
+pub(crate) fn connector<F, Fut, S>(connect: F) -> Connector
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = std::io::Result<S>> + Send + 'static,
+    S: Transport,
+{
+    Box::new(move || {
+        let fut = connect();
+        Box::pin(async move { Ok(Box::new(fut.await?) as BoxedTransport) })
+    })
+}
 
-pub(crate) use {deserialize::*, packet::*, serialize::*, stream::*};
+/// [`Connector`] that hands out `stream` once, and fails for every reconnect attempt after that.
+pub(crate) fn stream_connector(stream: impl Transport) -> Connector {
+    let stream = StdMutex::new(Some(Box::new(stream) as BoxedTransport));
+    Box::new(move || {
+        let stream = stream.lock().expect("stream lock poisoned").take();
+        Box::pin(async move {
+            stream.ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotConnected,
+                    "a stream passed to the client cannot be reopened",
+                )
+            })
+        })
+    })
+}
+
+/// Called with every update this client sent to a controller, see [`OpenRgbProtocol::add_update_listener`].
+///
+/// Returns false once it is no longer interested, which removes it.
+pub(crate) type UpdateListener = Box<dyn Fn(u32, &ControllerUpdate) -> bool + Send + Sync>;
 
 /// `OpenRGB` client.
 ///
 /// This struct makes sure the `protocol_id` and the stream are in sync.
-///
-/// Todo: reintroduce a generic `stream` type to support sync/async streams.
+/// Clones share the same connection, which is replaced when reconnecting.
+/// Any [`Transport`] can carry the connection, see [`Connector`].
 #[derive(Clone)]
 pub(crate) struct OpenRgbProtocol {
-    protocol_id: u32,
-    stream: Arc<Mutex<ProtocolStream>>,
+    inner: Arc<ProtocolInner>,
+}
+
+/// State shared by all clones of an [`OpenRgbProtocol`].
+pub(crate) struct ProtocolInner {
+    /// Server address, only used in error messages.
+    addr: String,
+    connector: Connector,
+    /// Highest protocol version to negotiate with the server.
+    max_protocol_version: u32,
+    /// Connection currently in use.
+    connection: StdMutex<Arc<Connection>>,
+    /// Held while replacing the connection, so only one reconnect happens at a time.
+    reconnect_lock: Mutex<()>,
+    reconnect_policy: StdMutex<Option<ReconnectPolicy>>,
+    /// Client name, sent again after reconnecting.
+    name: StdMutex<Option<String>>,
+    /// Last state sent to each controller, only recorded if the reconnect policy restores state.
+    replay: StdMutex<HashMap<u32, ReplayState>>,
+    update_listeners: StdMutex<Vec<UpdateListener>>,
+    events: broadcast::Sender<ServerEvent>,
+    /// Shared with every connection, so changes apply to the current connection right away.
+    timeouts: watch::Sender<Timeouts>,
+    /// Applied by controllers to the colors they send, see [`crate::Controller::calibration`].
+    calibrations: StdMutex<Arc<Calibrations>>,
+    /// Applied by controllers to the colors they send, see [`crate::OpenRgbClient::set_brightness`].
+    brightness: StdMutex<Brightness>,
+    /// Brightness of single devices, see [`crate::Controller::set_brightness`].
+    ///
+    /// Kept here rather than in each [`crate::Controller`], so it applies to every copy of a device,
+    /// including ones fetched later. Looked up like [`ControllerKey`] finds devices,
+    /// so it still applies after the location of a device changed.
+    controller_brightness: StdMutex<Vec<(ControllerKey, Brightness)>>,
+}
+
+impl ProtocolInner {
+    /// Returns the connection currently in use, which may be closed.
+    fn current(&self) -> Arc<Connection> {
+        self.connection
+            .lock()
+            .expect("connection lock poisoned")
+            .clone()
+    }
+
+    fn reconnect_policy(&self) -> Option<ReconnectPolicy> {
+        self.reconnect_policy
+            .lock()
+            .expect("reconnect policy lock poisoned")
+            .clone()
+    }
+
+    /// Opens and negotiates a new connection, without making it the current one.
+    async fn open(&self) -> OpenRgbResult<Connection> {
+        open_connection(
+            &self.connector,
+            &self.addr,
+            self.max_protocol_version,
+            &self.events,
+            self.timeouts.subscribe(),
+        )
+        .await
+    }
+
+    /// Records an update sent to a controller, if it should be restored after reconnecting,
+    /// and hands it to the update listeners.
+    ///
+    /// `update` is only called if anyone needs the update.
+    fn sent(&self, controller_id: u32, update: impl FnOnce() -> ControllerUpdate) {
+        let restore = self.reconnect_policy().is_some_and(|p| p.restore_state());
+        let mut listeners = self
+            .update_listeners
+            .lock()
+            .expect("update listeners lock poisoned");
+        if !restore && listeners.is_empty() {
+            return;
+        }
+        let update = update();
+        if restore {
+            let mut replay = self.replay.lock().expect("replay lock poisoned");
+            replay.entry(controller_id).or_default().record(&update);
+        }
+        listeners.retain(|listener| listener(controller_id, &update));
+    }
+}
+
+/// Opens and negotiates a new connection using `connector`.
+async fn open_connection(
+    connector: &Connector,
+    addr: &str,
+    max_protocol_version: u32,
+    events: &broadcast::Sender<ServerEvent>,
+    timeouts: watch::Receiver<Timeouts>,
+) -> OpenRgbResult<Connection> {
+    tracing::debug!("Connecting to OpenRGB server at {}...", addr);
+    let open = async {
+        let stream = connector()
+            .await
+            .map_err(|source| OpenRgbError::ConnectionError {
+                addr: addr.to_owned(),
+                source,
+            })?;
+        negotiate(ProtocolStream::new(stream, max_protocol_version)).await
+    };
+    let connect_timeout = timeouts.borrow().connect();
+    let stream = match connect_timeout {
+        Some(timeout) => {
+            tokio::time::timeout(timeout, open)
+                .await
+                .map_err(|_| OpenRgbError::Timeout {
+                    operation: "Connecting".to_owned(),
+                    packet_id: None,
+                })??
+        }
+        None => open.await?,
+    };
+    Ok(Connection::new(stream, events.clone(), timeouts))
+}
+
+/// Requests the protocol version from the server and settles on the lowest of both versions.
+///
+/// `stream` starts out with the highest version this client is willing to use.
+async fn negotiate(mut stream: ProtocolStream) -> OpenRgbResult<ProtocolStream> {
+    let max_version = stream.protocol_version();
+    let server_protocol: u32 = stream
+        .request(NO_DEVICE_ID, PacketId::RequestProtocolVersion, &max_version)
+        .await?;
+    let protocol = max_version.min(server_protocol);
+
+    tracing::debug!(
+        "Connected to OpenRGB server using protocol version {:?}",
+        protocol
+    );
+    stream.set_protocol_version(protocol);
+    Ok(stream)
 }
 
 impl OpenRgbProtocol {
     /// Connect to `OpenRGB` server at given address with given protocol version.
+    ///
+    /// The address is resolved once, reconnecting uses the same resolved addresses.
     pub async fn connect_to(
-        addr: impl ToSocketAddrs + Debug + Copy,
+        addr: impl ToSocketAddrs + Debug,
+        protocol_version: u32,
+    ) -> OpenRgbResult<Self> {
+        Self::connect_tcp(
+            addr,
+            protocol_version,
+            TcpOptions::default(),
+            Timeouts::default(),
+        )
+        .await
+    }
+
+    /// Connect to `OpenRGB` server at given address, applying `options` to every socket.
+    ///
+    /// The address is resolved once, reconnecting uses the same resolved addresses.
+    pub async fn connect_tcp(
+        addr: impl ToSocketAddrs + Debug,
         protocol_version: u32,
+        options: TcpOptions,
+        timeouts: Timeouts,
     ) -> OpenRgbResult<Self> {
-        tracing::debug!("Connecting to OpenRGB server at {:?}...", addr);
-        let stream = ProtocolStream::connect(addr, protocol_version)
+        let addr_str = format!("{addr:?}");
+        let addrs: Arc<[SocketAddr]> = tokio::net::lookup_host(addr)
             .await
             .map_err(|source| OpenRgbError::ConnectionError {
-                addr: format!("{addr:?}"),
+                addr: addr_str.clone(),
                 source,
-            })?;
-        Self::new(stream).await
+            })?
+            .collect();
+        let connector = tcp_connector(addrs, options);
+        Self::new(addr_str, connector, protocol_version, timeouts).await
+    }
+
+    /// Connect to `OpenRGB` server listening on the Unix domain socket at `path`.
+    #[cfg(unix)]
+    pub async fn connect_unix(
+        path: impl AsRef<Path>,
+        protocol_version: u32,
+    ) -> OpenRgbResult<Self> {
+        let path = path.as_ref().to_owned();
+        let addr = path.display().to_string();
+        let connector = connector(move || tokio::net::UnixStream::connect(path.clone()));
+        Self::new(addr, connector, protocol_version, Timeouts::default()).await
+    }
+
+    /// Use an already connected `stream` to talk to the `OpenRGB` server.
+    ///
+    /// The stream cannot be reopened, so reconnecting always fails.
+    pub async fn from_stream(stream: impl Transport, protocol_version: u32) -> OpenRgbResult<Self> {
+        Self::new(
+            "custom stream".to_owned(),
+            stream_connector(stream),
+            protocol_version,
+            Timeouts::default(),
+        )
+        .await
     }
 }
 
 impl OpenRgbProtocol {
-    /// Build a new client from given stream.
+    /// Build a new client that uses `connector` to open connections.
     ///
-    /// This constructor expects a connected, ready to use stream.
-    pub async fn new(mut stream: ProtocolStream) -> OpenRgbResult<Self> {
-        let req_protocol = stream
-            .request(
-                NO_DEVICE_ID,
-                PacketId::RequestProtocolVersion,
-                &DEFAULT_PROTOCOL,
+    /// The first connection is opened immediately.
+    pub async fn new(
+        addr: String,
+        connector: Connector,
+        max_protocol_version: u32,
+        timeouts: Timeouts,
+    ) -> OpenRgbResult<Self> {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let timeouts = watch::Sender::new(timeouts);
+        let connection = Arc::new(
+            open_connection(
+                &connector,
+                &addr,
+                max_protocol_version,
+                &events,
+                timeouts.subscribe(),
             )
-            .await?;
-        let protocol = DEFAULT_PROTOCOL.min(req_protocol);
-
-        tracing::debug!(
-            "Connected to OpenRGB server using protocol version {:?}",
-            protocol
+            .await?,
         );
-        stream.set_protocol_version(protocol);
+        let inner = Arc::new(ProtocolInner {
+            addr,
+            connector,
+            max_protocol_version,
+            connection: StdMutex::new(connection.clone()),
+            reconnect_lock: Mutex::new(()),
+            reconnect_policy: StdMutex::new(None),
+            name: StdMutex::new(None),
+            replay: StdMutex::new(HashMap::new()),
+            update_listeners: StdMutex::new(Vec::new()),
+            events,
+            timeouts,
+            calibrations: StdMutex::new(Arc::default()),
+            brightness: StdMutex::new(Brightness::FULL),
+            controller_brightness: StdMutex::new(Vec::new()),
+        });
+        reconnect::supervise(&inner, &connection);
+        Ok(Self { inner })
+    }
 
-        Ok(Self {
-            protocol_id: protocol,
-            stream: Arc::new(Mutex::new(stream)),
-        })
+    /// Sets how to reconnect after the connection is lost. `None` disables reconnecting.
+    pub fn set_reconnect_policy(&self, policy: Option<ReconnectPolicy>) {
+        if !policy.as_ref().is_some_and(|p| p.restore_state()) {
+            self.inner
+                .replay
+                .lock()
+                .expect("replay lock poisoned")
+                .clear();
+        }
+        *self
+            .inner
+            .reconnect_policy
+            .lock()
+            .expect("reconnect policy lock poisoned") = policy;
+    }
+
+    /// Calls `listener` with every update sent to a controller after it was written,
+    /// until it returns false.
+    pub fn add_update_listener(&self, listener: UpdateListener) {
+        self.inner
+            .update_listeners
+            .lock()
+            .expect("update listeners lock poisoned")
+            .push(listener);
+    }
+
+    /// Sets the timeouts for reading and writing, and for connecting when reconnecting.
+    pub fn set_timeouts(&self, timeouts: Timeouts) {
+        self.inner.timeouts.send_replace(timeouts);
+    }
+
+    /// Sets the calibrations controllers apply to the colors they send.
+    pub fn set_calibrations(&self, calibrations: Calibrations) {
+        *self
+            .inner
+            .calibrations
+            .lock()
+            .expect("calibrations lock poisoned") = Arc::new(calibrations);
+    }
+
+    /// Returns the calibrations controllers apply to the colors they send.
+    pub fn calibrations(&self) -> Arc<Calibrations> {
+        self.inner
+            .calibrations
+            .lock()
+            .expect("calibrations lock poisoned")
+            .clone()
+    }
+
+    /// Sets the brightness controllers apply to the colors they send.
+    pub fn set_brightness(&self, brightness: Brightness) {
+        *self
+            .inner
+            .brightness
+            .lock()
+            .expect("brightness lock poisoned") = brightness;
+    }
+
+    /// Returns the brightness controllers apply to the colors they send.
+    pub fn brightness(&self) -> Brightness {
+        *self
+            .inner
+            .brightness
+            .lock()
+            .expect("brightness lock poisoned")
+    }
+
+    /// Sets the brightness the device with `key` applies to the colors it sends.
+    pub fn set_controller_brightness(&self, key: ControllerKey, brightness: Brightness) {
+        let mut overrides = self
+            .inner
+            .controller_brightness
+            .lock()
+            .expect("controller brightness lock poisoned");
+        // full brightness is kept as well, so it overrides entries for the device at an earlier location
+        overrides.retain(|(other, _)| *other != key);
+        overrides.push((key, brightness));
+    }
+
+    /// Returns the brightness the device with `key` applies to the colors it sends.
+    pub fn controller_brightness(&self, key: &ControllerKey) -> Brightness {
+        let overrides = self
+            .inner
+            .controller_brightness
+            .lock()
+            .expect("controller brightness lock poisoned");
+        key.find_entry(&overrides)
+            .copied()
+            .unwrap_or(Brightness::FULL)
+    }
+
+    /// Returns the connection to use for the next packet.
+    ///
+    /// If the current connection was lost and reconnecting is enabled, this reconnects first.
+    async fn connection(&self) -> OpenRgbResult<Arc<Connection>> {
+        let connection = self.inner.current();
+        if connection.is_closed() && self.inner.reconnect_policy().is_some() {
+            return self.reconnect(&connection).await;
+        }
+        Ok(connection)
+    }
+
+    /// Replaces the `lost` connection, unless another task already did so.
+    async fn reconnect(&self, lost: &Arc<Connection>) -> OpenRgbResult<Arc<Connection>> {
+        reconnect::reconnect(&Arc::downgrade(&self.inner), &Arc::downgrade(lost)).await
+    }
+
+    /// Returns true if `result` failed in a way that reconnecting could fix.
+    fn should_retry<T>(&self, result: &OpenRgbResult<T>) -> bool {
+        matches!(result, Err(OpenRgbError::CommunicationError { .. }))
+            && self.inner.reconnect_policy().is_some()
+    }
+
+    /// Subscribes to events pushed by the server.
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerEvent> {
+        self.inner.events.subscribe()
     }
 
     /// Get protocol version negotiated with server.
     ///
     /// This is the lowest between this client maximum supported version ([`DEFAULT_PROTOCOL`]) and server version.
+    /// It is negotiated again when reconnecting.
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#protocol-versions) for more information.
     pub fn get_protocol_version(&self) -> u32 {
-        self.protocol_id
+        self.inner.current().protocol_version()
     }
 
     /// Helper method to write a packet to the server.
+    ///
+    /// If writing fails and reconnecting is enabled, the packet is written again after reconnecting.
     async fn write_packet<T: SerToBuf>(
         &self,
         device_id: u32,
         packet_id: PacketId,
         data: &T,
     ) -> OpenRgbResult<()> {
-        self.stream
-            .lock()
-            .await
-            .write_packet(device_id, packet_id, data)
-            .await
+        let connection = self.connection().await?;
+        let result = connection.write_packet(device_id, packet_id, data).await;
+        if self.should_retry(&result) {
+            return self
+                .reconnect(&connection)
+                .await?
+                .write_packet(device_id, packet_id, data)
+                .await;
+        }
+        result
     }
 
     /// Helper method to write a packet to the server and parse the response.
+    ///
+    /// If the request fails and reconnecting is enabled, the request is sent again after reconnecting.
     async fn request<I: SerToBuf, O: DeserFromBuf>(
         &self,
         device_id: u32,
         packet_id: PacketId,
         data: &I,
     ) -> OpenRgbResult<O> {
-        self.stream
-            .lock()
-            .await
-            .request(device_id, packet_id, data)
-            .await
+        let connection = self.connection().await?;
+        let result = connection.request(device_id, packet_id, data).await;
+        if self.should_retry(&result) {
+            return self
+                .reconnect(&connection)
+                .await?
+                .request(device_id, packet_id, data)
+                .await;
+        }
+        result
+    }
+
+    /// Helper method to write packets for several devices to the server at once.
+    ///
+    /// If writing fails and reconnecting is enabled, all packets are written again after reconnecting.
+    async fn write_packets<T: SerToBuf>(
+        &self,
+        packet_id: PacketId,
+        packets: &[(u32, T)],
+    ) -> OpenRgbResult<()> {
+        let connection = self.connection().await?;
+        let result = connection.write_packets(packet_id, packets).await;
+        if self.should_retry(&result) {
+            return self
+                .reconnect(&connection)
+                .await?
+                .write_packets(packet_id, packets)
+                .await;
+        }
+        result
+    }
+
+    /// Helper method to send requests for several devices at once and parse all responses.
+    ///
+    /// If any request fails and reconnecting is enabled, all requests are sent again after reconnecting.
+    async fn request_all<I: SerToBuf, O: DeserFromBuf>(
+        &self,
+        packet_id: PacketId,
+        requests: &[(u32, I)],
+    ) -> OpenRgbResult<Vec<O>> {
+        let connection = self.connection().await?;
+        let result = connection.request_all(packet_id, requests).await;
+        if self.should_retry(&result) {
+            return self
+                .reconnect(&connection)
+                .await?
+                .request_all(packet_id, requests)
+                .await;
+        }
+        result
     }
 
     /// Set client name.
     ///
+    /// The name is sent again after reconnecting.
+    ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_set_client_name) for more information.
     pub async fn set_name(&self, name: impl Into<String>) -> OpenRgbResult<()> {
-        self.write_packet(
-            NO_DEVICE_ID,
-            PacketId::SetClientName,
-            &RawString(&name.into()),
-        )
-        .await
+        let name = name.into();
+        self.write_packet(NO_DEVICE_ID, PacketId::SetClientName, &RawString(&name))
+            .await?;
+        *self.inner.name.lock().expect("name lock poisoned") = Some(name);
+        Ok(())
     }
 
     /// Get number of controllers.
@@ -144,13 +571,32 @@ impl OpenRgbProtocol {
             .request(
                 controller_id,
                 PacketId::RequestControllerData,
-                &self.protocol_id,
+                &self.get_protocol_version(),
             )
             .await?;
         c.set_id(controller_id);
         Ok(c)
     }
 
+    /// Get controller data of several controllers, with all requests in flight at once.
+    pub async fn get_controllers(
+        &self,
+        controller_ids: impl IntoIterator<Item = u32>,
+    ) -> OpenRgbResult<Vec<ControllerData>> {
+        let protocol_version = self.get_protocol_version();
+        let requests = controller_ids
+            .into_iter()
+            .map(|id| (id, protocol_version))
+            .collect::<Vec<_>>();
+        let mut controllers: Vec<ControllerData> = self
+            .request_all(PacketId::RequestControllerData, &requests)
+            .await?;
+        for (c, (id, _)) in controllers.iter_mut().zip(&requests) {
+            c.set_id(*id);
+        }
+        Ok(controllers)
+    }
+
     /// Resize a controller zone.
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_resizezone) for more information.
@@ -165,7 +611,13 @@ impl OpenRgbProtocol {
             PacketId::RGBControllerResizeZone,
             &(zone_id, new_size),
         )
-        .await
+        .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::ResizeZone {
+                zone_id,
+                size: new_size,
+            });
+        Ok(())
     }
 
     /// Update a single LED.
@@ -182,7 +634,13 @@ impl OpenRgbProtocol {
             PacketId::RGBControllerUpdateSingleLed,
             &(led_id, color),
         )
-        .await
+        .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::UpdateSingleLed {
+                led_id,
+                color: *color,
+            });
+        Ok(())
     }
 
     /// Update LEDs.
@@ -196,7 +654,29 @@ impl OpenRgbProtocol {
     pub async fn update_leds(&self, controller_id: u32, colors: &[Color]) -> OpenRgbResult<()> {
         let packet = OpenRgbPacket::new(colors);
         self.write_packet(controller_id, PacketId::RGBControllerUpdateLeds, &packet)
-            .await
+            .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::UpdateLeds {
+                colors: colors.to_vec(),
+            });
+        Ok(())
+    }
+
+    /// Update the LEDs of several controllers, writing all packets at once.
+    pub async fn update_leds_batch(&self, updates: &[(u32, &[Color])]) -> OpenRgbResult<()> {
+        let packets = updates
+            .iter()
+            .map(|(controller_id, colors)| (*controller_id, OpenRgbPacket::new(*colors)))
+            .collect::<Vec<_>>();
+        self.write_packets(PacketId::RGBControllerUpdateLeds, &packets)
+            .await?;
+        for (controller_id, colors) in updates {
+            self.inner
+                .sent(*controller_id, || ControllerUpdate::UpdateLeds {
+                    colors: colors.to_vec(),
+                });
+        }
+        Ok(())
     }
 
     /// Update a zone LEDs.
@@ -214,7 +694,13 @@ impl OpenRgbProtocol {
             PacketId::RGBControllerUpdateZoneLeds,
             &packet,
         )
-        .await
+        .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::UpdateZoneLeds {
+                zone_id,
+                colors: colors.to_vec(),
+            });
+        Ok(())
     }
 
     /// Update a mode. This sets it to the current mode.
@@ -223,7 +709,12 @@ impl OpenRgbProtocol {
     pub async fn update_mode(&self, controller_id: u32, mode: &ModeData) -> OpenRgbResult<()> {
         let packet = OpenRgbPacket::new((mode.id() as u32, mode));
         self.write_packet(controller_id, PacketId::RGBControllerUpdateMode, &packet)
-            .await
+            .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::UpdateMode {
+                mode: mode.clone(),
+            });
+        Ok(())
     }
 
     /// Set custom mode.
@@ -264,7 +755,7 @@ impl OpenRgbProtocol {
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_save_profile) for more information.
     pub async fn save_profile(&self, name: impl Into<String>) -> OpenRgbResult<()> {
         self.check_protocol_version(2, "Save profiles")?;
-        self.write_packet(0, PacketId::RequestSaveProfile, &name.into())
+        self.write_packet(0, PacketId::RequestSaveProfile, &RawString(&name.into()))
             .await
     }
 
@@ -273,7 +764,7 @@ impl OpenRgbProtocol {
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_delete_profile) for more information.
     pub async fn delete_profile(&self, name: impl Into<String>) -> OpenRgbResult<()> {
         self.check_protocol_version(2, "Delete profiles")?;
-        self.write_packet(0, PacketId::RequestDeleteProfile, &name.into())
+        self.write_packet(0, PacketId::RequestDeleteProfile, &RawString(&name.into()))
             .await
     }
 
@@ -284,7 +775,12 @@ impl OpenRgbProtocol {
         self.check_protocol_version(3, "Save mode")?;
         let packet = OpenRgbPacket::new((mode.id() as u32, mode));
         self.write_packet(controller_id, PacketId::RGBControllerSaveMode, &packet)
-            .await
+            .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::SaveMode {
+                mode: mode.clone(),
+            });
+        Ok(())
     }
 
     /// Returns a a list of names of installed plugins.
@@ -321,13 +817,22 @@ impl OpenRgbProtocol {
         self.check_protocol_version(5, "Add Segment")?;
         let packet = OpenRgbPacket::new((zone_id, segment));
         self.write_packet(controller_id, PacketId::RGBControllerAddSegment, &packet)
-            .await
+            .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::AddSegment {
+                zone_id,
+                segment: segment.clone(),
+            });
+        Ok(())
     }
 
     pub async fn clear_segments(&self, controller_id: u32) -> OpenRgbResult<()> {
         self.check_protocol_version(5, "Clear segment")?;
         self.write_packet(controller_id, PacketId::RgbControllerClearSegments, &())
-            .await
+            .await?;
+        self.inner
+            .sent(controller_id, || ControllerUpdate::ClearSegments);
+        Ok(())
     }
 
     /// Request a device rescan.
@@ -338,10 +843,11 @@ impl OpenRgbProtocol {
     }
 
     fn check_protocol_version(&self, min: u32, msg: &str) -> OpenRgbResult<()> {
-        if self.protocol_id < min {
+        let protocol_version = self.get_protocol_version();
+        if protocol_version < min {
             return Err(OpenRgbError::UnsupportedOperation {
                 operation: msg.to_owned(),
-                current_protocol_version: self.protocol_id,
+                current_protocol_version: protocol_version,
                 min_protocol_version: min,
             });
         }
@@ -351,8 +857,12 @@ impl OpenRgbProtocol {
 
 #[cfg(test)]
 mod tests {
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::time::Duration;
 
     use crate::SegmentData;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
     use tracing_test::traced_test;
 
     use crate::{
@@ -360,10 +870,449 @@ mod tests {
         Color,
         DEFAULT_ADDR,
         DEFAULT_PROTOCOL,
+        OpenRgbError,
         OpenRgbProtocol,
         OpenRgbResult,
+        PacketId,
+        ReceivedMessage,
+        ReconnectPolicy,
+        ServerEvent,
+        Timeouts,
+        connector,
+        read_raw_packet,
+        write_raw_packet,
     };
 
+    /// Accepts a single client and answers its protocol version request.
+    async fn accept_negotiated(listener: &TcpListener) -> OpenRgbResult<TcpStream> {
+        let (stream, _) = listener.accept().await?;
+        serve_negotiation(stream).await
+    }
+
+    /// Answers the protocol version request of the client on the other end of `stream`.
+    async fn serve_negotiation<S: AsyncRead + AsyncWrite + Unpin>(
+        mut stream: S,
+    ) -> OpenRgbResult<S> {
+        let (header, _) = read_raw_packet(&mut stream).await?;
+        assert_eq!(header.packet_id, PacketId::RequestProtocolVersion);
+        write_raw_packet(
+            &mut stream,
+            DEFAULT_PROTOCOL,
+            0,
+            PacketId::RequestProtocolVersion,
+            &DEFAULT_PROTOCOL,
+        )
+        .await?;
+        Ok(stream)
+    }
+
+    async fn listen() -> OpenRgbResult<(TcpListener, SocketAddr)> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let addr = listener.local_addr()?;
+        Ok((listener, addr))
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_device_list_updated_event() -> OpenRgbResult<()> {
+        let (listener, addr) = listen().await?;
+        let server = tokio::spawn(async move {
+            let mut stream = accept_negotiated(&listener).await?;
+            let (header, _) = read_raw_packet(&mut stream).await?;
+            assert_eq!(header.packet_id, PacketId::RequestControllerCount);
+            // server pushes an update right before answering
+            write_raw_packet(
+                &mut stream,
+                DEFAULT_PROTOCOL,
+                0,
+                PacketId::DeviceListUpdated,
+                &(),
+            )
+            .await?;
+            write_raw_packet(
+                &mut stream,
+                DEFAULT_PROTOCOL,
+                0,
+                PacketId::RequestControllerCount,
+                &3_u32,
+            )
+            .await?;
+            OpenRgbResult::Ok(stream)
+        });
+
+        let client = OpenRgbProtocol::connect_to(addr, DEFAULT_PROTOCOL).await?;
+        let mut events = client.subscribe();
+        assert_eq!(client.get_controller_count().await?, 3);
+        assert_eq!(
+            events.recv().await.ok(),
+            Some(ServerEvent::DeviceListUpdated)
+        );
+        server.await.expect("server panicked")?;
+        Ok(())
+    }
+
+    /// Answers a single controller count request with `count`.
+    async fn serve_controller_count<S: AsyncRead + AsyncWrite + Unpin>(
+        stream: S,
+        count: u32,
+    ) -> OpenRgbResult<S> {
+        let mut stream = serve_negotiation(stream).await?;
+        let (header, _) = read_raw_packet(&mut stream).await?;
+        assert_eq!(header.packet_id, PacketId::RequestControllerCount);
+        write_raw_packet(
+            &mut stream,
+            DEFAULT_PROTOCOL,
+            0,
+            PacketId::RequestControllerCount,
+            &count,
+        )
+        .await?;
+        Ok(stream)
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_pipelined_requests() -> OpenRgbResult<()> {
+        let (client_end, server_end) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            let mut stream = serve_negotiation(server_end).await?;
+            // all requests arrive before any of them is answered
+            let mut devices = Vec::new();
+            for _ in 0..3 {
+                let (header, _) = read_raw_packet(&mut stream).await?;
+                assert_eq!(header.packet_id, PacketId::RequestControllerCount);
+                devices.push(header.device_id);
+            }
+            assert_eq!(devices, [0, 1, 0]);
+            // replies for different devices can come in any order
+            for (device_id, reply) in [(1, 10_u32), (0, 1), (0, 2)] {
+                write_raw_packet(
+                    &mut stream,
+                    DEFAULT_PROTOCOL,
+                    device_id,
+                    PacketId::RequestControllerCount,
+                    &reply,
+                )
+                .await?;
+            }
+            OpenRgbResult::Ok(stream)
+        });
+
+        let client = OpenRgbProtocol::from_stream(client_end, DEFAULT_PROTOCOL).await?;
+        let replies: Vec<u32> = client
+            .request_all(
+                PacketId::RequestControllerCount,
+                &[(0, ()), (1, ()), (0, ())],
+            )
+            .await?;
+        assert_eq!(replies, [1, 10, 2]);
+        server.await.expect("server panicked")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_read_timeout() -> OpenRgbResult<()> {
+        let (listener, addr) = listen().await?;
+        let server = tokio::spawn(async move {
+            // never answers, but keeps the connection open
+            let mut wedged = accept_negotiated(&listener).await?;
+            let (header, _) = read_raw_packet(&mut wedged).await?;
+            assert_eq!(header.packet_id, PacketId::RequestControllerCount);
+            let (stream, _) = listener.accept().await?;
+            serve_controller_count(stream, 4).await?;
+            OpenRgbResult::Ok(wedged)
+        });
+
+        let client = OpenRgbProtocol::connect_to(addr, DEFAULT_PROTOCOL).await?;
+        client.set_timeouts(Timeouts::default().with_read(Duration::from_millis(50)));
+        let result = client.get_controller_count().await;
+        assert!(matches!(
+            result,
+            Err(OpenRgbError::Timeout {
+                packet_id: Some(0),
+                ..
+            })
+        ));
+        // the connection is poisoned, so a late reply cannot be read as the reply to this request
+        assert!(client.get_controller_count().await.is_err());
+
+        client.set_reconnect_policy(Some(ReconnectPolicy::default()));
+        assert_eq!(client.get_controller_count().await?, 4);
+        server.await.expect("server panicked")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_connect_timeout() -> OpenRgbResult<()> {
+        let (listener, addr) = listen().await?;
+        let connector = connector(move || TcpStream::connect(addr));
+        let timeouts = Timeouts::default().with_connect(Duration::from_millis(50));
+        let result =
+            OpenRgbProtocol::new(addr.to_string(), connector, DEFAULT_PROTOCOL, timeouts).await;
+        assert!(matches!(
+            result,
+            Err(OpenRgbError::Timeout {
+                packet_id: None,
+                ..
+            })
+        ));
+        drop(listener);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_duplex_stream() -> OpenRgbResult<()> {
+        let (client_end, server_end) = tokio::io::duplex(1024);
+        let server = tokio::spawn(serve_controller_count(server_end, 7));
+
+        let client = OpenRgbProtocol::from_stream(client_end, DEFAULT_PROTOCOL).await?;
+        assert_eq!(client.get_protocol_version(), DEFAULT_PROTOCOL);
+        assert_eq!(client.get_controller_count().await?, 7);
+        server.await.expect("server panicked")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_write_timeout_excludes_waiting() -> OpenRgbResult<()> {
+        let (client_end, server_end) = tokio::io::duplex(64);
+        let server = tokio::spawn(async move {
+            // a slow but healthy server, taking about 300ms for each update
+            let mut stream = serve_negotiation(server_end).await?;
+            let mut buf = [0; 64];
+            while stream.read(&mut buf).await? > 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            OpenRgbResult::Ok(())
+        });
+        let client = OpenRgbProtocol::from_stream(client_end, DEFAULT_PROTOCOL).await?;
+        client.set_timeouts(Timeouts::default().with_write(Duration::from_millis(500)));
+
+        // the second update waits for the first one, which does not count towards its timeout
+        let colors = [Color::new(255, 0, 0); 475];
+        let (first, second) = tokio::join!(
+            client.update_leds(0, &colors),
+            client.update_leds(1, &colors)
+        );
+        first?;
+        second?;
+        drop(client);
+        server.await.expect("server panicked")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_cancelled_write() -> OpenRgbResult<()> {
+        // too small for a whole header, so writes block until the server reads
+        let (client_end, server_end) = tokio::io::duplex(8);
+        let server = tokio::spawn(serve_negotiation(server_end));
+        let client = OpenRgbProtocol::from_stream(client_end, DEFAULT_PROTOCOL).await?;
+        let _server_end = server.await.expect("server panicked")?;
+
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(50), client.get_controller_count()).await;
+        assert!(cancelled.is_err());
+        // half a packet was written, so the connection is poisoned
+        assert!(client.get_controller_count().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_cancelled_update() -> OpenRgbResult<()> {
+        let (client_end, server_end) = tokio::io::duplex(8);
+        let server = tokio::spawn(serve_negotiation(server_end));
+        let client = OpenRgbProtocol::from_stream(client_end, DEFAULT_PROTOCOL).await?;
+        let _server_end = server.await.expect("server panicked")?;
+
+        let colors = [Color::new(255, 0, 0); 4];
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(50), client.update_leds(0, &colors)).await;
+        assert!(cancelled.is_err());
+        assert!(client.get_controller_count().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_oversized_packet() -> OpenRgbResult<()> {
+        let (client_end, mut server_end) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            read_raw_packet(&mut server_end).await?;
+            // header of a 4 GiB reply, without a body
+            let mut header = b"ORGB".to_vec();
+            header.extend_from_slice(&0_u32.to_le_bytes());
+            header.extend_from_slice(&40_u32.to_le_bytes());
+            header.extend_from_slice(&u32::MAX.to_le_bytes());
+            server_end.write_all(&header).await?;
+            OpenRgbResult::Ok(server_end)
+        });
+
+        let result = OpenRgbProtocol::from_stream(client_end, DEFAULT_PROTOCOL).await;
+        assert!(matches!(result, Err(OpenRgbError::ProtocolError(_))));
+        server.await.expect("server panicked")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_stream_cannot_reconnect() -> OpenRgbResult<()> {
+        let (client_end, server_end) = tokio::io::duplex(1024);
+        let server = tokio::spawn(serve_negotiation(server_end));
+
+        let client = OpenRgbProtocol::from_stream(client_end, DEFAULT_PROTOCOL).await?;
+        client.set_reconnect_policy(Some(ReconnectPolicy::default().with_max_attempts(1)));
+        drop(server.await.expect("server panicked")?);
+        assert!(client.get_controller_count().await.is_err());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    #[traced_test]
+    async fn test_unix_socket() -> OpenRgbResult<()> {
+        let path = std::env::temp_dir().join(format!("openrgb2-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            serve_controller_count(stream, 2).await
+        });
+
+        let client = OpenRgbProtocol::connect_unix(&path, DEFAULT_PROTOCOL).await?;
+        assert_eq!(client.get_controller_count().await?, 2);
+        server.await.expect("server panicked")?;
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_request_after_disconnect() -> OpenRgbResult<()> {
+        let (listener, addr) = listen().await?;
+        let server = tokio::spawn(async move { accept_negotiated(&listener).await });
+
+        let client = OpenRgbProtocol::connect_to(addr, DEFAULT_PROTOCOL).await?;
+        drop(server.await.expect("server panicked")?);
+        assert!(client.get_controller_count().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_reconnect_restores_state() -> OpenRgbResult<()> {
+        let colors = vec![Color::new(255, 0, 0), Color::new(0, 0, 255)];
+        let expected = colors.clone();
+        let (listener, addr) = listen().await?;
+        let server = tokio::spawn(async move {
+            // first connection is dropped right after receiving the colors
+            let mut stream = accept_negotiated(&listener).await?;
+            let (header, _) = read_raw_packet(&mut stream).await?;
+            assert_eq!(header.packet_id, PacketId::SetClientName);
+            let (header, _) = read_raw_packet(&mut stream).await?;
+            assert_eq!(header.packet_id, PacketId::RGBControllerUpdateLeds);
+            drop(stream);
+
+            let mut stream = accept_negotiated(&listener).await?;
+            let (header, body) = read_raw_packet(&mut stream).await?;
+            assert_eq!(header.packet_id, PacketId::SetClientName);
+            assert_eq!(body, b"test client\0");
+            let (header, body) = read_raw_packet(&mut stream).await?;
+            assert_eq!(header.packet_id, PacketId::RGBControllerUpdateLeds);
+            assert_eq!(header.device_id, 1);
+            let mut recv = ReceivedMessage::new(&body, DEFAULT_PROTOCOL);
+            recv.read_u32()?;
+            assert_eq!(recv.read_value::<Vec<Color>>()?, expected);
+            OpenRgbResult::Ok(stream)
+        });
+
+        let client = OpenRgbProtocol::connect_to(addr, DEFAULT_PROTOCOL).await?;
+        client.set_reconnect_policy(Some(
+            ReconnectPolicy::default()
+                .with_initial_delay(Duration::from_millis(10))
+                .with_max_attempts(10)
+                .with_restore_state(true),
+        ));
+        let mut events = client.subscribe();
+        client.set_name("test client").await?;
+        client.update_leds(1, &colors).await?;
+
+        assert_eq!(events.recv().await.ok(), Some(ServerEvent::Disconnected));
+        assert_eq!(events.recv().await.ok(), Some(ServerEvent::Reconnected));
+        server.await.expect("server panicked")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_profile_name_encoding() -> OpenRgbResult<()> {
+        let (listener, addr) = listen().await?;
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            read_raw_packet(&mut stream).await?;
+            write_raw_packet(
+                &mut stream,
+                DEFAULT_PROTOCOL,
+                0,
+                PacketId::RequestProtocolVersion,
+                &DEFAULT_PROTOCOL,
+            )
+            .await?;
+            let mut packets = Vec::new();
+            for _ in 0..2 {
+                let (header, body) = read_raw_packet(&mut stream).await?;
+                packets.push((header.packet_id, body));
+            }
+            OpenRgbResult::Ok(packets)
+        });
+
+        let client = OpenRgbProtocol::connect_to(addr, DEFAULT_PROTOCOL).await?;
+        client.save_profile("Desk").await?;
+        client.delete_profile("Desk").await?;
+        // profile names are null terminated, without the length prefix of other strings
+        assert_eq!(
+            server.await.expect("server panicked")?,
+            [
+                (PacketId::RequestSaveProfile, b"Desk\0".to_vec()),
+                (PacketId::RequestDeleteProfile, b"Desk\0".to_vec()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_requested_version() -> OpenRgbResult<()> {
+        let (listener, addr) = listen().await?;
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            let (header, body) = read_raw_packet(&mut stream).await?;
+            write_raw_packet(
+                &mut stream,
+                DEFAULT_PROTOCOL,
+                0,
+                PacketId::RequestProtocolVersion,
+                &DEFAULT_PROTOCOL,
+            )
+            .await?;
+            OpenRgbResult::Ok(((header.packet_id, body), stream))
+        });
+
+        let client = OpenRgbProtocol::connect_to(addr, 3).await?;
+        assert_eq!(client.get_protocol_version(), 3);
+        let (request, _stream) = server.await.expect("server panicked")?;
+        assert_eq!(
+            request,
+            (
+                PacketId::RequestProtocolVersion,
+                3_u32.to_le_bytes().to_vec()
+            )
+        );
+        Ok(())
+    }
+
     // create test methods for each of the OpenRGBProtocol methods
 
     #[tokio::test]
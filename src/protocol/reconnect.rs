@@ -0,0 +1,178 @@
+//! Reconnecting after the connection to the server is lost.
+
+use std::sync::{Arc, Weak};
+
+use super::{
+    Connection, ControllerUpdate, NO_DEVICE_ID, OpenRgbPacket, PacketId, ProtocolInner,
+    connection_closed,
+};
+use crate::{Color, ModeData, OpenRgbResult, RawString, ReconnectPolicy, ServerEvent};
+
+/// LED update that was sent after the last update of all LEDs.
+#[derive(Debug, Clone)]
+enum LedUpdate {
+    Zone(u32, Vec<Color>),
+    Single(i32, Color),
+}
+
+/// Last state this client sent to a single controller, re-applied after reconnecting.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReplayState {
+    mode: Option<ModeData>,
+    leds: Option<Vec<Color>>,
+    /// Zone and single LED updates sent after `leds`, in the order they were sent.
+    updates: Vec<LedUpdate>,
+}
+
+impl ReplayState {
+    /// Records an update that was sent to the controller, ignoring updates that are not restored.
+    pub fn record(&mut self, update: &ControllerUpdate) {
+        match update {
+            ControllerUpdate::UpdateMode { mode } => self.mode = Some(mode.clone()),
+            ControllerUpdate::UpdateLeds { colors } => {
+                self.leds = Some(colors.clone());
+                self.updates.clear();
+            }
+            ControllerUpdate::UpdateZoneLeds { zone_id, colors } => {
+                self.updates
+                    .retain(|u| !matches!(u, LedUpdate::Zone(id, _) if id == zone_id));
+                self.updates.push(LedUpdate::Zone(*zone_id, colors.clone()));
+            }
+            ControllerUpdate::UpdateSingleLed { led_id, color } => {
+                self.updates
+                    .retain(|u| !matches!(u, LedUpdate::Single(id, _) if id == led_id));
+                self.updates.push(LedUpdate::Single(*led_id, *color));
+            }
+            _ => {}
+        }
+    }
+
+    /// Sends this state to the controller with `controller_id` over `connection`.
+    async fn apply(&self, controller_id: u32, connection: &Connection) -> OpenRgbResult<()> {
+        if let Some(mode) = &self.mode {
+            let packet = OpenRgbPacket::new((mode.id() as u32, mode));
+            connection
+                .write_packet(controller_id, PacketId::RGBControllerUpdateMode, &packet)
+                .await?;
+        }
+        if let Some(leds) = &self.leds {
+            let packet = OpenRgbPacket::new(leds.as_slice());
+            connection
+                .write_packet(controller_id, PacketId::RGBControllerUpdateLeds, &packet)
+                .await?;
+        }
+        for update in &self.updates {
+            match update {
+                LedUpdate::Zone(zone_id, colors) => {
+                    let packet = OpenRgbPacket::new((*zone_id, colors.as_slice()));
+                    connection
+                        .write_packet(
+                            controller_id,
+                            PacketId::RGBControllerUpdateZoneLeds,
+                            &packet,
+                        )
+                        .await?;
+                }
+                LedUpdate::Single(led_id, color) => {
+                    connection
+                        .write_packet(
+                            controller_id,
+                            PacketId::RGBControllerUpdateSingleLed,
+                            &(*led_id, color),
+                        )
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Spawns a task that waits for `connection` to close, and then reconnects if a policy is set.
+///
+/// The task only holds weak references, so it never keeps the client alive.
+pub(crate) fn supervise(inner: &Arc<ProtocolInner>, connection: &Arc<Connection>) {
+    let inner = Arc::downgrade(inner);
+    let lost = Arc::downgrade(connection);
+    let mut closed = connection.closed();
+    tokio::spawn(async move {
+        // this fails when the connection is dropped, in which case nobody needs it anymore
+        if closed.wait_for(|closed| *closed).await.is_err() {
+            return;
+        }
+
+        let Some(strong) = inner.upgrade() else {
+            return;
+        };
+        tracing::debug!("Lost connection to OpenRGB server at {}", strong.addr);
+        let _ = strong.events.send(ServerEvent::Disconnected);
+        let enabled = strong.reconnect_policy().is_some();
+        drop(strong);
+
+        if enabled {
+            if let Err(e) = reconnect(&inner, &lost).await {
+                tracing::warn!("Giving up reconnecting to OpenRGB server: {e}");
+            }
+        }
+    });
+}
+
+/// Replaces the `lost` connection, retrying according to the reconnect policy.
+///
+/// If another task already replaced `lost`, the current connection is returned instead.
+pub(crate) async fn reconnect(
+    inner: &Weak<ProtocolInner>,
+    lost: &Weak<Connection>,
+) -> OpenRgbResult<Arc<Connection>> {
+    let mut delays = None;
+    loop {
+        let strong = inner.upgrade().ok_or_else(connection_closed)?;
+        let policy = strong.reconnect_policy().ok_or_else(connection_closed)?;
+
+        let guard = strong.reconnect_lock.lock().await;
+        let current = strong.current();
+        if !std::ptr::eq(Arc::as_ptr(&current), lost.as_ptr()) {
+            return Ok(current);
+        }
+        let err = match try_reconnect(&strong, &policy).await {
+            Ok(connection) => return Ok(connection),
+            Err(e) => e,
+        };
+        drop(guard);
+        drop(strong);
+
+        let Some(delay) = delays.get_or_insert_with(|| policy.delays()).next() else {
+            return Err(err);
+        };
+        tracing::warn!("Reconnecting to OpenRGB server failed, retrying in {delay:?}: {err}");
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Makes a single attempt at opening a new connection and restoring the client state on it.
+async fn try_reconnect(
+    inner: &Arc<ProtocolInner>,
+    policy: &ReconnectPolicy,
+) -> OpenRgbResult<Arc<Connection>> {
+    let connection = Arc::new(inner.open().await?);
+
+    let name = inner.name.lock().expect("name lock poisoned").clone();
+    if let Some(name) = name {
+        connection
+            .write_packet(NO_DEVICE_ID, PacketId::SetClientName, &RawString(&name))
+            .await?;
+    }
+
+    if policy.restore_state() {
+        let replay = inner.replay.lock().expect("replay lock poisoned").clone();
+        for (controller_id, state) in &replay {
+            state.apply(*controller_id, &connection).await?;
+        }
+    }
+
+    *inner.connection.lock().expect("connection lock poisoned") = connection.clone();
+    supervise(inner, &connection);
+    tracing::debug!("Reconnected to OpenRGB server at {}", inner.addr);
+    let _ = inner.events.send(ServerEvent::Reconnected);
+    Ok(connection)
+}
@@ -2,10 +2,7 @@ use std::pin::Pin;
 
 use crate::protocol::PacketId;
 use crate::{DeserFromBuf, OpenRgbError, OpenRgbResult, ReceivedMessage, SerToBuf, WriteMessage};
-use tokio::{
-    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
-    net::{TcpStream, ToSocketAddrs},
-};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 /// Utility struct to write packets.
 /// Some packets need to be prepended by their length.
@@ -31,16 +28,20 @@ impl<T: SerToBuf> SerToBuf for OpenRgbPacket<T> {
     }
 }
 
+/// Largest packet body accepted from the other end, so a corrupt or hostile header
+/// cannot make us allocate gigabytes. Far above what any real device list or profile needs.
+pub(crate) const MAX_PACKET_SIZE: u32 = 16 * 1024 * 1024;
+
 pub(crate) struct OpenRgbMessageHeader {
-    packet_id: PacketId,
-    device_id: u32,
-    packet_size: u32,
+    pub packet_id: PacketId,
+    pub device_id: u32,
+    pub packet_size: u32,
 }
 
 impl OpenRgbMessageHeader {
     pub(crate) const MAGIC: [u8; 4] = *b"ORGB";
 
-    async fn read(stream: &mut TcpStream) -> OpenRgbResult<Self> {
+    async fn read(stream: &mut (impl AsyncRead + Unpin)) -> OpenRgbResult<Self> {
         // header is always 16 bytes long
         let mut buf = [0u8; 16];
         stream.read_exact(&mut buf).await?;
@@ -56,6 +57,12 @@ impl OpenRgbMessageHeader {
         let device_id = recv.read_u32()?;
         let packet_id = recv.read_value::<PacketId>()?;
         let packet_size = recv.read_u32()?;
+        // checked before the body is allocated
+        if packet_size > MAX_PACKET_SIZE {
+            return Err(OpenRgbError::ProtocolError(format!(
+                "packet of {packet_size} bytes exceeds the maximum of {MAX_PACKET_SIZE} bytes"
+            )));
+        }
         Ok(Self {
             device_id,
             packet_id,
@@ -63,34 +70,85 @@ impl OpenRgbMessageHeader {
         })
     }
 
-    async fn write(&self, stream: &mut TcpStream) -> OpenRgbResult<()> {
-        let mut buf = WriteMessage::with_capacity(0, 16);
+    fn write(&self, buf: &mut WriteMessage) -> OpenRgbResult<()> {
         buf.write_slice(&Self::MAGIC);
         buf.write_u32(self.device_id);
         buf.write_value(&self.packet_id)?;
         buf.write_u32(self.packet_size);
-        stream.write_all(buf.bytes()).await?;
         Ok(())
     }
 }
 
-/// `tokio TcpStream` with an OpenRGB protocol version.
+/// Reads a single packet from `stream`, returning its header and the raw packet body.
+///
+/// The body is not deserialized, since that depends on who is waiting for the packet.
+pub(crate) async fn read_raw_packet(
+    stream: &mut (impl AsyncRead + Unpin),
+) -> OpenRgbResult<(OpenRgbMessageHeader, Vec<u8>)> {
+    let header = OpenRgbMessageHeader::read(stream).await?;
+    let mut buf = vec![0u8; header.packet_size as usize];
+    stream.read_exact(&mut buf).await?;
+    Ok((header, buf))
+}
+
+/// Serializes `data` and writes it to `stream` as a single packet, header included.
+///
+/// Header and body are written at once, so Nagle's algorithm does not hold back the body.
+pub(crate) async fn write_raw_packet<T: SerToBuf>(
+    stream: &mut (impl AsyncWrite + Unpin),
+    protocol_version: u32,
+    device_id: u32,
+    packet_id: PacketId,
+    data: &T,
+) -> OpenRgbResult<()> {
+    let mut buf = WriteMessage::new(protocol_version);
+    encode_packet(&mut buf, device_id, packet_id, data)?;
+    stream.write_all(buf.bytes()).await?;
+    Ok(())
+}
+
+/// Serializes `data` as a packet, header included, and appends it to `buf`.
+pub(crate) fn encode_packet<T: SerToBuf>(
+    buf: &mut WriteMessage,
+    device_id: u32,
+    packet_id: PacketId,
+    data: &T,
+) -> OpenRgbResult<()> {
+    let mut body = WriteMessage::new(buf.protocol_version());
+    data.serialize(&mut body)?;
+    let header = OpenRgbMessageHeader {
+        packet_id,
+        device_id,
+        packet_size: body.len() as u32,
+    };
+    tracing::debug!("Writing packet: {}", body);
+
+    header.write(buf)?;
+    buf.write_slice(body.bytes());
+    Ok(())
+}
+
+/// Any bidirectional byte stream that can carry the OpenRGB protocol, such as a TCP or Unix socket.
+pub(crate) trait Transport: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send + 'static> Transport for S {}
+
+/// Type-erased [`Transport`], so clients over different transports have the same type.
+pub(crate) type BoxedTransport = Box<dyn Transport>;
+
+/// Stream with an OpenRGB protocol version.
 /// The version is tagged to all received and written packets, since packet format depends on protocol version.
-pub(crate) struct ProtocolStream {
-    stream: TcpStream,
+pub(crate) struct ProtocolStream<S = BoxedTransport> {
+    stream: S,
     protocol_version: u32,
 }
 
-impl ProtocolStream {
-    pub async fn connect<A: ToSocketAddrs>(
-        addr: A,
-        protocol_version: u32,
-    ) -> std::io::Result<Self> {
-        let stream = TcpStream::connect(addr).await?;
-        Ok(Self {
+impl<S: AsyncRead + AsyncWrite + Unpin> ProtocolStream<S> {
+    pub fn new(stream: S, protocol_version: u32) -> Self {
+        Self {
             stream,
             protocol_version,
-        })
+        }
     }
 
     pub fn protocol_version(&self) -> u32 {
@@ -131,20 +189,20 @@ impl ProtocolStream {
         packet_id: PacketId,
         data: &T,
     ) -> OpenRgbResult<()> {
-        // let mut buf = Vec::with_capacity(8);
-        let mut buf = WriteMessage::new(self.protocol_version());
-        data.serialize(&mut buf)?;
-        let packet_size = buf.len() as u32;
-        let header = OpenRgbMessageHeader {
-            packet_id,
+        let protocol_version = self.protocol_version();
+        write_raw_packet(
+            &mut self.stream,
+            protocol_version,
             device_id,
-            packet_size,
-        };
-        header.write(&mut self.stream).await?;
+            packet_id,
+            data,
+        )
+        .await
+    }
 
-        tracing::debug!("Writing packet: {}", buf);
-        self.stream.write_all(buf.bytes()).await?;
-        Ok(())
+    /// Returns the underlying stream, dropping the protocol version.
+    pub fn into_inner(self) -> S {
+        self.stream
     }
 
     async fn read_header(
@@ -169,7 +227,7 @@ impl ProtocolStream {
     }
 }
 
-impl AsyncRead for ProtocolStream {
+impl<S: AsyncRead + Unpin> AsyncRead for ProtocolStream<S> {
     fn poll_read(
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
@@ -180,7 +238,7 @@ impl AsyncRead for ProtocolStream {
     }
 }
 
-impl AsyncWrite for ProtocolStream {
+impl<S: AsyncWrite + Unpin> AsyncWrite for ProtocolStream<S> {
     fn poll_write(
         self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
@@ -206,3 +264,58 @@ impl AsyncWrite for ProtocolStream {
         AsyncWrite::poll_shutdown(pin, cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use tokio::io::AsyncWrite;
+
+    use super::write_raw_packet;
+    use crate::OpenRgbResult;
+    use crate::protocol::{DEFAULT_PROTOCOL, PacketId};
+
+    /// Stream that keeps every write separately, to see how packets are split.
+    #[derive(Default)]
+    struct RecordingStream {
+        writes: Vec<Vec<u8>>,
+    }
+
+    impl AsyncWrite for RecordingStream {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.writes.push(buf.to_vec());
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_packet_in_one_piece() -> OpenRgbResult<()> {
+        let mut stream = RecordingStream::default();
+        write_raw_packet(
+            &mut stream,
+            DEFAULT_PROTOCOL,
+            2,
+            PacketId::RequestControllerData,
+            &DEFAULT_PROTOCOL,
+        )
+        .await?;
+        // 16 bytes of header followed by the u32 body
+        assert_eq!(stream.writes.len(), 1);
+        assert_eq!(stream.writes[0].len(), 20);
+        assert_eq!(stream.writes[0][..4], *b"ORGB");
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+//! Opening TCP connections with socket options applied.
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::net::{TcpSocket, TcpStream};
+
+use super::{Connector, connector};
+
+/// Socket options applied to every TCP connection, including reconnects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TcpOptions {
+    /// Disables Nagle's algorithm, so small packets such as LED updates are sent right away.
+    pub nodelay: bool,
+    /// Enables TCP keepalive probes, to notice a server that disappeared without closing the connection.
+    pub keepalive: bool,
+}
+
+impl Default for TcpOptions {
+    fn default() -> Self {
+        Self {
+            nodelay: true,
+            keepalive: false,
+        }
+    }
+}
+
+/// [`Connector`] that tries each of `addrs` in turn, like [`TcpStream::connect`] does.
+pub(crate) fn tcp_connector(addrs: Arc<[SocketAddr]>, options: TcpOptions) -> Connector {
+    connector(move || {
+        let addrs = addrs.clone();
+        async move { connect(&addrs, options).await }
+    })
+}
+
+async fn connect(addrs: &[SocketAddr], options: TcpOptions) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for addr in addrs {
+        let socket = match addr {
+            SocketAddr::V4(_) => TcpSocket::new_v4()?,
+            SocketAddr::V6(_) => TcpSocket::new_v6()?,
+        };
+        socket.set_nodelay(options.nodelay)?;
+        socket.set_keepalive(options.keepalive)?;
+        match socket.connect(*addr).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "could not resolve to any address",
+        )
+    }))
+}
@@ -0,0 +1,208 @@
+use crate::{
+    Color, ControllerData, Led, ModeData, OpenRgbError, OpenRgbResult, SegmentData, ZoneData,
+};
+#[cfg(any(test, feature = "server"))]
+use crate::{PacketId, ReceivedMessage};
+
+/// Change a client requested to a single controller.
+///
+/// Sent by [`super::OpenRgbProtocol`] to update listeners, and read by the server from client packets.
+#[derive(Debug, Clone)]
+pub(crate) enum ControllerUpdate {
+    ResizeZone {
+        zone_id: u32,
+        size: u32,
+    },
+    ClearSegments,
+    AddSegment {
+        zone_id: u32,
+        segment: SegmentData,
+    },
+    UpdateLeds {
+        colors: Vec<Color>,
+    },
+    UpdateZoneLeds {
+        zone_id: u32,
+        colors: Vec<Color>,
+    },
+    UpdateSingleLed {
+        led_id: i32,
+        color: Color,
+    },
+    /// Only read by the server, this client does not send it.
+    #[cfg_attr(not(any(test, feature = "server")), allow(dead_code))]
+    SetCustomMode,
+    /// The mode has its id set to the mode index in the packet.
+    UpdateMode {
+        mode: ModeData,
+    },
+    /// The mode has its id set to the mode index in the packet.
+    SaveMode {
+        mode: ModeData,
+    },
+}
+
+impl ControllerUpdate {
+    /// Reads the body of a packet sent to a controller, returns `None` for any other packet.
+    #[cfg(any(test, feature = "server"))]
+    pub fn read(packet_id: PacketId, msg: &mut ReceivedMessage<'_>) -> OpenRgbResult<Option<Self>> {
+        let update = match packet_id {
+            PacketId::RGBControllerResizeZone => {
+                let (zone_id, size) = msg.read_value::<(u32, u32)>()?;
+                Self::ResizeZone { zone_id, size }
+            }
+            PacketId::RgbControllerClearSegments => Self::ClearSegments,
+            PacketId::RGBControllerAddSegment => {
+                let (_size, zone_id, segment) = msg.read_value::<(u32, u32, SegmentData)>()?;
+                Self::AddSegment { zone_id, segment }
+            }
+            PacketId::RGBControllerUpdateLeds => {
+                let (_size, colors) = msg.read_value::<(u32, Vec<Color>)>()?;
+                Self::UpdateLeds { colors }
+            }
+            PacketId::RGBControllerUpdateZoneLeds => {
+                let (_size, zone_id, colors) = msg.read_value::<(u32, u32, Vec<Color>)>()?;
+                Self::UpdateZoneLeds { zone_id, colors }
+            }
+            PacketId::RGBControllerUpdateSingleLed => {
+                let (led_id, color) = msg.read_value::<(i32, Color)>()?;
+                Self::UpdateSingleLed { led_id, color }
+            }
+            PacketId::RGBControllerSetCustomMode => Self::SetCustomMode,
+            PacketId::RGBControllerUpdateMode | PacketId::RGBControllerSaveMode => {
+                let (_size, mode_id, mut mode) = msg.read_value::<(u32, u32, ModeData)>()?;
+                mode.set_id(mode_id as usize);
+                match packet_id {
+                    PacketId::RGBControllerSaveMode => Self::SaveMode { mode },
+                    _ => Self::UpdateMode { mode },
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(update))
+    }
+
+    /// Applies this update to `controller`, leaving it untouched if the update is invalid.
+    ///
+    /// Added segments get their id set to their index in the zone.
+    pub fn apply(&mut self, controller: &mut ControllerData) -> OpenRgbResult<()> {
+        match self {
+            Self::ResizeZone { zone_id, size } => resize_zone(controller, *zone_id, *size)?,
+            Self::ClearSegments => {
+                for zone in &mut controller.zones {
+                    if let Some(segments) = zone.segments.value_mut() {
+                        segments.clear();
+                    }
+                }
+            }
+            Self::AddSegment { zone_id, segment } => {
+                let zone = zone_mut(controller, *zone_id)?;
+                if let Some(segments) = zone.segments.value_mut() {
+                    segment.set_id(segments.len());
+                    segments.push(segment.clone());
+                }
+            }
+            Self::UpdateLeds { colors } => set_colors(controller, 0, colors),
+            Self::UpdateZoneLeds { zone_id, colors } => {
+                let (offset, len) = zone_range(controller, *zone_id)?;
+                set_colors(controller, offset, &colors[..colors.len().min(len)]);
+            }
+            Self::UpdateSingleLed { led_id, color } => {
+                let slot = usize::try_from(*led_id)
+                    .ok()
+                    .and_then(|id| controller.colors.get_mut(id))
+                    .ok_or_else(|| OpenRgbError::CommandError(format!("LED {led_id} not found")))?;
+                *slot = *color;
+            }
+            Self::SetCustomMode => {
+                // same order as OpenRGB uses
+                let custom = ["direct", "custom", "static"].iter().find_map(|pat| {
+                    controller
+                        .modes
+                        .iter()
+                        .position(|m| m.name.to_ascii_lowercase() == *pat)
+                });
+                if let Some(mode_id) = custom {
+                    controller.active_mode = mode_id as i32;
+                }
+            }
+            Self::UpdateMode { mode } | Self::SaveMode { mode } => {
+                let mode_id = mode.id();
+                let slot = controller.modes.get_mut(mode_id).ok_or_else(|| {
+                    OpenRgbError::CommandError(format!("Mode {mode_id} not found"))
+                })?;
+                *slot = mode.clone();
+                controller.active_mode = mode_id as i32;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Name given to LEDs that were not named explicitly, `index` starts at 0.
+pub(crate) fn default_led_name(zone: &str, index: usize) -> String {
+    format!("{zone} LED {}", index + 1)
+}
+
+fn zone_mut(controller: &mut ControllerData, zone_id: u32) -> OpenRgbResult<&mut ZoneData> {
+    controller
+        .zones
+        .get_mut(zone_id as usize)
+        .ok_or_else(|| zone_not_found(zone_id))
+}
+
+fn zone_not_found(zone_id: u32) -> OpenRgbError {
+    OpenRgbError::CommandError(format!("Zone {zone_id} not found"))
+}
+
+/// Returns the offset of the first LED of a zone, and the number of LEDs in it.
+fn zone_range(controller: &ControllerData, zone_id: u32) -> OpenRgbResult<(usize, usize)> {
+    let index = zone_id as usize;
+    let zone = controller
+        .zones
+        .get(index)
+        .ok_or_else(|| zone_not_found(zone_id))?;
+    let offset = controller.zones[..index]
+        .iter()
+        .map(|z| z.leds_count as usize)
+        .sum();
+    Ok((offset, zone.leds_count as usize))
+}
+
+/// Sets colors starting at `offset`, ignoring colors for LEDs that do not exist.
+fn set_colors(controller: &mut ControllerData, offset: usize, colors: &[Color]) {
+    for (slot, color) in controller.colors.iter_mut().skip(offset).zip(colors) {
+        *slot = *color;
+    }
+}
+
+fn resize_zone(controller: &mut ControllerData, zone_id: u32, size: u32) -> OpenRgbResult<()> {
+    let (offset, len) = zone_range(controller, zone_id)?;
+    let zone = zone_mut(controller, zone_id)?;
+    if size < zone.leds_min || size > zone.leds_max {
+        return Err(OpenRgbError::CommandError(format!(
+            "Zone {zone_id} cannot be resized to {size} LEDs"
+        )));
+    }
+    zone.leds_count = size;
+    let zone_name = zone.name.clone();
+
+    let size = size as usize;
+    let (leds, colors) = match size > len {
+        true => (
+            (len..size)
+                .map(|i| Led {
+                    name: default_led_name(&zone_name, i),
+                    value: 0,
+                })
+                .collect(),
+            vec![Color::default(); size - len],
+        ),
+        false => (Vec::new(), Vec::new()),
+    };
+    let kept = offset + len.min(size);
+    controller.leds.splice(kept..offset + len, leds);
+    controller.colors.splice(kept..offset + len, colors);
+    controller.num_leds = controller.leds.len();
+    Ok(())
+}
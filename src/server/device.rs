@@ -0,0 +1,458 @@
+use array2d::Array2D;
+use flagset::FlagSet;
+
+use crate::{
+    Color, ColorMode, ControllerData, ControllerFlags, DeviceType, Direction, Led, ModeData,
+    ModeFlag, OpenRgbResult, ProtocolOption, SegmentData, ZoneData, ZoneFlags, ZoneType,
+    default_led_name,
+};
+
+/// Device published by an [`super::OpenRgbServer`].
+///
+/// The server keeps the colors, active mode and zone sizes that clients set,
+/// and calls these methods after applying a change so the device can show it.
+/// They are called while the server state is locked, so they should return quickly.
+/// Devices that do slow I/O can send the change to a task of their own instead.
+///
+/// Errors are logged, since the `OpenRGB` protocol has no way to report them to the client.
+///
+/// # Example
+///
+/// ```
+/// use openrgb2::server::{RgbDevice, VirtualController, VirtualZone};
+/// use openrgb2::{Color, DeviceType, OpenRgbResult};
+///
+/// struct Strip;
+///
+/// impl RgbDevice for Strip {
+///     fn controller(&self) -> VirtualController {
+///         VirtualController::new("Strip", DeviceType::LEDStrip)
+///             .with_zone(VirtualZone::linear("Strip", 30))
+///     }
+///
+///     fn update_leds(&mut self, colors: &[Color]) -> OpenRgbResult<()> {
+///         // write colors to the hardware
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait RgbDevice: Send + 'static {
+    /// Describes the device, called once when it is added to the server.
+    fn controller(&self) -> VirtualController;
+
+    /// Called after a client set the colors of all LEDs.
+    fn update_leds(&mut self, colors: &[Color]) -> OpenRgbResult<()>;
+
+    /// Called after a client set the colors of the LEDs in zone `zone_id`.
+    ///
+    /// `colors` holds all LEDs of the device. Calls [`RgbDevice::update_leds`] by default.
+    fn update_zone_leds(&mut self, zone_id: u32, colors: &[Color]) -> OpenRgbResult<()> {
+        let _ = zone_id;
+        self.update_leds(colors)
+    }
+
+    /// Called after a client set the color of LED `led_id`.
+    ///
+    /// `colors` holds all LEDs of the device. Calls [`RgbDevice::update_leds`] by default.
+    fn update_single_led(&mut self, led_id: u32, colors: &[Color]) -> OpenRgbResult<()> {
+        let _ = led_id;
+        self.update_leds(colors)
+    }
+
+    /// Called after a client activated mode `mode_id`, or changed its settings.
+    ///
+    /// Does nothing by default.
+    fn update_mode(&mut self, mode_id: u32, mode: &ModeData) -> OpenRgbResult<()> {
+        let _ = (mode_id, mode);
+        Ok(())
+    }
+
+    /// Called after a client asked to save mode `mode_id` to the device.
+    ///
+    /// Calls [`RgbDevice::update_mode`] by default.
+    fn save_mode(&mut self, mode_id: u32, mode: &ModeData) -> OpenRgbResult<()> {
+        self.update_mode(mode_id, mode)
+    }
+
+    /// Called after a client resized zone `zone_id` to `size` LEDs,
+    /// which is always within the range of the zone.
+    ///
+    /// Returning an error undoes the resize. Does nothing by default.
+    fn resize_zone(&mut self, zone_id: u32, size: u32) -> OpenRgbResult<()> {
+        let _ = (zone_id, size);
+        Ok(())
+    }
+}
+
+/// Description of a controller served by an [`super::OpenRgbServer`] or a `MockServer`.
+///
+/// Unless modes are added, the controller has a single [`VirtualMode::direct()`] mode.
+///
+/// # Example
+///
+/// ```
+/// use openrgb2::DeviceType;
+/// use openrgb2::server::{VirtualController, VirtualMode, VirtualZone};
+///
+/// let keyboard = VirtualController::new("Keyboard", DeviceType::Keyboard)
+///     .with_vendor("Acme")
+///     .with_zone(VirtualZone::matrix("Keys", 6, 21))
+///     .with_zone(VirtualZone::linear("Underglow", 12).with_size_range(0, 40))
+///     .with_mode(VirtualMode::direct())
+///     .with_mode(VirtualMode::new("Breathing").with_speed(1, 10, 5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct VirtualController {
+    device_type: DeviceType,
+    name: String,
+    vendor: String,
+    description: String,
+    version: String,
+    serial: String,
+    location: String,
+    active_mode: usize,
+    modes: Vec<VirtualMode>,
+    zones: Vec<VirtualZone>,
+    flags: FlagSet<ControllerFlags>,
+}
+
+impl VirtualController {
+    /// Creates a controller without zones.
+    pub fn new(name: impl Into<String>, device_type: DeviceType) -> Self {
+        Self {
+            device_type,
+            name: name.into(),
+            vendor: String::new(),
+            description: String::new(),
+            version: String::new(),
+            serial: String::new(),
+            location: String::new(),
+            active_mode: 0,
+            modes: Vec::new(),
+            zones: Vec::new(),
+            flags: ControllerFlags::IsLocal.into(),
+        }
+    }
+
+    /// Sets the vendor.
+    pub fn with_vendor(mut self, vendor: impl Into<String>) -> Self {
+        self.vendor = vendor.into();
+        self
+    }
+
+    /// Sets the description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Sets the version.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Sets the serial number.
+    pub fn with_serial(mut self, serial: impl Into<String>) -> Self {
+        self.serial = serial.into();
+        self
+    }
+
+    /// Sets the location.
+    pub fn with_location(mut self, location: impl Into<String>) -> Self {
+        self.location = location.into();
+        self
+    }
+
+    /// Sets the controller flags, only sent in protocol version 5 and above.
+    pub fn with_flags(mut self, flags: impl Into<FlagSet<ControllerFlags>>) -> Self {
+        self.flags = flags.into();
+        self
+    }
+
+    /// Adds a mode.
+    pub fn with_mode(mut self, mode: VirtualMode) -> Self {
+        self.modes.push(mode);
+        self
+    }
+
+    /// Sets the index of the mode that is active before any client changes it.
+    pub fn with_active_mode(mut self, mode_id: usize) -> Self {
+        self.active_mode = mode_id;
+        self
+    }
+
+    /// Adds a zone. LEDs of all zones are numbered in the order the zones are added.
+    pub fn with_zone(mut self, zone: VirtualZone) -> Self {
+        self.zones.push(zone);
+        self
+    }
+
+    pub(crate) fn build(&self) -> ControllerData {
+        let mut modes = match self.modes.is_empty() {
+            true => vec![VirtualMode::direct().mode],
+            false => self.modes.iter().map(|m| m.mode.clone()).collect(),
+        };
+        for (id, mode) in modes.iter_mut().enumerate() {
+            mode.set_id(id);
+        }
+
+        let mut zones = Vec::with_capacity(self.zones.len());
+        let mut leds = Vec::new();
+        let mut led_alt_names = Vec::new();
+        for (id, zone) in self.zones.iter().enumerate() {
+            leds.extend(zone.led_names().map(|name| Led { name, value: 0 }));
+            led_alt_names.extend(zone.led_alt_names());
+            zones.push(zone.build(id));
+        }
+        let num_leds = leds.len();
+        // servers only send alternate names for devices that have them
+        if led_alt_names.iter().all(String::is_empty) {
+            led_alt_names.clear();
+        }
+
+        ControllerData {
+            device_type: self.device_type,
+            name: self.name.clone(),
+            vendor: self.vendor.clone(),
+            description: self.description.clone(),
+            version: self.version.clone(),
+            serial: self.serial.clone(),
+            location: self.location.clone(),
+            active_mode: self.active_mode as i32,
+            modes,
+            zones,
+            colors: vec![Color::default(); num_leds],
+            leds,
+            led_alt_names: ProtocolOption::Some(led_alt_names),
+            flags: ProtocolOption::Some(self.flags),
+            id: u32::MAX,
+            num_leds,
+        }
+    }
+}
+
+/// Zone of a [`VirtualController`].
+#[derive(Debug, Clone)]
+pub struct VirtualZone {
+    name: String,
+    zone_type: ZoneType,
+    leds_min: u32,
+    leds_max: u32,
+    leds_count: u32,
+    matrix: Option<Array2D<u32>>,
+    segments: Vec<SegmentData>,
+    led_names: Vec<String>,
+    led_alt_names: Vec<String>,
+}
+
+impl VirtualZone {
+    fn new(name: impl Into<String>, zone_type: ZoneType, leds_count: u32) -> Self {
+        Self {
+            name: name.into(),
+            zone_type,
+            leds_min: leds_count,
+            leds_max: leds_count,
+            leds_count,
+            matrix: None,
+            segments: Vec::new(),
+            led_names: Vec::new(),
+            led_alt_names: Vec::new(),
+        }
+    }
+
+    /// Creates a zone with a single LED.
+    pub fn single(name: impl Into<String>) -> Self {
+        Self::new(name, ZoneType::Single, 1)
+    }
+
+    /// Creates a linear zone with `leds_count` LEDs.
+    pub fn linear(name: impl Into<String>, leds_count: u32) -> Self {
+        Self::new(name, ZoneType::Linear, leds_count)
+    }
+
+    /// Creates a matrix zone with an LED at every position, numbered row by row.
+    pub fn matrix(name: impl Into<String>, height: usize, width: usize) -> Self {
+        let ids = (0..(height * width) as u32).collect::<Vec<_>>();
+        let matrix = Array2D::from_row_major(&ids, height, width)
+            .expect("matrix dimensions match number of LEDs");
+        Self::new(name, ZoneType::Matrix, ids.len() as u32).with_matrix(matrix)
+    }
+
+    /// Sets the matrix of a matrix zone, where `u32::MAX` means there is no LED at that position.
+    ///
+    /// The number of LEDs is set to the number of positions with an LED.
+    pub fn with_matrix(mut self, matrix: Array2D<u32>) -> Self {
+        let leds_count = matrix
+            .elements_row_major_iter()
+            .filter(|id| **id != u32::MAX)
+            .count() as u32;
+        self.zone_type = ZoneType::Matrix;
+        self.leds_min = leds_count;
+        self.leds_max = leds_count;
+        self.leds_count = leds_count;
+        self.matrix = Some(matrix);
+        self
+    }
+
+    /// Makes this zone resizable between `min` and `max` LEDs.
+    pub fn with_size_range(mut self, min: u32, max: u32) -> Self {
+        self.leds_min = min;
+        self.leds_max = max;
+        self
+    }
+
+    /// Adds a segment of `led_count` LEDs starting at `offset`.
+    pub fn with_segment(mut self, name: impl Into<String>, offset: u32, led_count: u32) -> Self {
+        self.segments
+            .push(SegmentData::new(name, offset, led_count));
+        self
+    }
+
+    /// Sets the LED names. LEDs without a name are called `"<zone> LED <n>"`.
+    pub fn with_led_names<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.led_names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the alternate LED names, such as `"Key: Left Shift"` for an LED named `"Key: Shift"`.
+    /// LEDs without one get an empty alternate name.
+    pub fn with_led_alt_names<S: Into<String>>(
+        mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.led_alt_names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn led_names(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.leds_count as usize).map(|i| match self.led_names.get(i) {
+            Some(name) => name.clone(),
+            None => default_led_name(&self.name, i),
+        })
+    }
+
+    fn led_alt_names(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.leds_count as usize)
+            .map(|i| self.led_alt_names.get(i).cloned().unwrap_or_default())
+    }
+
+    fn build(&self, id: usize) -> ZoneData {
+        let mut segments = self.segments.clone();
+        for (id, segment) in segments.iter_mut().enumerate() {
+            segment.set_id(id);
+        }
+        ZoneData {
+            id,
+            name: self.name.clone(),
+            zone_type: self.zone_type,
+            leds_min: self.leds_min,
+            leds_max: self.leds_max,
+            leds_count: self.leds_count,
+            segments: ProtocolOption::Some(segments),
+            flags: ProtocolOption::Some(FlagSet::<ZoneFlags>::default()),
+            matrix: self.matrix.clone(),
+        }
+    }
+}
+
+/// Mode of a [`VirtualController`].
+#[derive(Debug, Clone)]
+pub struct VirtualMode {
+    mode: ModeData,
+}
+
+impl VirtualMode {
+    /// Creates a mode without any flags.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            mode: ModeData {
+                name: name.into(),
+                value: 0,
+                flags: FlagSet::default(),
+                speed_min: 0,
+                speed_max: 0,
+                speed: 0,
+                brightness_min: ProtocolOption::Some(0),
+                brightness_max: ProtocolOption::Some(0),
+                brightness: ProtocolOption::Some(0),
+                color_mode: ColorMode::None,
+                colors: Vec::new(),
+                colors_min: 0,
+                colors_max: 0,
+                direction: Direction::default(),
+                id: u32::MAX,
+            },
+        }
+    }
+
+    /// Creates a "Direct" mode with per-LED colors, which clients use to control LEDs.
+    pub fn direct() -> Self {
+        Self::new("Direct")
+            .with_flags(ModeFlag::HasPerLEDColor)
+            .with_color_mode(ColorMode::PerLED)
+    }
+
+    /// Sets the device specific value.
+    pub fn with_value(mut self, value: i32) -> Self {
+        self.mode.value = value;
+        self
+    }
+
+    /// Adds mode flags.
+    pub fn with_flags(mut self, flags: impl Into<FlagSet<ModeFlag>>) -> Self {
+        self.mode.flags |= flags.into();
+        self
+    }
+
+    /// Sets the speed range and value, and adds [`ModeFlag::HasSpeed`].
+    pub fn with_speed(mut self, min: u32, max: u32, speed: u32) -> Self {
+        self.mode.flags |= ModeFlag::HasSpeed;
+        self.mode.speed_min = min;
+        self.mode.speed_max = max;
+        self.mode.speed = speed;
+        self
+    }
+
+    /// Sets the brightness range and value, and adds [`ModeFlag::HasBrightness`].
+    pub fn with_brightness(mut self, min: u32, max: u32, brightness: u32) -> Self {
+        self.mode.flags |= ModeFlag::HasBrightness;
+        self.mode.brightness_min = ProtocolOption::Some(min);
+        self.mode.brightness_max = ProtocolOption::Some(max);
+        self.mode.brightness = ProtocolOption::Some(brightness);
+        self
+    }
+
+    /// Sets the direction, and adds the direction flag for its axis.
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.mode.flags |= match direction {
+            Direction::Left | Direction::Right => ModeFlag::HasDirectionLR,
+            Direction::Up | Direction::Down => ModeFlag::HasDirectionUD,
+            Direction::Horizontal | Direction::Vertical => ModeFlag::HasDirectionHV,
+        };
+        self.mode.direction = direction;
+        self
+    }
+
+    /// Sets the color mode.
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.mode.color_mode = color_mode;
+        self
+    }
+
+    /// Sets the mode specific colors and how many are allowed,
+    /// and adds [`ModeFlag::HasModeSpecificColor`].
+    pub fn with_colors(
+        mut self,
+        min: u32,
+        max: u32,
+        colors: impl IntoIterator<Item = Color>,
+    ) -> Self {
+        self.mode.flags |= ModeFlag::HasModeSpecificColor;
+        self.mode.color_mode = ColorMode::ModeSpecific;
+        self.mode.colors_min = min;
+        self.mode.colors_max = max;
+        self.mode.colors = colors.into_iter().collect();
+        self
+    }
+}
@@ -0,0 +1,156 @@
+//! Accepting clients and exchanging packets with them.
+
+use std::sync::Arc;
+
+use tokio::{
+    io::{AsyncWriteExt, ReadHalf, WriteHalf},
+    net::TcpListener,
+    sync::{Mutex, broadcast},
+    task::JoinSet,
+};
+
+use crate::{
+    OpenRgbResult, PacketId, ReceivedMessage, Transport, WriteMessage, read_raw_packet,
+    write_raw_packet,
+};
+
+/// Number of signals buffered for each connected client.
+pub(crate) const SIGNAL_CAPACITY: usize = 16;
+
+/// Signals sent from a server handle to every connected client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Signal {
+    DeviceListUpdated,
+    Disconnect,
+}
+
+/// Server state that answers packets, shared by all clients.
+pub(crate) trait PacketHandler: Send + Sync + 'static {
+    /// Highest protocol version the server supports.
+    fn protocol_version(&self) -> u32;
+
+    /// Handles a packet other than `RequestProtocolVersion`, and returns the reply to send, if any.
+    ///
+    /// `protocol_version` is the version negotiated with the client that sent the packet.
+    fn handle(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        body: &[u8],
+        protocol_version: u32,
+    ) -> OpenRgbResult<Option<WriteMessage>>;
+}
+
+/// Accepts clients until the task is aborted, which also stops all clients.
+pub(crate) async fn accept_loop<H: PacketHandler>(
+    listener: TcpListener,
+    handler: Arc<H>,
+    signals: broadcast::Sender<Signal>,
+) {
+    let mut clients = JoinSet::new();
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                tracing::debug!("OpenRGB server accepted client {addr}");
+                let _ = stream.set_nodelay(true);
+                clients.spawn(serve_client(stream, handler.clone(), signals.subscribe()));
+            }
+            Err(e) => tracing::warn!("OpenRGB server failed to accept client: {e}"),
+        }
+        // clean up clients that disconnected
+        while clients.try_join_next().is_some() {}
+    }
+}
+
+/// Serves a single client until it disconnects or is told to disconnect.
+pub(crate) async fn serve_client<H: PacketHandler>(
+    stream: impl Transport,
+    handler: Arc<H>,
+    mut signals: broadcast::Receiver<Signal>,
+) {
+    let (reader, writer) = tokio::io::split(stream);
+    let writer = Arc::new(Mutex::new(writer));
+
+    let mut packets = tokio::spawn(handle_packets(reader, writer.clone(), handler));
+
+    loop {
+        tokio::select! {
+            result = &mut packets => {
+                if let Ok(Err(e)) = result {
+                    tracing::debug!("OpenRGB server client disconnected: {e}");
+                }
+                return;
+            }
+            signal = signals.recv() => match signal {
+                Ok(Signal::DeviceListUpdated) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                    let mut writer = writer.lock().await;
+                    if write_raw_packet(&mut *writer, 0, 0, PacketId::DeviceListUpdated, &()).await.is_err() {
+                        packets.abort();
+                        return;
+                    }
+                }
+                Ok(Signal::Disconnect) | Err(broadcast::error::RecvError::Closed) => {
+                    packets.abort();
+                    let _ = writer.lock().await.shutdown().await;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Answers packets from a single client until it disconnects.
+async fn handle_packets<S: Transport, H: PacketHandler>(
+    mut reader: ReadHalf<S>,
+    writer: Arc<Mutex<WriteHalf<S>>>,
+    handler: Arc<H>,
+) -> OpenRgbResult<()> {
+    // clients use protocol 0 until they negotiate
+    let mut protocol_version = 0;
+    loop {
+        let (header, body) = read_raw_packet(&mut reader).await?;
+        let reply = match header.packet_id {
+            PacketId::RequestProtocolVersion => {
+                negotiate(&body, handler.protocol_version(), &mut protocol_version)
+            }
+            packet_id => handler.handle(header.device_id, packet_id, &body, protocol_version),
+        };
+        match reply {
+            Ok(Some(reply)) => {
+                let mut writer = writer.lock().await;
+                write_raw_packet(
+                    &mut *writer,
+                    protocol_version,
+                    header.device_id,
+                    header.packet_id,
+                    &reply,
+                )
+                .await?;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                "OpenRGB server could not handle {:?} packet: {e}",
+                header.packet_id
+            ),
+        }
+    }
+}
+
+/// Answers `RequestProtocolVersion`, and sets the version used with this client from then on.
+fn negotiate(
+    body: &[u8],
+    max_protocol_version: u32,
+    protocol_version: &mut u32,
+) -> OpenRgbResult<Option<WriteMessage>> {
+    let client_version = ReceivedMessage::new(body, *protocol_version).read_u32()?;
+    *protocol_version = client_version.min(max_protocol_version);
+    let mut reply = WriteMessage::new(*protocol_version);
+    reply.write_u32(max_protocol_version);
+    Ok(Some(reply))
+}
+
+/// Reads a null terminated string that is not prefixed by its length.
+pub(crate) fn raw_string(body: &[u8]) -> String {
+    let end = body.iter().position(|b| *b == 0).unwrap_or(body.len());
+    String::from_utf8_lossy(&body[..end]).into_owned()
+}
@@ -0,0 +1,551 @@
+//! `OpenRGB` SDK server, to publish devices implemented in Rust to any `OpenRGB` client.
+//!
+//! An [`OpenRgbServer`] serves [`RgbDevice`]s to clients of this crate, to `OpenRGB` itself
+//! when it is set up as an SDK client, and to any other SDK client.
+//! The server keeps the colors, active mode and zone sizes clients set,
+//! and passes every change on to the device.
+//!
+//! Profiles and plugins are not supported, clients get an empty list for both.
+//!
+//! Only available with the `server` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use openrgb2::server::{OpenRgbServer, RgbDevice, VirtualController, VirtualZone};
+//! use openrgb2::{Color, DeviceType, OpenRgbResult};
+//!
+//! struct Matrix;
+//!
+//! impl RgbDevice for Matrix {
+//!     fn controller(&self) -> VirtualController {
+//!         VirtualController::new("Matrix", DeviceType::LEDStrip)
+//!             .with_zone(VirtualZone::matrix("Matrix", 8, 32))
+//!     }
+//!
+//!     fn update_leds(&mut self, colors: &[Color]) -> OpenRgbResult<()> {
+//!         println!("first LED is now {:?}", colors[0]);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let server = OpenRgbServer::builder().with_device(Matrix).build();
+//! server.listen("0.0.0.0:6742").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod device;
+mod listen;
+
+pub use device::*;
+
+pub(crate) use listen::{
+    PacketHandler, SIGNAL_CAPACITY, Signal, accept_loop, raw_string, serve_client,
+};
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex as StdMutex};
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, ToSocketAddrs},
+    sync::broadcast,
+    task::JoinSet,
+};
+
+use crate::{
+    Color, ControllerData, ControllerUpdate, DEFAULT_PROTOCOL, ModeData, OpenRgbError,
+    OpenRgbPacket, OpenRgbResult, PacketId, PluginData, ReceivedMessage, WriteMessage,
+};
+
+/// Builder for an [`OpenRgbServer`].
+pub struct OpenRgbServerBuilder {
+    protocol_version: u32,
+    devices: Vec<Box<dyn RgbDevice>>,
+}
+
+impl Default for OpenRgbServerBuilder {
+    fn default() -> Self {
+        Self {
+            protocol_version: DEFAULT_PROTOCOL,
+            devices: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for OpenRgbServerBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenRgbServerBuilder")
+            .field("protocol_version", &self.protocol_version)
+            .field("devices", &self.devices.len())
+            .finish()
+    }
+}
+
+impl OpenRgbServerBuilder {
+    /// Sets the highest protocol version the server supports, [`DEFAULT_PROTOCOL`] by default.
+    pub fn with_protocol_version(mut self, protocol_version: u32) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// Adds a device, controller ids are given in the order devices are added.
+    pub fn with_device(mut self, device: impl RgbDevice) -> Self {
+        self.devices.push(Box::new(device));
+        self
+    }
+
+    /// Creates the server. It serves no clients until [`OpenRgbServer::listen`]
+    /// or [`OpenRgbServer::serve_stream`] is called.
+    pub fn build(self) -> OpenRgbServer {
+        let (signals, _) = broadcast::channel(SIGNAL_CAPACITY);
+        let devices = self.devices.into_iter().map(ServedDevice::new).collect();
+        OpenRgbServer {
+            state: Arc::new(StdMutex::new(ServerState {
+                protocol_version: self.protocol_version,
+                devices,
+                signals: signals.clone(),
+            })),
+            signals,
+            tasks: StdMutex::new(JoinSet::new()),
+        }
+    }
+}
+
+/// `OpenRGB` SDK server publishing [`RgbDevice`]s. See the [module documentation](self).
+///
+/// The server stops listening and disconnects all clients when this handle is dropped.
+pub struct OpenRgbServer {
+    state: Arc<StdMutex<ServerState>>,
+    signals: broadcast::Sender<Signal>,
+    tasks: StdMutex<JoinSet<()>>,
+}
+
+impl std::fmt::Debug for OpenRgbServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenRgbServer")
+            .field("devices", &self.device_count())
+            .finish()
+    }
+}
+
+impl OpenRgbServer {
+    /// Returns a builder to configure the server before starting it.
+    pub fn builder() -> OpenRgbServerBuilder {
+        OpenRgbServerBuilder::default()
+    }
+
+    /// Starts accepting clients on `addr`, and returns the address the server is listening on.
+    ///
+    /// Can be called several times to listen on multiple addresses.
+    /// Use port 0 to listen on a free port.
+    pub async fn listen(&self, addr: impl ToSocketAddrs) -> OpenRgbResult<SocketAddr> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        self.tasks().spawn(accept_loop(
+            listener,
+            self.state.clone(),
+            self.signals.clone(),
+        ));
+        tracing::debug!("OpenRGB server listening on {addr}");
+        Ok(addr)
+    }
+
+    /// Serves a single client connected over `stream`, such as a Unix socket or a pipe.
+    pub fn serve_stream(&self, stream: impl AsyncRead + AsyncWrite + Unpin + Send + 'static) {
+        self.tasks().spawn(serve_client(
+            stream,
+            self.state.clone(),
+            self.signals.subscribe(),
+        ));
+    }
+
+    /// Returns the number of devices.
+    pub fn device_count(&self) -> usize {
+        self.state().devices.len()
+    }
+
+    /// Returns the colors clients last set on a device.
+    pub fn colors(&self, controller_id: usize) -> Option<Vec<Color>> {
+        let state = self.state();
+        state
+            .devices
+            .get(controller_id)
+            .map(|d| d.data.colors.clone())
+    }
+
+    /// Returns the active mode of a device.
+    pub fn active_mode(&self, controller_id: usize) -> Option<ModeData> {
+        let state = self.state();
+        state
+            .devices
+            .get(controller_id)
+            .and_then(|d| d.data.active_mode())
+            .cloned()
+    }
+
+    /// Adds a device, tells clients the device list was updated, and returns its controller id.
+    pub fn add_device(&self, device: impl RgbDevice) -> usize {
+        let controller_id = {
+            let mut state = self.state();
+            state.devices.push(ServedDevice::new(Box::new(device)));
+            state.devices.len() - 1
+        };
+        self.notify_device_list_updated();
+        controller_id
+    }
+
+    /// Removes a device and tells clients the device list was updated.
+    ///
+    /// Devices after it shift down by one id, like they do in `OpenRGB`.
+    pub fn remove_device(&self, controller_id: usize) -> bool {
+        let removed = {
+            let mut state = self.state();
+            (controller_id < state.devices.len())
+                .then(|| state.devices.remove(controller_id))
+                .is_some()
+        };
+        if removed {
+            self.notify_device_list_updated();
+        }
+        removed
+    }
+
+    /// Sends a `DeviceListUpdated` packet to every connected client.
+    pub fn notify_device_list_updated(&self) {
+        // only fails when no client is connected
+        let _ = self.signals.send(Signal::DeviceListUpdated);
+    }
+
+    /// Closes the connection of every connected client, the server keeps accepting new clients.
+    pub fn disconnect_clients(&self) {
+        let _ = self.signals.send(Signal::Disconnect);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ServerState> {
+        self.state.lock().expect("server state lock poisoned")
+    }
+
+    fn tasks(&self) -> std::sync::MutexGuard<'_, JoinSet<()>> {
+        let mut tasks = self.tasks.lock().expect("server tasks lock poisoned");
+        // clean up clients that disconnected
+        while tasks.try_join_next().is_some() {}
+        tasks
+    }
+}
+
+/// Device together with the state clients see.
+struct ServedDevice {
+    data: ControllerData,
+    device: Box<dyn RgbDevice>,
+}
+
+impl ServedDevice {
+    fn new(device: Box<dyn RgbDevice>) -> Self {
+        Self {
+            data: device.controller().build(),
+            device,
+        }
+    }
+
+    /// Applies `update`, and passes it on to the device.
+    fn update(&mut self, mut update: ControllerUpdate) -> OpenRgbResult<()> {
+        // only a resize can be undone, other updates are shown by the device as soon as it can
+        let previous =
+            matches!(update, ControllerUpdate::ResizeZone { .. }).then(|| self.data.clone());
+        update.apply(&mut self.data)?;
+
+        let colors = &self.data.colors;
+        let result = match &update {
+            ControllerUpdate::ResizeZone { zone_id, size } => {
+                self.device.resize_zone(*zone_id, *size)
+            }
+            ControllerUpdate::ClearSegments | ControllerUpdate::AddSegment { .. } => Ok(()),
+            ControllerUpdate::UpdateLeds { .. } => self.device.update_leds(colors),
+            ControllerUpdate::UpdateZoneLeds { zone_id, .. } => {
+                self.device.update_zone_leds(*zone_id, colors)
+            }
+            ControllerUpdate::UpdateSingleLed { led_id, .. } => {
+                self.device.update_single_led(*led_id as u32, colors)
+            }
+            ControllerUpdate::SetCustomMode => match self.data.active_mode() {
+                Some(mode) => self.device.update_mode(mode.id() as u32, mode),
+                None => Ok(()),
+            },
+            ControllerUpdate::UpdateMode { mode } => {
+                self.device.update_mode(mode.id() as u32, mode)
+            }
+            ControllerUpdate::SaveMode { mode } => self.device.save_mode(mode.id() as u32, mode),
+        };
+        if let (Err(_), Some(previous)) = (&result, previous) {
+            self.data = previous;
+        }
+        result
+    }
+}
+
+/// State of the server, shared by all clients.
+struct ServerState {
+    protocol_version: u32,
+    devices: Vec<ServedDevice>,
+    signals: broadcast::Sender<Signal>,
+}
+
+impl PacketHandler for StdMutex<ServerState> {
+    fn protocol_version(&self) -> u32 {
+        self.lock()
+            .expect("server state lock poisoned")
+            .protocol_version
+    }
+
+    fn handle(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        body: &[u8],
+        protocol_version: u32,
+    ) -> OpenRgbResult<Option<WriteMessage>> {
+        self.lock().expect("server state lock poisoned").handle(
+            device_id,
+            packet_id,
+            body,
+            protocol_version,
+        )
+    }
+}
+
+impl ServerState {
+    fn handle(
+        &mut self,
+        device_id: u32,
+        packet_id: PacketId,
+        body: &[u8],
+        protocol_version: u32,
+    ) -> OpenRgbResult<Option<WriteMessage>> {
+        let mut msg = ReceivedMessage::new(body, protocol_version);
+        if let Some(update) = ControllerUpdate::read(packet_id, &mut msg)? {
+            self.device_mut(device_id)?.update(update)?;
+            return Ok(None);
+        }
+
+        let mut reply = WriteMessage::new(protocol_version);
+        match packet_id {
+            PacketId::RequestControllerCount => {
+                reply.write_u32(self.devices.len() as u32);
+            }
+            PacketId::RequestControllerData => {
+                // clients older than protocol 1 do not send their version
+                let requested = msg.read_u32().unwrap_or(protocol_version);
+                let mut reply = WriteMessage::new(requested.min(self.protocol_version));
+                reply.write_value(&self.device(device_id)?.data)?;
+                return Ok(Some(reply));
+            }
+            PacketId::SetClientName => {
+                tracing::debug!("OpenRGB server client is called {:?}", raw_string(body));
+                return Ok(None);
+            }
+            PacketId::RequestDeviceRescan => {
+                // devices are added by the application, but clients still expect to hear back
+                let _ = self.signals.send(Signal::DeviceListUpdated);
+                return Ok(None);
+            }
+            PacketId::RequestProfileList => {
+                reply.write_value(&OpenRgbPacket::new(&Vec::<String>::new()))?;
+            }
+            PacketId::RequestPluginList => {
+                reply.write_value(&OpenRgbPacket::new(&Vec::<PluginData>::new()))?;
+            }
+            PacketId::RequestLoadProfile
+            | PacketId::RequestSaveProfile
+            | PacketId::RequestDeleteProfile => {
+                return Err(OpenRgbError::CommandError(
+                    "Profiles are not supported".to_owned(),
+                ));
+            }
+            PacketId::PluginSpecific => {
+                return Err(OpenRgbError::CommandError(format!(
+                    "Plugin {device_id} not found"
+                )));
+            }
+            _ => return Ok(None),
+        }
+        Ok(Some(reply))
+    }
+
+    fn device(&self, controller_id: u32) -> OpenRgbResult<&ServedDevice> {
+        self.devices
+            .get(controller_id as usize)
+            .ok_or_else(|| controller_not_found(controller_id))
+    }
+
+    fn device_mut(&mut self, controller_id: u32) -> OpenRgbResult<&mut ServedDevice> {
+        self.devices
+            .get_mut(controller_id as usize)
+            .ok_or_else(|| controller_not_found(controller_id))
+    }
+}
+
+pub(crate) fn controller_not_found(controller_id: u32) -> OpenRgbError {
+    OpenRgbError::CommandError(format!("Controller {controller_id} not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::sync::mpsc;
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{DeviceType, OpenRgbClient, ServerEvent, ZoneType};
+
+    /// Calls made to a [`TestDevice`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Call {
+        Leds(Vec<Color>),
+        Mode(u32, String),
+        Resize(u32, u32),
+    }
+
+    /// Strip with a matrix and a resizable zone, that rejects resizing to more than 10 LEDs.
+    struct TestDevice {
+        calls: mpsc::UnboundedSender<Call>,
+    }
+
+    impl TestDevice {
+        fn new() -> (Self, mpsc::UnboundedReceiver<Call>) {
+            let (calls, rx) = mpsc::unbounded_channel();
+            (Self { calls }, rx)
+        }
+    }
+
+    impl RgbDevice for TestDevice {
+        fn controller(&self) -> VirtualController {
+            VirtualController::new("Virtual", DeviceType::LEDStrip)
+                .with_zone(VirtualZone::matrix("Matrix", 2, 2))
+                .with_zone(VirtualZone::linear("Strip", 2).with_size_range(0, 20))
+                .with_mode(VirtualMode::direct())
+                .with_mode(VirtualMode::new("Rainbow").with_speed(0, 10, 5))
+        }
+
+        fn update_leds(&mut self, colors: &[Color]) -> OpenRgbResult<()> {
+            let _ = self.calls.send(Call::Leds(colors.to_vec()));
+            Ok(())
+        }
+
+        fn update_mode(&mut self, mode_id: u32, mode: &ModeData) -> OpenRgbResult<()> {
+            let _ = self.calls.send(Call::Mode(mode_id, mode.name().to_owned()));
+            Ok(())
+        }
+
+        fn resize_zone(&mut self, zone_id: u32, size: u32) -> OpenRgbResult<()> {
+            let _ = self.calls.send(Call::Resize(zone_id, size));
+            match size > 10 {
+                true => Err(OpenRgbError::CommandError("Strip too long".to_owned())),
+                false => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_serve_device() -> OpenRgbResult<()> {
+        let (device, mut calls) = TestDevice::new();
+        let server = OpenRgbServer::builder().with_device(device).build();
+        let addr = server.listen("127.0.0.1:0").await?;
+        let client = OpenRgbClient::connect_to(addr, DEFAULT_PROTOCOL).await?;
+
+        let controller = client.get_controller(0).await?;
+        assert_eq!(controller.name(), "Virtual");
+        assert_eq!(controller.num_leds(), 6);
+        assert_eq!(controller.zones()[0].zone_type(), ZoneType::Matrix);
+
+        let red = Color::new(255, 0, 0);
+        controller.set_all_leds(red).await?;
+        assert_eq!(calls.recv().await, Some(Call::Leds(vec![red; 6])));
+        controller.set_led(5, Color::default()).await?;
+        let mut expected = vec![red; 6];
+        expected[5] = Color::default();
+        assert_eq!(calls.recv().await, Some(Call::Leds(expected.clone())));
+        assert_eq!(server.colors(0), Some(expected));
+
+        controller.set_mode(&controller.modes()[1]).await?;
+        assert_eq!(
+            calls.recv().await,
+            Some(Call::Mode(1, "Rainbow".to_owned()))
+        );
+        assert_eq!(server.active_mode(0).and_then(|m| m.speed()), Some(5));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_resize_zone() -> OpenRgbResult<()> {
+        let (device, mut calls) = TestDevice::new();
+        let server = OpenRgbServer::builder().with_device(device).build();
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        server.serve_stream(server_stream);
+        let client = OpenRgbClient::from_stream(client_stream).await?;
+
+        let mut controller = client.get_controller(0).await?;
+        controller.get_zone(1)?.resize(8).await?;
+        assert_eq!(calls.recv().await, Some(Call::Resize(1, 8)));
+        controller.get_zone(1)?.resize(12).await?;
+        assert_eq!(calls.recv().await, Some(Call::Resize(1, 12)));
+
+        controller.sync_controller_data().await?;
+        assert_eq!(controller.num_leds(), 12);
+        assert!(logs_contain("Strip too long"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_add_remove_device() -> OpenRgbResult<()> {
+        let server = OpenRgbServer::builder().build();
+        let addr = server.listen("127.0.0.1:0").await?;
+        let mut client = OpenRgbClient::connect_to(addr, DEFAULT_PROTOCOL).await?;
+        let mut events = client.events();
+        assert_eq!(client.get_controller_count().await?, 0);
+
+        assert_eq!(server.add_device(TestDevice::new().0), 0);
+        assert_eq!(
+            events.recv().await.ok(),
+            Some(ServerEvent::DeviceListUpdated)
+        );
+        assert_eq!(client.get_all_controllers().await?.controllers().len(), 1);
+        assert!(client.get_profiles().await?.is_empty());
+
+        assert!(server.remove_device(0));
+        assert!(!server.remove_device(0));
+        assert_eq!(
+            events.recv().await.ok(),
+            Some(ServerEvent::DeviceListUpdated)
+        );
+        assert_eq!(client.get_controller_count().await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_drop_client_on_oversized_packet() -> OpenRgbResult<()> {
+        let server = OpenRgbServer::builder().build();
+        let addr = server.listen("127.0.0.1:0").await?;
+        let mut stream = TcpStream::connect(addr).await?;
+        // header of a 4 GiB controller count request, without a body
+        let mut header = b"ORGB".to_vec();
+        header.extend_from_slice(&0_u32.to_le_bytes());
+        header.extend_from_slice(&0_u32.to_le_bytes());
+        header.extend_from_slice(&u32::MAX.to_le_bytes());
+        stream.write_all(&header).await?;
+
+        // the server closes the connection instead of waiting for the body
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).await?;
+        assert!(rest.is_empty());
+        Ok(())
+    }
+}
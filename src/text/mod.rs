@@ -0,0 +1,284 @@
+//! Scrolling text on matrix zones, such as status messages across a keyboard.
+//!
+//! [`ScrollingText`] draws text in a built-in bitmap [`Font`] onto a [`MatrixView`],
+//! and [`ScrollingText::play`] scrolls it across a zone with one LED update per frame.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use openrgb2::text::{Font, ScrollingText};
+//! use openrgb2::{Color, OpenRgbClient, OpenRgbResult};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> OpenRgbResult<()> {
+//! let client = OpenRgbClient::connect().await?;
+//! let keyboard = client.get_controller(0).await?;
+//! let zone = keyboard.get_zone(0)?;
+//!
+//! ScrollingText::new("Build passed")
+//!     .with_font(Font::Small)
+//!     .with_foreground(Color::new(0, 255, 0))
+//!     .with_speed(8.0)
+//!     .play(&zone)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod font;
+
+pub use font::*;
+
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::effects::tick_period;
+use crate::{Color, MatrixView, OpenRgbResult, Zone};
+
+/// Text scrolling from right to left across a matrix zone.
+///
+/// The text enters at the right edge and moves one column at a time, centered vertically.
+/// Without looping it scrolls once until it has left at the left edge.
+/// With looping it enters again after a gap, forever.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollingText {
+    text: String,
+    font: Font,
+    foreground: Color,
+    background: Color,
+    speed: f32,
+    looping: bool,
+    gap: Option<usize>,
+}
+
+impl ScrollingText {
+    /// Creates white text on black in the [`Font::Medium`] font, scrolling 10 columns per second.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            font: Font::default(),
+            foreground: Color::new(255, 255, 255),
+            background: Color::default(),
+            speed: 10.0,
+            looping: false,
+            gap: None,
+        }
+    }
+
+    /// Sets the font.
+    pub fn with_font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Sets the color of the text.
+    pub fn with_foreground(mut self, color: impl Into<Color>) -> Self {
+        self.foreground = color.into();
+        self
+    }
+
+    /// Sets the color of all other LEDs of the matrix.
+    pub fn with_background(mut self, color: impl Into<Color>) -> Self {
+        self.background = color.into();
+        self
+    }
+
+    /// Sets how many columns per second the text moves.
+    ///
+    /// With a speed of 0.0 the text stands still at the left edge.
+    /// Negative speeds and NaN are treated as 0.0, infinite speeds as [`f32::MAX`].
+    pub fn with_speed(mut self, columns_per_second: f32) -> Self {
+        self.speed = match columns_per_second.is_nan() {
+            true => 0.0,
+            false => columns_per_second.clamp(0.0, f32::MAX),
+        };
+        self
+    }
+
+    /// Sets whether the text enters again after it scrolled by.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Sets the number of empty columns between the end of the text and its next start when looping.
+    ///
+    /// By default this is the width of the matrix, so the text has left before it enters again.
+    pub fn with_gap(mut self, columns: usize) -> Self {
+        self.gap = Some(columns);
+        self
+    }
+
+    /// Returns the text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the width of the text in pixels.
+    pub fn width(&self) -> usize {
+        self.font.text_width(&self.text)
+    }
+
+    /// Fills `view` with the background and draws the text with its left edge at column `x`.
+    pub fn draw(&self, view: &mut MatrixView<'_>, x: isize) {
+        view.fill(self.background);
+        self.draw_text(view, x);
+    }
+
+    /// Draws the frame after the text scrolled `step` columns, starting just outside the right edge.
+    ///
+    /// Returns false if the text is not looping and has left the matrix before this step.
+    pub fn draw_scrolled(&self, view: &mut MatrixView<'_>, step: usize) -> bool {
+        let width = view.width() as isize;
+        let text = self.width() as isize;
+        // the first copy of the text starts at the right edge, and each copy after it one period later
+        let first = width - step as isize;
+        if !self.looping {
+            self.draw(view, first);
+            return first + text >= 0;
+        }
+        view.fill(self.background);
+        let period = (text + self.gap.map_or(width, |gap| gap as isize)).max(1);
+        let mut x = match first + text > 0 {
+            true => first,
+            false => first + ((-first - text) / period + 1) * period,
+        };
+        while x < width {
+            self.draw_text(view, x);
+            x += period;
+        }
+        true
+    }
+
+    /// Scrolls the text across the matrix of `zone`, sending one [`crate::Command`] per step.
+    ///
+    /// Steps follow the time since the start, so slow updates skip columns instead of slowing down.
+    /// Without looping this returns once the text has left the matrix, which is then filled with the background.
+    /// With looping it never returns, drop the future to stop.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the zone has no matrix or a frame cannot be sent.
+    pub async fn play(&self, zone: &Zone<'_>) -> OpenRgbResult<()> {
+        let mut view = zone.matrix_view()?;
+        if self.speed == 0.0 {
+            self.draw(&mut view, 0);
+            return view.execute().await;
+        }
+        let end = view.width() + self.width();
+        let mut interval = tokio::time::interval(tick_period(self.speed));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let start = Instant::now();
+        let mut last = None;
+        loop {
+            interval.tick().await;
+            let mut step = (start.elapsed().as_secs_f32() * self.speed) as usize;
+            if !self.looping {
+                step = step.min(end);
+            }
+            if last == Some(step) {
+                continue;
+            }
+            self.draw_scrolled(&mut view, step);
+            view.execute().await?;
+            if !self.looping && step == end {
+                return Ok(());
+            }
+            last = Some(step);
+        }
+    }
+
+    /// Draws the glyphs with the left edge of the text at column `x`, without the background.
+    fn draw_text(&self, view: &mut MatrixView<'_>, x: isize) {
+        let top = (view.height() as isize - self.font.height() as isize) / 2;
+        let glyph_width = self.font.glyph_width();
+        for (i, c) in self.text.chars().enumerate() {
+            let left = x + (i * (glyph_width + 1)) as isize;
+            if left >= view.width() as isize {
+                break;
+            }
+            if left + (glyph_width as isize) <= 0 {
+                continue;
+            }
+            for (row, bits) in self.font.glyph(c).iter().enumerate() {
+                for column in 0..glyph_width {
+                    if bits >> (glyph_width - 1 - column) & 1 == 0 {
+                        continue;
+                    }
+                    let (px, py) = (left + column as isize, top + row as isize);
+                    let (Ok(px), Ok(py)) = (usize::try_from(px), usize::try_from(py)) else {
+                        continue;
+                    };
+                    if let Some(led) = view.zone_led(px, py) {
+                        view.colors_mut()[led] = self.foreground;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockController, MockServer, MockZone, RecordedPacket};
+    use crate::{DeviceType, OpenRgbResult};
+
+    use super::*;
+
+    /// Returns the rows of the view, `#` for the text and `.` for the background.
+    fn pixels(view: &MatrixView<'_>, foreground: Color) -> Vec<String> {
+        view.rows()
+            .map(|row| {
+                row.map(|pixel| if pixel == Some(foreground) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_scrolling_text() -> OpenRgbResult<()> {
+        let server = MockServer::builder()
+            .with_controller(
+                MockController::new("Keyboard", DeviceType::Keyboard)
+                    .with_zone(MockZone::matrix("Keys", 5, 4)),
+            )
+            .start()
+            .await?;
+        let client = server.client().await?;
+        let keyboard = client.get_controller(0).await?;
+        let zone = keyboard.get_zone(0)?;
+        let mut view = zone.matrix_view()?;
+        let red = Color::new(255, 0, 0);
+
+        let text = ScrollingText::new("1t")
+            .with_font(Font::Small)
+            .with_foreground(red);
+        assert_eq!(text.width(), 7);
+        text.draw(&mut view, 0);
+        assert_eq!(pixels(&view, red), [".#..", "##..", ".#..", ".#..", "###."]);
+        assert!(text.draw_scrolled(&mut view, 2));
+        assert_eq!(pixels(&view, red)[0], "...#");
+        assert!(text.draw_scrolled(&mut view, 11));
+        assert_eq!(pixels(&view, red), ["...."; 5]);
+        assert!(!text.draw_scrolled(&mut view, 12));
+
+        // with a gap of one column the second copy follows right after the first
+        let looping = text.clone().with_looping(true).with_gap(1);
+        assert!(looping.draw_scrolled(&mut view, 10));
+        assert_eq!(pixels(&view, red)[0], "#..#");
+        assert!(looping.draw_scrolled(&mut view, 4 + 8 * 100));
+        assert_eq!(pixels(&view, red)[0], ".#..");
+
+        text.clone().with_speed(1000.0).play(&zone).await?;
+        // scrolls by at once instead of panicking
+        text.clone().with_speed(f32::INFINITY).play(&zone).await?;
+        client.get_controller(0).await?;
+        let received = server.received();
+        assert!(
+            received
+                .iter()
+                .all(|packet| matches!(packet, RecordedPacket::UpdateLeds { .. }))
+        );
+        assert_eq!(server.colors(0), Some(vec![Color::default(); 20]));
+        Ok(())
+    }
+}